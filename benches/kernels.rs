@@ -0,0 +1,72 @@
+//! Benchmarks of the kernels behind the IQR iteration: truncation assembly, the
+//! convergence scans and the vector reductions, at sizes in the hundreds.
+//!
+//! Run with `cargo bench --bench kernels --features bench`. The `naive_*` entries are the plain folds
+//! the unrolled reductions replaced, kept for comparison.
+
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use infinite_arrays::kernels::{dot, max_norm_sqr, norm_sqr};
+use infinite_arrays::iqr::{ConvergenceCriterion, InfiniteOperator};
+use num_complex::Complex64;
+
+const SIZES: [usize; 3] = [200, 400, 600];
+
+fn vector(n: usize, phase: f64) -> Vec<Complex64> {
+    (0..n).map(|i| Complex64::new((i as f64 + phase).sin(), (i as f64 * phase).cos())).collect()
+}
+
+fn operator() -> InfiniteOperator {
+    InfiniteOperator::new(|i, j| Complex64::new(1.0 / (1.0 + i.abs_diff(j) as f64), (i + j) as f64 * 1e-3))
+}
+
+fn reductions(c: &mut Criterion) {
+    let mut group = c.benchmark_group("reductions");
+    for n in SIZES {
+        let (x, y) = (vector(n, 0.5), vector(n, 1.5));
+        group.bench_with_input(BenchmarkId::new("norm_sqr", n), &x, |b, x| b.iter(|| norm_sqr(black_box(x))));
+        group.bench_with_input(BenchmarkId::new("naive_norm_sqr", n), &x, |b, x| {
+            b.iter(|| black_box(x).iter().map(|v| v.norm_sqr()).sum::<f64>())
+        });
+        group.bench_with_input(BenchmarkId::new("max_norm_sqr", n), &x, |b, x| b.iter(|| max_norm_sqr(black_box(x))));
+        group.bench_with_input(BenchmarkId::new("naive_max_norm_sqr", n), &x, |b, x| {
+            b.iter(|| black_box(x).iter().map(|v| v.norm_sqr()).fold(0.0, f64::max))
+        });
+        group.bench_with_input(BenchmarkId::new("dot", n), &(&x, &y), |b, (x, y)| {
+            b.iter(|| dot(black_box(x), black_box(y)))
+        });
+        group.bench_with_input(BenchmarkId::new("naive_dot", n), &(&x, &y), |b, (x, y)| {
+            b.iter(|| black_box(x).iter().zip(black_box(y).iter()).map(|(a, b)| a.conj() * b).sum::<Complex64>())
+        });
+    }
+    group.finish();
+}
+
+fn truncation(c: &mut Criterion) {
+    let mut group = c.benchmark_group("truncation");
+    group.sample_size(20);
+    for n in SIZES {
+        group.bench_function(BenchmarkId::new("cold", n), |b| {
+            b.iter_batched(operator, |operator| operator.get_truncation(n), BatchSize::SmallInput)
+        });
+        let warm = operator();
+        warm.get_truncation(n);
+        group.bench_function(BenchmarkId::new("warm", n), |b| b.iter(|| warm.get_truncation(n)));
+    }
+    group.finish();
+}
+
+fn convergence_scans(c: &mut Criterion) {
+    let mut group = c.benchmark_group("convergence_scans");
+    for n in SIZES {
+        let a = operator().get_truncation(n);
+        for (name, criterion) in
+            [("subdiagonal", ConvergenceCriterion::Subdiagonal), ("max_off_diagonal", ConvergenceCriterion::MaxOffDiagonal)]
+        {
+            group.bench_function(BenchmarkId::new(name, n), |b| b.iter(|| criterion.measure(black_box(&a), &[])));
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, reductions, truncation, convergence_scans);
+criterion_main!(benches);
@@ -28,7 +28,7 @@ fn main() {
     println!("{}", "-".repeat(60));
     let result = BroadcastArray::new(
         |i| (-((i + 1) as f64)).exp() + 2.0,
-        arrays::Shape::OneD(Some(INFINITY)),
+        arrays::Shape::OneD(Size::Infinite),
     );
     println!("First few values of exp(-i) + 2:");
     for i in 0..10 {
@@ -41,14 +41,14 @@ fn main() {
     let x_clone1 = x.clone();
     let y = BroadcastArray::new(
         move |i| x_clone1.get(i) + 2.0,
-        arrays::Shape::OneD(Some(INFINITY)),
+        arrays::Shape::OneD(Size::Infinite),
     );
     println!("y[0] = {}", y.get(0));
 
     let x_clone2 = x.clone();
     let z = BroadcastArray::new(
         move |i| x_clone2.get(i) * 3.0,
-        arrays::Shape::OneD(Some(INFINITY)),
+        arrays::Shape::OneD(Size::Infinite),
     );
     println!("z[0] = {}", z.get(0));
 
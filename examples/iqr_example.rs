@@ -1,9 +1,10 @@
 //! Example usage of the Infinite-dimensional QR (IQR) algorithm.
 
 use infinite_arrays::iqr::*;
+use infinite_arrays::InfiniteArraysError;
 use num_complex::Complex64;
 
-fn main() {
+fn main() -> Result<(), InfiniteArraysError> {
     println!("{}", "=".repeat(70));
     println!("Infinite-dimensional QR Algorithm Examples");
     println!("{}", "=".repeat(70));
@@ -12,7 +13,7 @@ fn main() {
     println!("\n1. Diagonal Operator (eigenvalues = 1, 2, 3, ...)");
     println!("{}", "-".repeat(70));
     let diag_op = create_diagonal_operator(|i| Complex64::new((i + 1) as f64, 0.0));
-    let result = iqr_algorithm(&diag_op, 20, 500, 1e-12, None, false);
+    let result = iqr_algorithm(&diag_op, 20, 500, 1e-12, None, false)?;
     println!("Computed {} eigenvalues", result.eigenvalues.len());
     println!("Iterations: {}, Converged: {}", result.iterations, result.converged);
     println!("First 10 eigenvalues:");
@@ -28,7 +29,7 @@ fn main() {
         Some(|_| Complex64::new(-1.0, 0.0)),
         Some(|_| Complex64::new(-1.0, 0.0)),
     );
-    let result = iqr_algorithm(&tridiag_op, 50, 1000, 1e-10, None, false);
+    let result = iqr_algorithm(&tridiag_op, 50, 1000, 1e-10, None, false)?;
     println!("Computed {} eigenvalues", result.eigenvalues.len());
     println!("Iterations: {}, Converged: {}", result.iterations, result.converged);
     println!("First 10 eigenvalues:");
@@ -42,7 +43,7 @@ fn main() {
     let custom_op = InfiniteOperator::new(|i, j| {
         Complex64::new(1.0 / (1.0 + (i as f64 - j as f64).abs()), 0.0)
     });
-    let result = iqr_algorithm(&custom_op, 30, 500, 1e-10, None, false);
+    let result = iqr_algorithm(&custom_op, 30, 500, 1e-10, None, false)?;
     println!("Computed {} eigenvalues", result.eigenvalues.len());
     println!("Iterations: {}, Converged: {}", result.iterations, result.converged);
     println!("First 10 eigenvalues (sorted by magnitude):");
@@ -54,16 +55,22 @@ fn main() {
     println!("\n4. Adaptive Spectrum Computation (multiple truncation sizes)");
     println!("{}", "-".repeat(70));
     let n_range = vec![20, 50, 100];
-    let spectrum_result = iqr_spectrum(&diag_op, &n_range, 500, 1e-10);
+    let spectrum_result = iqr_spectrum(&diag_op, &n_range, 500, 1e-10)?;
     println!("Recommended truncation size: {}", spectrum_result.recommended_n);
-    println!("Converged: {}", spectrum_result.converged);
     println!("Eigenvalues from largest truncation:");
-    for (i, ev) in spectrum_result.eigenvalues.iter().take(10).enumerate() {
-        println!("  λ_{} = {:.10}", i + 1, ev.re);
+    for (i, (ev, status)) in spectrum_result
+        .eigenvalues
+        .iter()
+        .zip(&spectrum_result.classifications)
+        .take(10)
+        .enumerate()
+    {
+        println!("  λ_{} = {:.10} ({:?})", i + 1, ev.re, status);
     }
 
     println!("\n{}", "=".repeat(70));
     println!("Examples completed!");
     println!("{}", "=".repeat(70));
+    Ok(())
 }
 
@@ -0,0 +1,19 @@
+// Cargo runner for wasm32-unknown-unknown test binaries, see .cargo/config.toml.
+//
+// The binaries import nothing: std on this target has no stdout, so the libtest
+// report is lost, and panics abort. The runner therefore calls `main` and reports
+// the outcome itself; a failing test traps, which Node turns into a nonzero exit
+// with the panicking function in the stack trace. Test name filters are ignored.
+
+import fs from "node:fs";
+import path from "node:path";
+
+const file = process.argv[2];
+const wasm = new WebAssembly.Module(fs.readFileSync(file));
+const instance = new WebAssembly.Instance(wasm, {});
+const status = instance.exports.main(0, 0);
+if (status !== 0) {
+    console.error(`${path.basename(file)}: main returned ${status}`);
+    process.exit(1);
+}
+console.log(`${path.basename(file)}: all tests passed`);
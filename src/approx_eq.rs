@@ -0,0 +1,158 @@
+//! `approx` comparisons of infinite arrays and eigenvalue lists, available with the
+//! `approx` feature.
+//!
+//! An infinite array cannot be compared in full, so [`Terms`] stands for its first n
+//! entries: `assert_relative_eq!(a.terms(50), b.terms(50), max_relative = 1e-12)`.
+//! Prefixes of different lengths are never equal. With `std`, an [`IqrResult`] is
+//! compared by its eigenvalue list.
+//!
+//! [`IqrResult`]: crate::iqr::IqrResult
+
+use crate::arrays::InfiniteArray;
+use ::approx::{AbsDiffEq, RelativeEq};
+use core::fmt;
+
+/// The first `n` entries of an infinite array, see [`InfiniteArray::terms`]
+#[derive(Clone, Copy)]
+pub struct Terms<'a> {
+    array: &'a dyn InfiniteArray,
+    n: usize,
+}
+
+impl<'a> Terms<'a> {
+    pub fn new(array: &'a dyn InfiniteArray, n: usize) -> Self {
+        Terms { array, n }
+    }
+
+    /// Whether both prefixes have the same length and `eq` holds entrywise
+    fn all(&self, other: &Terms<'_>, eq: impl Fn(f64, f64) -> bool) -> bool {
+        self.n == other.n && (0..self.n).all(|k| eq(self.array.get(k), other.array.get(k)))
+    }
+}
+
+impl PartialEq for Terms<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.all(other, |a, b| a == b)
+    }
+}
+
+/// Lists the entries, so failed assertions show both prefixes
+impl fmt::Debug for Terms<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries((0..self.n).map(|k| self.array.get(k))).finish()
+    }
+}
+
+impl AbsDiffEq for Terms<'_> {
+    type Epsilon = f64;
+
+    fn default_epsilon() -> f64 {
+        f64::default_epsilon()
+    }
+
+    fn abs_diff_eq(&self, other: &Self, epsilon: f64) -> bool {
+        self.all(other, |a, b| a.abs_diff_eq(&b, epsilon))
+    }
+}
+
+impl RelativeEq for Terms<'_> {
+    fn default_max_relative() -> f64 {
+        f64::default_max_relative()
+    }
+
+    fn relative_eq(&self, other: &Self, epsilon: f64, max_relative: f64) -> bool {
+        self.all(other, |a, b| a.relative_eq(&b, epsilon, max_relative))
+    }
+}
+
+#[cfg(feature = "std")]
+mod eigenvalues {
+    use crate::iqr::IqrResult;
+    use ::approx::{AbsDiffEq, RelativeEq};
+    use num_complex::Complex64;
+
+    /// Whether the eigenvalue lists have the same length and `eq` holds for the real
+    /// and imaginary parts of each pair
+    fn all(a: &IqrResult, b: &IqrResult, eq: impl Fn(f64, f64) -> bool) -> bool {
+        let close = |x: &Complex64, y: &Complex64| eq(x.re, y.re) && eq(x.im, y.im);
+        a.eigenvalues.len() == b.eigenvalues.len() && a.eigenvalues.iter().zip(&b.eigenvalues).all(|(x, y)| close(x, y))
+    }
+
+    /// Compares the eigenvalue lists in order; iteration counts, eigenvectors and
+    /// diagnostics are ignored
+    impl AbsDiffEq for IqrResult {
+        type Epsilon = f64;
+
+        fn default_epsilon() -> f64 {
+            f64::default_epsilon()
+        }
+
+        fn abs_diff_eq(&self, other: &Self, epsilon: f64) -> bool {
+            all(self, other, |a, b| a.abs_diff_eq(&b, epsilon))
+        }
+    }
+
+    impl RelativeEq for IqrResult {
+        fn default_max_relative() -> f64 {
+            f64::default_max_relative()
+        }
+
+        fn relative_eq(&self, other: &Self, epsilon: f64, max_relative: f64) -> bool {
+            all(self, other, |a, b| a.relative_eq(&b, epsilon, max_relative))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arrays::{Cyclic, Fill, Vcat};
+    use ::approx::{assert_abs_diff_eq, assert_abs_diff_ne, assert_relative_eq};
+    use alloc::sync::Arc;
+    use alloc::{format, vec};
+
+    #[test]
+    fn prefixes_compare_entrywise_and_by_length() {
+        let cyclic = Cyclic::new(vec![1.0, 2.0]);
+        let vcat = Vcat::new(vec![1.0, 2.0, 1.0], Arc::new(Fill::new(2.0, None)));
+        assert_eq!(cyclic.terms(4), vcat.terms(4));
+        assert_ne!(cyclic.terms(5), vcat.terms(5));
+        assert_ne!(cyclic.terms(3), vcat.terms(4));
+        assert_eq!(format!("{:?}", cyclic.terms(3)), "[1.0, 2.0, 1.0]");
+    }
+
+    #[test]
+    fn prefixes_compare_within_tolerances() {
+        let a = Fill::new(1.0, None);
+        let b = Fill::new(1.0 + 1e-10, None);
+        assert_abs_diff_eq!(a.terms(10), b.terms(10), epsilon = 1e-9);
+        assert_abs_diff_ne!(a.terms(10), b.terms(10), epsilon = 1e-11);
+        assert_relative_eq!(a.terms(10), b.terms(10), max_relative = 1e-9);
+        assert_abs_diff_ne!(a.terms(10), b.terms(11), epsilon = 1.0);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn iqr_results_compare_by_their_eigenvalues() {
+        use crate::iqr::IqrResult;
+        use num_complex::Complex64;
+
+        let result = |eigenvalues: Vec<Complex64>, iterations| IqrResult {
+            eigenvalues,
+            eigenvectors: None,
+            iterations,
+            converged: true,
+            residual: None,
+            warning: None,
+            interrupted: false,
+            error_bounds: None,
+        };
+        let a = result(vec![Complex64::new(2.0, 1.0), Complex64::new(1.0, 0.0)], 10);
+        let b = result(vec![Complex64::new(2.0, 1.0 + 1e-12), Complex64::new(1.0, 0.0)], 20);
+        assert_abs_diff_eq!(a, b, epsilon = 1e-10);
+        assert_abs_diff_ne!(a, b, epsilon = 1e-14);
+        assert_relative_eq!(a, b, max_relative = 1e-10);
+        let shorter = result(vec![Complex64::new(2.0, 1.0)], 10);
+        assert_abs_diff_ne!(a, shorter, epsilon = 1.0);
+    }
+}
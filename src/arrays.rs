@@ -1,15 +1,34 @@
 //! Infinite array types.
 
-use std::fmt;
-use crate::infinity::Infinity;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::any::type_name;
+use core::fmt;
+use core::marker::PhantomData;
+use alloc::sync::Arc;
+use crate::display;
+use crate::dims::{Dim, Dims, Finite, Inf};
+use crate::infinity::Size;
+use crate::ranges::{InfStepRange, OneToInf};
+use num_traits::{One, Zero};
+#[cfg(feature = "approx")]
+use crate::approx_eq::Terms;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
 /// Base trait for infinite arrays
-pub trait InfiniteArray: Send + Sync {
+///
+/// Entries are of the scalar type `T`, `f64` unless stated otherwise; the arrays of
+/// this module, [`crate::BroadcastArray`], [`crate::CachedArray`] and
+/// [`crate::InfiniteDiagonal`] hold any `Copy` scalar such as `f32` or `Complex64`.
+pub trait InfiniteArray<T = f64>: Send + Sync {
     /// Get item at index (0-based)
-    fn get(&self, index: usize) -> f64;
+    fn get(&self, index: usize) -> T;
     
     /// Get item at multi-dimensional index
-    fn get_multi(&self, indices: &[usize]) -> f64 {
+    fn get_multi(&self, indices: &[usize]) -> T {
         if indices.len() == 1 {
             self.get(indices[0])
         } else {
@@ -20,58 +39,212 @@ pub trait InfiniteArray: Send + Sync {
     /// Get the shape of the array
     fn shape(&self) -> Shape;
     
+    /// Index range of each dimension, e.g. `[Infinite(OneToInf)]` for a vector
+    fn axes(&self) -> Vec<ArrayAxis> {
+        self.shape().axes()
+    }
+    
+    /// Get item at 1-based (Julia-style) indices, one per axis
+    ///
+    /// The indices are converted to 0-based positions by [`ArrayAxis::position`].
+    fn get_at(&self, indices: &[usize]) -> T {
+        let axes = self.axes();
+        if indices.len() != axes.len() {
+            panic!("expected {} indices, got {}", axes.len(), indices.len());
+        }
+        let positions: Vec<usize> = axes.iter().zip(indices).map(|(axis, &index)| axis.position(index)).collect();
+        self.get_multi(&positions)
+    }
+    
     /// Get the dtype (represented as a string for simplicity)
     fn dtype(&self) -> &'static str {
-        "f64"
+        type_name::<T>()
+    }
+    
+    /// Lazy view whose entry k is `self.get(range.get(k))`
+    ///
+    /// Gives strided and offset views, e.g. `a.index_with(InfStepRange::new(0, 2))`
+    /// for the even-indexed entries. See [`IndexedArray::new`] for shared arrays.
+    fn index_with(self, range: impl Into<InfStepRange>) -> IndexedArray<T>
+    where
+        Self: Sized + 'static,
+    {
+        IndexedArray::new(Arc::new(self), range)
+    }
+    
+    /// Entries 0..n, evaluated in parallel
+    ///
+    /// For pure arrays, whose entries are independent; cached arrays override it to
+    /// fill their cache in one pass.
+    #[cfg(feature = "rayon")]
+    fn par_take_finite(&self, n: usize) -> Vec<T>
+    where
+        T: Send,
+    {
+        (0..n).into_par_iter().map(|i| self.get(i)).collect()
+    }
+    
+    /// The first n entries, compared by the `approx` traits
+    #[cfg(feature = "approx")]
+    fn terms(&self, n: usize) -> Terms<'_>
+    where
+        Self: Sized + InfiniteArray<f64>,
+    {
+        Terms::new(self, n)
+    }
+    
+    /// The array as an ∞×1 column, panicking unless it is an infinite vector
+    fn into_column(self) -> Dims<Self, Inf, Finite<1>>
+    where
+        Self: Sized,
+    {
+        match self.shape() {
+            Shape::OneD(Size::Infinite) => Dims::new(self),
+            shape => panic!("an infinite column needs a one-dimensional infinite array, got shape {}", shape),
+        }
+    }
+}
+
+impl<T, A: InfiniteArray<T>, R: Dim, C: Dim> InfiniteArray<T> for Dims<A, R, C> {
+    fn get(&self, index: usize) -> T {
+        (**self).get(index)
+    }
+    
+    fn get_multi(&self, indices: &[usize]) -> T {
+        (**self).get_multi(indices)
+    }
+    
+    fn shape(&self) -> Shape {
+        (**self).shape()
+    }
+    
+    fn dtype(&self) -> &'static str {
+        (**self).dtype()
     }
 }
 
-/// Shape representation (can contain Infinity)
-#[derive(Debug, Clone)]
+/// Shape of an array: the [`Size`] of each dimension, finite or infinite
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Shape {
     Scalar,
-    OneD(Option<Infinity>),
-    MultiD(Vec<Option<Infinity>>),
+    OneD(Size),
+    MultiD(Vec<Size>),
 }
 
+impl Shape {
+    /// Size of each dimension (none for a scalar)
+    pub fn dims(&self) -> &[Size] {
+        match self {
+            Shape::Scalar => &[],
+            Shape::OneD(size) => core::slice::from_ref(size),
+            Shape::MultiD(sizes) => sizes,
+        }
+    }
+    
+    /// Number of dimensions
+    pub fn ndim(&self) -> usize {
+        self.dims().len()
+    }
+    
+    /// Size of dimension `dim`, panicking if there is no such dimension
+    pub fn len(&self, dim: usize) -> Size {
+        match self.dims().get(dim) {
+            Some(&size) => size,
+            None => panic!("dimension {} out of range for a {}-dimensional shape", dim, self.ndim()),
+        }
+    }
+    
+    /// Whether dimension `dim` is infinite, panicking if there is no such dimension
+    pub fn is_infinite(&self, dim: usize) -> bool {
+        self.len(dim).is_infinite()
+    }
+    
+    /// Index range of each dimension (none for a scalar)
+    pub fn axes(&self) -> Vec<ArrayAxis> {
+        self.dims()
+            .iter()
+            .map(|size| match size {
+                Size::Infinite => ArrayAxis::Infinite(OneToInf),
+                Size::Finite(n) => ArrayAxis::Finite(*n),
+            })
+            .collect()
+    }
+}
+
+/// The axes in Julia notation, e.g. `(1:∞)` for a vector and `(1:∞, 1:∞)` for a matrix
 impl fmt::Display for Shape {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let dim_strs: Vec<String> = self.axes().iter().map(|axis| axis.to_string()).collect();
+        write!(f, "({})", dim_strs.join(", "))
+    }
+}
+
+/// Index range of one dimension of an array
+///
+/// Indices along an axis are 1-based as in Julia; [`ArrayAxis::position`] is the one
+/// place where they are turned into the 0-based positions used by `get`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArrayAxis {
+    /// Infinite axis with indices 1, 2, 3, ...
+    Infinite(OneToInf),
+    /// Finite axis with indices 1, ..., n
+    Finite(usize),
+}
+
+impl ArrayAxis {
+    pub fn is_infinite(&self) -> bool {
+        matches!(self, ArrayAxis::Infinite(_))
+    }
+    
+    /// 0-based position of a 1-based index along this axis
+    pub fn position(&self, index: usize) -> usize {
+        match self {
+            ArrayAxis::Infinite(range) => range.index(index),
+            &ArrayAxis::Finite(n) if (1..=n).contains(&index) => index - 1,
+            ArrayAxis::Finite(n) => panic!("{} not in 1:{}", index, n),
+        }
+    }
+}
+
+impl fmt::Display for ArrayAxis {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Shape::Scalar => write!(f, "()"),
-            Shape::OneD(None) => write!(f, "(finite)"),
-            Shape::OneD(Some(_)) => write!(f, "(∞)"),
-            Shape::MultiD(dims) => {
-                let dim_strs: Vec<String> = dims.iter().map(|d| {
-                    match d {
-                        None => "finite".to_string(),
-                        Some(_) => "∞".to_string(),
-                    }
-                }).collect();
-                write!(f, "({})", dim_strs.join(", "))
-            }
+            ArrayAxis::Infinite(range) => write!(f, "{}", range),
+            ArrayAxis::Finite(n) => write!(f, "1:{}", n),
         }
     }
 }
 
 /// Infinite array filled with ones
-pub struct Ones {
+pub struct Ones<T = f64> {
     shape: Shape,
     dtype: &'static str,
+    scalar: PhantomData<T>,
 }
 
 impl Ones {
+    /// Ones of type `f64`, see [`Ones::with_shape`] for other scalars
     pub fn new(shape: Option<Shape>) -> Self {
-        let shape = shape.unwrap_or_else(|| Shape::OneD(Some(Infinity)));
+        Ones::with_shape(shape)
+    }
+}
+
+impl<T: One> Ones<T> {
+    /// Ones of any scalar type, e.g. `Ones::<f32>::with_shape(None)`
+    pub fn with_shape(shape: Option<Shape>) -> Self {
+        let shape = shape.unwrap_or(Shape::OneD(Size::Infinite));
         Ones {
             shape,
-            dtype: "f64",
+            dtype: type_name::<T>(),
+            scalar: PhantomData,
         }
     }
 }
 
-impl InfiniteArray for Ones {
-    fn get(&self, _index: usize) -> f64 {
-        1.0
+impl<T: One + Send + Sync> InfiniteArray<T> for Ones<T> {
+    fn get(&self, _index: usize) -> T {
+        T::one()
     }
     
     fn shape(&self) -> Shape {
@@ -83,41 +256,51 @@ impl InfiniteArray for Ones {
     }
 }
 
-impl fmt::Display for Ones {
+impl<T: One + Send + Sync + fmt::Display> fmt::Display for Ones<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "Ones{}:", self.shape())?;
         for i in 0..12 {
             write!(f, "\n  {}", self.get(i))?;
         }
-        write!(f, "\n  ⋮")
+        write!(f, "\n  {}", display::vertical_ellipsis())
     }
 }
 
-impl fmt::Debug for Ones {
+impl<T> fmt::Debug for Ones<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "Ones{}", self.shape())
+        write!(f, "Ones{}", self.shape)
     }
 }
 
 /// Infinite array filled with zeros
-pub struct Zeros {
+pub struct Zeros<T = f64> {
     shape: Shape,
     dtype: &'static str,
+    scalar: PhantomData<T>,
 }
 
 impl Zeros {
+    /// Zeros of type `f64`, see [`Zeros::with_shape`] for other scalars
     pub fn new(shape: Option<Shape>) -> Self {
-        let shape = shape.unwrap_or_else(|| Shape::OneD(Some(Infinity)));
+        Zeros::with_shape(shape)
+    }
+}
+
+impl<T: Zero> Zeros<T> {
+    /// Zeros of any scalar type, e.g. `Zeros::<f32>::with_shape(None)`
+    pub fn with_shape(shape: Option<Shape>) -> Self {
+        let shape = shape.unwrap_or(Shape::OneD(Size::Infinite));
         Zeros {
             shape,
-            dtype: "f64",
+            dtype: type_name::<T>(),
+            scalar: PhantomData,
         }
     }
 }
 
-impl InfiniteArray for Zeros {
-    fn get(&self, _index: usize) -> f64 {
-        0.0
+impl<T: Zero + Send + Sync> InfiniteArray<T> for Zeros<T> {
+    fn get(&self, _index: usize) -> T {
+        T::zero()
     }
     
     fn shape(&self) -> Shape {
@@ -129,42 +312,42 @@ impl InfiniteArray for Zeros {
     }
 }
 
-impl fmt::Display for Zeros {
+impl<T: Zero + Send + Sync + fmt::Display> fmt::Display for Zeros<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "Zeros{}:", self.shape())?;
         for i in 0..12 {
             write!(f, "\n  {}", self.get(i))?;
         }
-        write!(f, "\n  ⋮")
+        write!(f, "\n  {}", display::vertical_ellipsis())
     }
 }
 
-impl fmt::Debug for Zeros {
+impl<T> fmt::Debug for Zeros<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "Zeros{}", self.shape())
+        write!(f, "Zeros{}", self.shape)
     }
 }
 
 /// Infinite array filled with a constant value
-pub struct Fill {
-    value: f64,
+pub struct Fill<T = f64> {
+    value: T,
     shape: Shape,
     dtype: &'static str,
 }
 
-impl Fill {
-    pub fn new(value: f64, shape: Option<Shape>) -> Self {
-        let shape = shape.unwrap_or_else(|| Shape::OneD(Some(Infinity)));
+impl<T: Copy> Fill<T> {
+    pub fn new(value: T, shape: Option<Shape>) -> Self {
+        let shape = shape.unwrap_or(Shape::OneD(Size::Infinite));
         Fill {
             value,
             shape,
-            dtype: "f64",
+            dtype: type_name::<T>(),
         }
     }
 }
 
-impl InfiniteArray for Fill {
-    fn get(&self, _index: usize) -> f64 {
+impl<T: Copy + Send + Sync> InfiniteArray<T> for Fill<T> {
+    fn get(&self, _index: usize) -> T {
         self.value
     }
     
@@ -177,19 +360,354 @@ impl InfiniteArray for Fill {
     }
 }
 
-impl fmt::Display for Fill {
+impl<T: Copy + Send + Sync + fmt::Display> fmt::Display for Fill<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "Fill({}){}:", self.value, self.shape())?;
         for i in 0..12 {
             write!(f, "\n  {}", self.get(i))?;
         }
-        write!(f, "\n  ⋮")
+        write!(f, "\n  {}", display::vertical_ellipsis())
+    }
+}
+
+impl<T: fmt::Display> fmt::Debug for Fill<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Fill({}){}", self.value, self.shape)
+    }
+}
+
+
+/// Finite vector padded with zeros to an infinite array
+pub struct PaddedArray<T = f64> {
+    values: Vec<T>,
+    shape: Shape,
+    dtype: &'static str,
+}
+
+impl<T> PaddedArray<T> {
+    pub fn new(values: Vec<T>) -> Self {
+        PaddedArray {
+            values,
+            shape: Shape::OneD(Size::Infinite),
+            dtype: type_name::<T>(),
+        }
+    }
+    
+    /// The explicitly stored leading entries
+    pub fn values(&self) -> &[T] {
+        &self.values
+    }
+}
+
+impl<T: Zero + Copy + Send + Sync> InfiniteArray<T> for PaddedArray<T> {
+    fn get(&self, index: usize) -> T {
+        self.values.get(index).copied().unwrap_or_else(T::zero)
+    }
+    
+    fn shape(&self) -> Shape {
+        self.shape.clone()
+    }
+    
+    fn dtype(&self) -> &'static str {
+        self.dtype
+    }
+}
+
+impl<T: Zero + Copy + Send + Sync + fmt::Display> fmt::Display for PaddedArray<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "PaddedArray{}:", self.shape())?;
+        for i in 0..12 {
+            write!(f, "\n  {}", self.get(i))?;
+        }
+        write!(f, "\n  {}", display::vertical_ellipsis())
+    }
+}
+
+impl<T> fmt::Debug for PaddedArray<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "PaddedArray({}){}", self.values.len(), self.shape)
+    }
+}
+
+/// Finite vector followed by an infinite array, as `Vcat` in InfiniteArrays.jl
+///
+/// Entry k is `head[k]` for the first `head.len()` positions and
+/// `tail.get(k - head.len())` after them.
+pub struct Vcat<T = f64> {
+    head: Vec<T>,
+    tail: Arc<dyn InfiniteArray<T>>,
+}
+
+impl<T> Vcat<T> {
+    pub fn new(head: Vec<T>, tail: Arc<dyn InfiniteArray<T>>) -> Self {
+        Vcat { head, tail }
+    }
+    
+    /// The explicitly stored leading entries
+    pub fn head(&self) -> &[T] {
+        &self.head
+    }
+    
+    /// The array following the head
+    pub fn tail(&self) -> &Arc<dyn InfiniteArray<T>> {
+        &self.tail
+    }
+}
+
+impl<T: Copy + Send + Sync> InfiniteArray<T> for Vcat<T> {
+    fn get(&self, index: usize) -> T {
+        match self.head.get(index) {
+            Some(&value) => value,
+            None => self.tail.get(index - self.head.len()),
+        }
+    }
+    
+    fn shape(&self) -> Shape {
+        Shape::OneD(Size::Infinite)
+    }
+}
+
+impl<T: Copy + Send + Sync + fmt::Display> fmt::Display for Vcat<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Vcat{}:", self.shape())?;
+        for i in 0..12 {
+            write!(f, "\n  {}", self.get(i))?;
+        }
+        write!(f, "\n  {}", display::vertical_ellipsis())
+    }
+}
+
+impl<T: Copy + Send + Sync> fmt::Debug for Vcat<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Vcat({}){}", self.head.len(), self.shape())
+    }
+}
+
+/// Periodic array repeating a finite vector: entry k is `period[k % period.len()]`
+pub struct Cyclic<T = f64> {
+    period: Vec<T>,
+}
+
+impl<T> Cyclic<T> {
+    /// Panics if `period` is empty
+    pub fn new(period: Vec<T>) -> Self {
+        assert!(!period.is_empty(), "a cyclic array needs a non-empty period");
+        Cyclic { period }
+    }
+    
+    /// One period of the entries
+    pub fn period(&self) -> &[T] {
+        &self.period
+    }
+}
+
+impl<T: Copy + Send + Sync> InfiniteArray<T> for Cyclic<T> {
+    fn get(&self, index: usize) -> T {
+        self.period[index % self.period.len()]
+    }
+    
+    fn shape(&self) -> Shape {
+        Shape::OneD(Size::Infinite)
+    }
+}
+
+impl<T: Copy + Send + Sync + fmt::Display> fmt::Display for Cyclic<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Cyclic{}:", self.shape())?;
+        for i in 0..12 {
+            write!(f, "\n  {}", self.get(i))?;
+        }
+        write!(f, "\n  {}", display::vertical_ellipsis())
+    }
+}
+
+impl<T: Copy + Send + Sync> fmt::Debug for Cyclic<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Cyclic({}){}", self.period.len(), self.shape())
+    }
+}
+
+/// Infinite array re-indexed by a range, see [`InfiniteArray::index_with`]
+pub struct IndexedArray<T = f64> {
+    array: Arc<dyn InfiniteArray<T>>,
+    range: InfStepRange,
+}
+
+impl<T> IndexedArray<T> {
+    /// Panics if the range descends or starts below 0, as its values would leave
+    /// the positions of the array
+    pub fn new(array: Arc<dyn InfiniteArray<T>>, range: impl Into<InfStepRange>) -> Self {
+        let range = range.into();
+        if !range.is_ascending() || range.start() < 0 {
+            panic!("{} does not index an infinite array", range);
+        }
+        IndexedArray { array, range }
+    }
+    
+    /// The positions read from the underlying array
+    pub fn range(&self) -> InfStepRange {
+        self.range
+    }
+}
+
+impl<T> InfiniteArray<T> for IndexedArray<T> {
+    fn get(&self, index: usize) -> T {
+        self.array.get(self.range.get(index) as usize)
+    }
+    
+    fn shape(&self) -> Shape {
+        Shape::OneD(Size::Infinite)
+    }
+    
+    fn dtype(&self) -> &'static str {
+        self.array.dtype()
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for IndexedArray<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "IndexedArray{}:", self.shape())?;
+        for i in 0..12 {
+            write!(f, "\n  {}", self.get(i))?;
+        }
+        write!(f, "\n  {}", display::vertical_ellipsis())
     }
 }
 
-impl fmt::Debug for Fill {
+impl<T> fmt::Debug for IndexedArray<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "Fill({}){}", self.value, self.shape())
+        write!(f, "IndexedArray[{}]{}", self.range, self.shape())
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+    use num_complex::Complex64;
+
+    #[test]
+    fn index_with_reads_through_the_range() {
+        let odd = Cyclic::new(vec![0.0, 1.0, 2.0, 3.0]).index_with(InfStepRange::new(1, 2));
+        assert_eq!([odd.get(0), odd.get(1), odd.get(2)], [1.0, 3.0, 1.0]);
+        let shifted = IndexedArray::new(Arc::new(PaddedArray::new(vec![1.0, 2.0, 3.0])), OneToInf);
+        assert_eq!([shifted.get(0), shifted.get(2)], [2.0, 0.0]);
+    }
+
+    #[test]
+    fn get_at_is_one_based() {
+        let cyclic = Cyclic::new(vec![5.0, 6.0, 7.0]);
+        assert_eq!(cyclic.get_at(&[1]), cyclic.get(0));
+        assert_eq!(cyclic.get_at(&[3]), cyclic.get(2));
+        assert_eq!(Ones::new(Some(Shape::OneD(Size::Finite(3)))).get_at(&[3]), 1.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "4 not in 1:3")]
+    fn get_at_rejects_an_index_past_a_finite_axis() {
+        Ones::new(Some(Shape::OneD(Size::Finite(3)))).get_at(&[4]);
+    }
+
+    #[test]
+    #[should_panic(expected = "expected 1 indices, got 2")]
+    fn get_at_rejects_the_wrong_number_of_indices() {
+        Ones::new(None).get_at(&[1, 1]);
+    }
+
+    #[test]
+    fn axes_follow_the_shape() {
+        assert_eq!(Ones::new(None).axes(), [ArrayAxis::Infinite(OneToInf)]);
+        assert_eq!(Ones::new(Some(Shape::OneD(Size::Finite(3)))).axes(), [ArrayAxis::Finite(3)]);
+        let matrix = Zeros::new(Some(Shape::MultiD(vec![Size::Infinite, Size::Finite(2)])));
+        assert_eq!(matrix.axes(), [ArrayAxis::Infinite(OneToInf), ArrayAxis::Finite(2)]);
+    }
+
+    #[test]
+    fn shape_queries_cover_every_kind_of_shape() {
+        assert_eq!(Shape::Scalar.ndim(), 0);
+        assert!(Shape::Scalar.axes().is_empty());
+
+        let finite = Shape::OneD(Size::Finite(4));
+        assert_eq!(finite.ndim(), 1);
+        assert_eq!(finite.len(0), Size::Finite(4));
+        assert!(!finite.is_infinite(0));
+        assert_eq!(finite.axes(), [ArrayAxis::Finite(4)]);
+
+        let infinite = Shape::OneD(Size::Infinite);
+        assert_eq!(infinite.ndim(), 1);
+        assert_eq!(infinite.len(0), Size::Infinite);
+        assert!(infinite.is_infinite(0));
+        assert_eq!(infinite.axes(), [ArrayAxis::Infinite(OneToInf)]);
+
+        let matrix = Shape::MultiD(vec![Size::Finite(2), Size::Infinite, Size::Finite(3)]);
+        assert_eq!(matrix.ndim(), 3);
+        assert_eq!([matrix.len(0), matrix.len(1), matrix.len(2)], [Size::Finite(2), Size::Infinite, Size::Finite(3)]);
+        assert_eq!([matrix.is_infinite(0), matrix.is_infinite(1), matrix.is_infinite(2)], [false, true, false]);
+        assert_eq!(matrix.axes(), [ArrayAxis::Finite(2), ArrayAxis::Infinite(OneToInf), ArrayAxis::Finite(3)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "dimension 1 out of range for a 1-dimensional shape")]
+    fn shape_len_rejects_a_missing_dimension() {
+        Shape::OneD(Size::Infinite).len(1);
+    }
+
+    #[test]
+    #[should_panic(expected = "dimension 0 out of range for a 0-dimensional shape")]
+    fn scalar_shape_has_no_dimensions() {
+        Shape::Scalar.is_infinite(0);
+    }
+
+    #[test]
+    #[cfg(feature = "rayon")]
+    fn par_take_finite_matches_the_serial_entries() {
+        let arrays: [Arc<dyn InfiniteArray>; 4] = [
+            Arc::new(Cyclic::new(vec![1.0, -2.0, 3.5])),
+            Arc::new(Vcat::new(vec![4.0, 5.0], Arc::new(Fill::new(0.5, None)))),
+            Arc::new(PaddedArray::new(vec![1.0, 2.0, 3.0])),
+            Arc::new(Cyclic::new(vec![0.0, 1.0]).index_with(InfStepRange::new(1, 3))),
+        ];
+        for array in arrays {
+            let serial: Vec<f64> = (0..40).map(|i| array.get(i)).collect();
+            assert_eq!(array.par_take_finite(40), serial);
+        }
+    }
+
+    #[test]
+    fn arrays_hold_f32_entries() {
+        let ones = Ones::<f32>::with_shape(None);
+        let zeros = Zeros::<f32>::with_shape(None);
+        let fill = Fill::new(2.5f32, None);
+        assert_eq!([ones.get(7), zeros.get(7), fill.get(7)], [1.0, 0.0, 2.5]);
+        assert_eq!([ones.dtype(), fill.dtype()], ["f32", "f32"]);
+        let vcat = Vcat::new(vec![-1.0f32], Arc::new(Cyclic::new(vec![3.0f32, 4.0])));
+        assert_eq!([vcat.get(0), vcat.get(1), vcat.get(2), vcat.get(3)], [-1.0, 3.0, 4.0, 3.0]);
+    }
+
+    #[test]
+    fn arrays_hold_complex_entries() {
+        let i = Complex64::new(0.0, 1.0);
+        let ones = Ones::<Complex64>::with_shape(None);
+        let zeros = Zeros::<Complex64>::with_shape(None);
+        assert_eq!([ones.get(3), zeros.get(3)], [Complex64::new(1.0, 0.0), Complex64::new(0.0, 0.0)]);
+        assert_eq!(Fill::new(i, None).get(10), i);
+        let vcat = Vcat::new(vec![ones.get(0)], Arc::new(Cyclic::new(vec![i, -i])));
+        assert_eq!([vcat.get(0), vcat.get(1), vcat.get(2), vcat.get(3)], [Complex64::new(1.0, 0.0), i, -i, i]);
+        assert_eq!(ones.dtype(), type_name::<Complex64>());
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn shapes_survive_a_json_round_trip() {
+        let shapes = [
+            Shape::Scalar,
+            Shape::OneD(Size::Finite(3)),
+            Shape::OneD(Size::Infinite),
+            Shape::MultiD(vec![Size::Infinite, Size::Finite(2)]),
+        ];
+        for shape in shapes {
+            let json = serde_json::to_string(&shape).unwrap();
+            assert_eq!(serde_json::from_str::<Shape>(&json).unwrap(), shape);
+        }
+    }
+}
@@ -0,0 +1,181 @@
+//! Arrays whose entries are computed asynchronously, available with the `async` feature.
+//!
+//! Some sequences come from IO, e.g. precomputed coefficients in a database or a
+//! remote service. [`AsyncInfiniteArray`] describes them without tying the crate to
+//! an executor, and [`AsyncCachedArray`] caches the fetched entries and prefetches
+//! missing ones in batches, after which synchronous code can read the fetched prefix
+//! through [`InfiniteArray`].
+
+use crate::arrays::{InfiniteArray, Shape};
+use crate::infinity::Size;
+use crate::sync::{Lock, LockValues};
+use std::collections::HashMap;
+use std::future::Future;
+use std::ops::Range;
+
+/// An infinite sequence whose entries are obtained asynchronously
+pub trait AsyncInfiniteArray: Send + Sync {
+    /// Entry at 0-based position `index`
+    fn get(&self, index: usize) -> impl Future<Output = f64> + Send;
+
+    /// Entries at the positions in `range`, in order
+    ///
+    /// Defaults to awaiting [`AsyncInfiniteArray::get`] for each position in turn;
+    /// sources that can fetch a block in one request should override it.
+    fn get_batch(&self, range: Range<usize>) -> impl Future<Output = Vec<f64>> + Send {
+        async move {
+            let mut values = Vec::with_capacity(range.len());
+            for index in range {
+                values.push(self.get(index).await);
+            }
+            values
+        }
+    }
+}
+
+/// Cache in front of an [`AsyncInfiniteArray`]
+///
+/// Entries are fetched once. [`AsyncCachedArray::prefetch`] fetches the missing
+/// entries of a range in batches of `batch_size` through
+/// [`AsyncInfiniteArray::get_batch`].
+pub struct AsyncCachedArray<A> {
+    source: A,
+    cache: Lock<HashMap<usize, f64>>,
+    batch_size: usize,
+}
+
+impl<A: AsyncInfiniteArray> AsyncCachedArray<A> {
+    pub fn new(source: A) -> Self {
+        AsyncCachedArray {
+            source,
+            cache: Lock::new(HashMap::new()),
+            batch_size: 64,
+        }
+    }
+
+    /// Largest number of entries requested in one `get_batch` call (default 64)
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        assert!(batch_size > 0, "batch size must be positive");
+        self.batch_size = batch_size;
+        self
+    }
+
+    /// Cached entry, if it has been fetched
+    pub fn cached(&self, index: usize) -> Option<f64> {
+        self.cache.lock_values().get(&index).copied()
+    }
+
+    /// Entry at `index`, fetched from the source on first access
+    pub async fn get(&self, index: usize) -> f64 {
+        if let Some(value) = self.cached(index) {
+            return value;
+        }
+        let value = self.source.get(index).await;
+        self.cache.lock_values().insert(index, value);
+        value
+    }
+
+    /// Fetch the uncached entries of `range`, batching runs of consecutive positions
+    pub async fn prefetch(&self, range: Range<usize>) {
+        let mut batches: Vec<Range<usize>> = Vec::new();
+        {
+            let cache = self.cache.lock_values();
+            for index in range.filter(|index| !cache.contains_key(index)) {
+                match batches.last_mut() {
+                    Some(batch) if batch.end == index && batch.len() < self.batch_size => batch.end += 1,
+                    _ => batches.push(index..index + 1),
+                }
+            }
+        }
+        for batch in batches {
+            let values = self.source.get_batch(batch.clone()).await;
+            self.cache.lock_values().extend(batch.zip(values));
+        }
+    }
+
+    /// The underlying source
+    pub fn source(&self) -> &A {
+        &self.source
+    }
+}
+
+/// Synchronous access to the fetched entries
+///
+/// `get` panics for an entry that has not been fetched; call
+/// [`AsyncCachedArray::prefetch`] for the positions a synchronous algorithm reads.
+impl<A: AsyncInfiniteArray> InfiniteArray for AsyncCachedArray<A> {
+    fn get(&self, index: usize) -> f64 {
+        self.cached(index)
+            .unwrap_or_else(|| panic!("entry {} has not been fetched; prefetch it first", index))
+    }
+
+    fn shape(&self) -> Shape {
+        Shape::OneD(Size::Infinite)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::pin::pin;
+    use std::sync::Arc;
+    use std::task::{Context, Poll, Wake, Waker};
+
+    struct NoopWaker;
+
+    impl Wake for NoopWaker {
+        fn wake(self: Arc<Self>) {}
+    }
+
+    /// Poll a future that never waits on anything until it completes
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let waker = Waker::from(Arc::new(NoopWaker));
+        let mut context = Context::from_waker(&waker);
+        let mut future = pin!(future);
+        loop {
+            if let Poll::Ready(value) = future.as_mut().poll(&mut context) {
+                return value;
+            }
+        }
+    }
+
+    /// Squares k², recording every batch it is asked for
+    #[derive(Default)]
+    struct Squares {
+        batches: Lock<Vec<Range<usize>>>,
+    }
+
+    impl AsyncInfiniteArray for Squares {
+        async fn get(&self, index: usize) -> f64 {
+            (index * index) as f64
+        }
+
+        async fn get_batch(&self, range: Range<usize>) -> Vec<f64> {
+            self.batches.lock().unwrap().push(range.clone());
+            range.map(|k| (k * k) as f64).collect()
+        }
+    }
+
+    #[test]
+    fn prefetch_fetches_missing_runs_in_batches() {
+        let array = AsyncCachedArray::new(Squares::default()).with_batch_size(3);
+        block_on(array.prefetch(0..7));
+        assert_eq!(*array.source().batches.lock().unwrap(), [0..3, 3..6, 6..7]);
+        assert_eq!(block_on(array.get(10)), 100.0);
+        assert_eq!(array.cached(10), Some(100.0));
+
+        // Only the gaps around the cached entries are requested
+        array.source().batches.lock().unwrap().clear();
+        block_on(array.prefetch(5..12));
+        assert_eq!(*array.source().batches.lock().unwrap(), [7..10, 11..12]);
+        let squares: Vec<f64> = (0..12).map(|k| (k * k) as f64).collect();
+        assert_eq!((0..12).map(|k| InfiniteArray::get(&array, k)).collect::<Vec<_>>(), squares);
+    }
+
+    #[test]
+    #[should_panic(expected = "has not been fetched")]
+    fn synchronous_reads_of_unfetched_entries_panic() {
+        let array = AsyncCachedArray::new(Squares::default());
+        InfiniteArray::get(&array, 3);
+    }
+}
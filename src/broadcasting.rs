@@ -1,30 +1,33 @@
 //! Broadcasting support for infinite arrays.
 
-use std::fmt;
+use alloc::boxed::Box;
+use core::any::type_name;
+use core::fmt;
 use crate::arrays::{InfiniteArray, Shape};
+use crate::display;
 
 /// Lazy broadcasted array that computes values on-demand
-pub struct BroadcastArray {
-    func: Box<dyn Fn(usize) -> f64 + Send + Sync>,
+pub struct BroadcastArray<T = f64> {
+    func: Box<dyn Fn(usize) -> T + Send + Sync>,
     shape: Shape,
     dtype: &'static str,
 }
 
-impl BroadcastArray {
+impl<T> BroadcastArray<T> {
     pub fn new<F>(func: F, shape: Shape) -> Self
     where
-        F: Fn(usize) -> f64 + Send + Sync + 'static,
+        F: Fn(usize) -> T + Send + Sync + 'static,
     {
         BroadcastArray {
             func: Box::new(func),
             shape,
-            dtype: "f64",
+            dtype: type_name::<T>(),
         }
     }
 }
 
-impl InfiniteArray for BroadcastArray {
-    fn get(&self, index: usize) -> f64 {
+impl<T> InfiniteArray<T> for BroadcastArray<T> {
+    fn get(&self, index: usize) -> T {
         (self.func)(index)
     }
     
@@ -37,19 +40,36 @@ impl InfiniteArray for BroadcastArray {
     }
 }
 
-impl fmt::Display for BroadcastArray {
+impl<T: fmt::Display> fmt::Display for BroadcastArray<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "BroadcastArray{}:", self.shape())?;
         for i in 0..12 {
             write!(f, "\n  {}", self.get(i))?;
         }
-        write!(f, "\n  ⋮")
+        write!(f, "\n  {}", display::vertical_ellipsis())
     }
 }
 
-impl fmt::Debug for BroadcastArray {
+impl<T> fmt::Debug for BroadcastArray<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "BroadcastArray{}", self.shape())
+        write!(f, "BroadcastArray{}", self.shape)
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infinity::Size;
+    use num_complex::Complex64;
+
+    #[test]
+    fn broadcast_arrays_hold_f32_and_complex_entries() {
+        let halves = BroadcastArray::new(|i| i as f32 / 2.0, Shape::OneD(Size::Infinite));
+        assert_eq!([halves.get(0), halves.get(3)], [0.0, 1.5]);
+        assert_eq!(halves.dtype(), "f32");
+        let ramp = BroadcastArray::new(|i| Complex64::new(i as f64, -(i as f64)), Shape::OneD(Size::Infinite));
+        assert_eq!(ramp.get(2), Complex64::new(2.0, -2.0));
+        assert_eq!(ramp.dtype(), type_name::<Complex64>());
+    }
+}
@@ -1,41 +1,47 @@
 //! Caching functionality for infinite arrays to enable mutation.
 
-use std::collections::HashMap;
-use std::fmt;
-use std::sync::{Arc, Mutex};
+use hashbrown::HashMap;
+use core::fmt;
+use alloc::sync::Arc;
 use crate::arrays::{InfiniteArray, Shape};
+use crate::display;
+use crate::sync::{Lock, LockValues};
+#[cfg(feature = "rayon")]
+use alloc::vec::Vec;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
 
 /// Cached version of an infinite array that allows mutation
-pub struct CachedArray {
-    base: Arc<dyn InfiniteArray>,
-    cache: Arc<Mutex<HashMap<usize, f64>>>,
+pub struct CachedArray<T = f64> {
+    base: Arc<dyn InfiniteArray<T>>,
+    cache: Arc<Lock<HashMap<usize, T>>>,
     shape: Shape,
     dtype: &'static str,
 }
 
-impl CachedArray {
-    pub fn new(array: Arc<dyn InfiniteArray>) -> Self {
+impl<T: Copy> CachedArray<T> {
+    pub fn new(array: Arc<dyn InfiniteArray<T>>) -> Self {
         let shape = (*array).shape();
         let dtype = (*array).dtype();
         CachedArray {
             base: array,
-            cache: Arc::new(Mutex::new(HashMap::new())),
+            cache: Arc::new(Lock::new(HashMap::new())),
             shape,
             dtype,
         }
     }
     
-    pub fn set(&self, index: usize, value: f64) {
-        let mut cache = self.cache.lock().unwrap();
+    pub fn set(&self, index: usize, value: T) {
+        let mut cache = self.cache.lock_values();
         cache.insert(index, value);
     }
 }
 
-impl InfiniteArray for CachedArray {
-    fn get(&self, index: usize) -> f64 {
+impl<T: Copy + Send + Sync> InfiniteArray<T> for CachedArray<T> {
+    fn get(&self, index: usize) -> T {
         // Check cache first
         {
-            let cache = self.cache.lock().unwrap();
+            let cache = self.cache.lock_values();
             if let Some(&value) = cache.get(&index) {
                 return value;
             }
@@ -46,7 +52,7 @@ impl InfiniteArray for CachedArray {
         
         // Cache it
         {
-            let mut cache = self.cache.lock().unwrap();
+            let mut cache = self.cache.lock_values();
             cache.insert(index, value);
         }
         
@@ -60,26 +66,102 @@ impl InfiniteArray for CachedArray {
     fn dtype(&self) -> &'static str {
         self.dtype
     }
+    
+    #[cfg(feature = "rayon")]
+    fn par_take_finite(&self, n: usize) -> Vec<T> {
+        par_fill(&self.cache, n, |i| self.base.get(i))
+    }
 }
 
-impl fmt::Display for CachedArray {
+/// Entries 0..n of a value cache, evaluating the missing ones with `eval` in parallel
+///
+/// The lock is taken once for the lookups and once to insert the new entries, so
+/// the worker threads never wait on each other.
+#[cfg(feature = "rayon")]
+pub(crate) fn par_fill<T: Copy + Send + Sync>(cache: &Lock<HashMap<usize, T>>, n: usize, eval: impl Fn(usize) -> T + Sync) -> Vec<T> {
+    let mut values: Vec<Option<T>> = {
+        let cache = cache.lock_values();
+        (0..n).map(|i| cache.get(&i).copied()).collect()
+    };
+    let missing: Vec<usize> = (0..n).filter(|&i| values[i].is_none()).collect();
+    if !missing.is_empty() {
+        let computed: Vec<T> = missing.par_iter().map(|&i| eval(i)).collect();
+        let mut cache = cache.lock_values();
+        for (i, value) in missing.into_iter().zip(computed) {
+            // An entry set or computed by another thread in the meantime wins
+            values[i] = Some(*cache.entry(i).or_insert(value));
+        }
+    }
+    values.into_iter().map(|value| value.expect("every entry is looked up or computed")).collect()
+}
+
+impl<T: Copy + Send + Sync + fmt::Display> fmt::Display for CachedArray<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "CachedArray{}:", self.shape())?;
         for i in 0..12 {
             write!(f, "\n  {}", self.get(i))?;
         }
-        write!(f, "\n  ⋮")
+        write!(f, "\n  {}", display::vertical_ellipsis())
     }
 }
 
-impl fmt::Debug for CachedArray {
+impl<T> fmt::Debug for CachedArray<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "CachedArray{}", self.shape())
+        write!(f, "CachedArray{}", self.shape)
     }
 }
 
 /// Convert an infinite array to a cached (mutable) version
-pub fn cache(array: Arc<dyn InfiniteArray>) -> CachedArray {
+pub fn cache<T: Copy>(array: Arc<dyn InfiniteArray<T>>) -> CachedArray<T> {
     CachedArray::new(array)
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arrays::Fill;
+    use crate::broadcasting::BroadcastArray;
+    use crate::infinity::Size;
+    use core::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn cached_array_evaluates_each_entry_once() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&calls);
+        let squares = BroadcastArray::new(
+            move |i| {
+                counter.fetch_add(1, Ordering::Relaxed);
+                (i * i) as f64
+            },
+            Shape::OneD(Size::Infinite),
+        );
+        let cached = CachedArray::new(Arc::new(squares));
+        assert_eq!(cached.get(3), 9.0);
+        assert_eq!(cached.get(3), 9.0);
+        assert_eq!(calls.load(Ordering::Relaxed), 1);
+
+        cached.set(3, -1.0);
+        assert_eq!(cached.get(3), -1.0);
+        assert_eq!(cached.get(4), 16.0);
+        assert_eq!(calls.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    #[cfg(feature = "rayon")]
+    fn par_take_finite_fills_the_cache_with_the_serial_entries() {
+        let squares = BroadcastArray::new(|i| (i * i) as f64, Shape::OneD(Size::Infinite));
+        let cached = CachedArray::new(Arc::new(squares));
+        assert_eq!(cached.get(2), 4.0);
+        let serial: Vec<f64> = (0..50).map(|i| (i * i) as f64).collect();
+        assert_eq!(cached.par_take_finite(50), serial);
+        assert_eq!((0..50).map(|i| cached.get(i)).collect::<Vec<_>>(), serial);
+    }
+
+    #[test]
+    fn cached_array_holds_f32_entries() {
+        let cached = CachedArray::new(Arc::new(Fill::new(1.5f32, None)));
+        cached.set(2, -1.0);
+        assert_eq!([cached.get(1), cached.get(2), cached.get(3)], [1.5, -1.0, 1.5]);
+        assert_eq!(cached.dtype(), "f32");
+    }
+}
@@ -0,0 +1,181 @@
+//! Contour integrals of the resolvent of truncated operators.
+//!
+//! For a closed contour Γ the Riesz projector P = (2πi)⁻¹ ∮_Γ (z − A)⁻¹ dz projects
+//! onto the invariant subspace of the eigenvalues enclosed by Γ. The integral is
+//! evaluated with the trapezoidal rule, which converges geometrically for analytic
+//! integrands on circles.
+
+use crate::iqr::InfiniteOperator;
+use crate::linalg;
+use ndarray::Array2;
+use num_complex::Complex64;
+use std::f64::consts::PI;
+
+/// Number of quadrature nodes used for the contour integrals
+pub(crate) const QUADRATURE_NODES: usize = 64;
+
+/// Closed contour in the complex plane
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Contour {
+    /// Circle with the given center and radius
+    Circle { center: Complex64, radius: f64 },
+    /// Real interval [a, b], enclosed by the circle having it as a diameter
+    Interval(f64, f64),
+}
+
+impl Contour {
+    /// Whether the point lies strictly inside the contour
+    pub fn encloses(&self, z: Complex64) -> bool {
+        let (center, radius) = self.circle();
+        (z - center).norm() < radius
+    }
+    
+    /// Trapezoidal quadrature of (2πi)⁻¹ ∮ f(z) dz as (node, weight) pairs
+    pub fn quadrature(&self, nodes: usize) -> Vec<(Complex64, Complex64)> {
+        let (center, radius) = self.circle();
+        (0..nodes)
+            .map(|k| {
+                let offset = Complex64::from_polar(radius, 2.0 * PI * k as f64 / nodes as f64);
+                (center + offset, offset / nodes as f64)
+            })
+            .collect()
+    }
+    
+    fn circle(&self) -> (Complex64, f64) {
+        match *self {
+            Contour::Circle { center, radius } => (center, radius),
+            Contour::Interval(a, b) => (Complex64::new(0.5 * (a + b), 0.0), 0.5 * (b - a).abs()),
+        }
+    }
+}
+
+/// Riesz projector of a square matrix for the eigenvalues enclosed by the contour
+pub fn riesz_projector(a: &Array2<Complex64>, contour: &Contour) -> Array2<Complex64> {
+    let n = a.nrows();
+    let mut projector = nalgebra::DMatrix::<Complex64>::zeros(n, n);
+    for (z, weight) in contour.quadrature(QUADRATURE_NODES) {
+        let mut shifted = -linalg::to_dmatrix(a);
+        for i in 0..n {
+            shifted[(i, i)] += z;
+        }
+        if let Some(resolvent) = shifted.try_inverse() {
+            projector += resolvent * weight;
+        }
+    }
+    Array2::from_shape_fn((n, n), |(i, j)| projector[(i, j)])
+}
+
+/// Spectral projector of one truncation
+#[derive(Debug, Clone)]
+pub struct SpectralProjection {
+    pub n: usize,
+    pub projector: Array2<Complex64>,
+    /// Rank of the projector, i.e. the number of enclosed eigenvalues
+    pub rank: usize,
+}
+
+/// Spectral projectors across truncation sizes
+#[derive(Debug, Clone)]
+pub struct SpectralProjectionResult {
+    pub projections: Vec<SpectralProjection>,
+    /// Largest principal angle between the ranges of consecutive projectors (the
+    /// smaller one padded with zeros), or `None` when their ranks differ
+    pub angles: Vec<Option<f64>>,
+}
+
+/// Compute the spectral projector of each truncation for the enclosed eigenvalue cluster
+///
+/// Converging projectors have a stable rank and principal angles tending to zero.
+pub fn spectral_projection(
+    operator: &InfiniteOperator,
+    contour: &Contour,
+    n_range: &[usize],
+) -> SpectralProjectionResult {
+    let mut sizes = n_range.to_vec();
+    sizes.sort_unstable();
+    sizes.dedup();
+    
+    let mut truncation = Array2::<Complex64>::zeros((0, 0));
+    let projections: Vec<SpectralProjection> = sizes
+        .iter()
+        .map(|&n| {
+            operator.extend_truncation(&mut truncation, n);
+            let projector = riesz_projector(&truncation, contour);
+            let trace: Complex64 = projector.diag().iter().sum();
+            let rank = trace.re.round().max(0.0) as usize;
+            SpectralProjection { n, projector, rank }
+        })
+        .collect();
+    
+    let angles = projections
+        .windows(2)
+        .map(|pair| {
+            if pair[0].rank != pair[1].rank {
+                return None;
+            }
+            Some(largest_principal_angle(&pair[0], &pair[1]))
+        })
+        .collect();
+    
+    SpectralProjectionResult { projections, angles }
+}
+
+/// Orthonormal basis for the range of a projector, embedded in dimension `n`
+fn range_basis(projection: &SpectralProjection, n: usize) -> nalgebra::DMatrix<Complex64> {
+    let m = projection.n;
+    let mut padded = nalgebra::DMatrix::<Complex64>::zeros(n, m);
+    for ((i, j), &value) in projection.projector.indexed_iter() {
+        padded[(i, j)] = value;
+    }
+    let svd = padded.svd(true, false);
+    let u = svd.u.expect("left singular vectors were requested");
+    let mut order: Vec<usize> = (0..svd.singular_values.len()).collect();
+    order.sort_by(|&a, &b| svd.singular_values[b].total_cmp(&svd.singular_values[a]));
+    let columns: Vec<_> = order.iter().take(projection.rank).map(|&k| u.column(k).into_owned()).collect();
+    if columns.is_empty() {
+        nalgebra::DMatrix::zeros(n, 0)
+    } else {
+        nalgebra::DMatrix::from_columns(&columns)
+    }
+}
+
+/// Largest principal angle between the ranges of two projectors of equal rank
+fn largest_principal_angle(a: &SpectralProjection, b: &SpectralProjection) -> f64 {
+    if a.rank == 0 {
+        return 0.0;
+    }
+    let n = a.n.max(b.n);
+    let overlap = range_basis(a, n).adjoint() * range_basis(b, n);
+    let cos_min = overlap.singular_values().min().clamp(0.0, 1.0);
+    cos_min.acos()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::iqr::create_diagonal_operator;
+    use crate::test_fixtures::real;
+
+    #[test]
+    fn riesz_projector_of_a_diagonal_matrix() {
+        let a = Array2::from_diag(&ndarray::arr1(&[real(1.0), real(2.0), real(3.0)]));
+        let contour = Contour::Circle { center: real(2.0), radius: 0.5 };
+        assert!(contour.encloses(real(2.2)) && !contour.encloses(real(3.0)));
+        let projector = riesz_projector(&a, &contour);
+        for ((i, j), value) in projector.indexed_iter() {
+            let expected = if i == 1 && j == 1 { 1.0 } else { 0.0 };
+            assert!((value - expected).norm() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn spectral_projection_settles_on_an_isolated_cluster() {
+        // Eigenvalues 1/(k + 1): the interval [0.4, 1.1] encloses 1 and 1/2
+        let operator = create_diagonal_operator(|i| real(1.0 / (i + 1) as f64));
+        let result = spectral_projection(&operator, &Contour::Interval(0.4, 1.1), &[8, 4, 16]);
+        assert_eq!(result.projections.iter().map(|p| p.n).collect::<Vec<_>>(), [4, 8, 16]);
+        assert!(result.projections.iter().all(|p| p.rank == 2));
+        assert_eq!(result.angles.len(), 2);
+        assert!(result.angles.iter().all(|angle| angle.is_some_and(|angle| angle < 1e-6)));
+    }
+}
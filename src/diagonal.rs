@@ -1,36 +1,43 @@
 //! Diagonal infinite arrays.
 
-use std::collections::HashMap;
-use std::fmt;
-use std::sync::{Arc, Mutex};
+use hashbrown::HashMap;
+use alloc::vec;
+use core::any::type_name;
+use core::fmt;
+use alloc::sync::Arc;
 use crate::arrays::{InfiniteArray, Shape};
-use crate::infinity::Infinity;
+use crate::dims::{Dims, Inf};
+use crate::display;
+use crate::infinity::Size;
+use crate::ranges::{InfStepRange, InfUnitRange, OneToInf, RationalStepRange};
+use crate::sync::{Lock, LockValues};
+use num_traits::Zero;
 
 /// Infinite diagonal matrix with values from a sequence
-pub struct InfiniteDiagonal {
-    values: Arc<dyn Fn(usize) -> f64 + Send + Sync>,
-    value_cache: Arc<Mutex<HashMap<usize, f64>>>,
+pub struct InfiniteDiagonal<T = f64> {
+    values: Arc<dyn Fn(usize) -> T + Send + Sync>,
+    value_cache: Arc<Lock<HashMap<usize, T>>>,
     shape: Shape,
     dtype: &'static str,
 }
 
-impl InfiniteDiagonal {
+impl<T: Zero + Copy> InfiniteDiagonal<T> {
     pub fn new<F>(values: F) -> Self
     where
-        F: Fn(usize) -> f64 + Send + Sync + 'static,
+        F: Fn(usize) -> T + Send + Sync + 'static,
     {
         InfiniteDiagonal {
             values: Arc::new(values),
-            value_cache: Arc::new(Mutex::new(HashMap::new())),
-            shape: Shape::MultiD(vec![Some(Infinity), Some(Infinity)]),
-            dtype: "f64",
+            value_cache: Arc::new(Lock::new(HashMap::new())),
+            shape: Shape::MultiD(vec![Size::Infinite, Size::Infinite]),
+            dtype: type_name::<T>(),
         }
     }
     
-    fn get_value(&self, i: usize) -> f64 {
+    fn get_value(&self, i: usize) -> T {
         // Check cache
         {
-            let cache = self.value_cache.lock().unwrap();
+            let cache = self.value_cache.lock_values();
             if let Some(&value) = cache.get(&i) {
                 return value;
             }
@@ -39,29 +46,34 @@ impl InfiniteDiagonal {
         // Compute and cache
         let value = (self.values)(i);
         {
-            let mut cache = self.value_cache.lock().unwrap();
+            let mut cache = self.value_cache.lock_values();
             cache.insert(i, value);
         }
         
         value
     }
     
-    pub fn get(&self, row: usize, col: usize) -> f64 {
+    pub fn get(&self, row: usize, col: usize) -> T {
         if row == col {
             self.get_value(row)
         } else {
-            0.0
+            T::zero()
         }
     }
+    
+    /// The diagonal with its ∞×∞ size in the type
+    pub fn typed(self) -> Dims<Self, Inf, Inf> {
+        Dims::new(self)
+    }
 }
 
-impl InfiniteArray for InfiniteDiagonal {
-    fn get(&self, index: usize) -> f64 {
+impl<T: Zero + Copy + Send + Sync> InfiniteArray<T> for InfiniteDiagonal<T> {
+    fn get(&self, index: usize) -> T {
         // For 1D indexing, return diagonal element
         self.get_value(index)
     }
     
-    fn get_multi(&self, indices: &[usize]) -> f64 {
+    fn get_multi(&self, indices: &[usize]) -> T {
         if indices.len() == 2 {
             self.get(indices[0], indices[1])
         } else if indices.len() == 1 {
@@ -78,9 +90,29 @@ impl InfiniteArray for InfiniteDiagonal {
     fn dtype(&self) -> &'static str {
         self.dtype
     }
+    
+    #[cfg(feature = "rayon")]
+    fn par_take_finite(&self, n: usize) -> alloc::vec::Vec<T> {
+        crate::cache::par_fill(&self.value_cache, n, |i| (self.values)(i))
+    }
+}
+
+/// Diagonal with the values of a range, e.g. the position operator diag(1, 2, 3, ...)
+macro_rules! diagonal_from_range {
+    ($($range:ty),+) => {
+        $(
+            impl From<$range> for InfiniteDiagonal {
+                fn from(range: $range) -> Self {
+                    InfiniteDiagonal::new(move |i| InfiniteArray::get(&range, i))
+                }
+            }
+        )+
+    };
 }
 
-impl fmt::Display for InfiniteDiagonal {
+diagonal_from_range!(OneToInf, InfUnitRange, InfStepRange, RationalStepRange);
+
+impl<T: Zero + Copy + Send + Sync + fmt::Display> fmt::Display for InfiniteDiagonal<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "InfiniteDiagonal{}:", self.shape())?;
         let n = 15;
@@ -89,26 +121,51 @@ impl fmt::Display for InfiniteDiagonal {
                 if i == j {
                     write!(f, "{}", self.get(i, j))?;
                 } else if j == n - 1 {
-                    write!(f, "…")?;
+                    write!(f, "{}", display::ellipsis())?;
                     break;
                 } else {
-                    write!(f, "⋅")?;
+                    write!(f, "{}", display::structural_zero())?;
                 }
                 if j < n - 1 {
                     write!(f, "  ")?;
                 }
             }
             if i < n - 1 {
-                write!(f, "\n")?;
+                writeln!(f)?;
             }
         }
-        write!(f, "\n⋮")
+        write!(f, "\n{}", display::vertical_ellipsis())
     }
 }
 
-impl fmt::Debug for InfiniteDiagonal {
+impl<T> fmt::Debug for InfiniteDiagonal<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "InfiniteDiagonal{}", self.shape())
+        write!(f, "InfiniteDiagonal{}", self.shape)
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num_complex::Complex64;
+
+    #[test]
+    fn diagonal_is_zero_off_the_diagonal() {
+        let position = InfiniteDiagonal::from(OneToInf);
+        assert_eq!([position.get(0, 0), position.get(4, 4), position.get(4, 3)], [1.0, 5.0, 0.0]);
+        assert_eq!([position.get_multi(&[2, 2]), position.get_multi(&[2, 0])], [3.0, 0.0]);
+        assert_eq!(position.shape(), Shape::MultiD(vec![Size::Infinite, Size::Infinite]));
+        let rotation = InfiniteDiagonal::new(|i| Complex64::new(0.0, i as f64));
+        assert_eq!([rotation.get(3, 3), rotation.get(3, 2)], [Complex64::new(0.0, 3.0), Complex64::new(0.0, 0.0)]);
+        assert_eq!(rotation.dtype(), type_name::<Complex64>());
+    }
+
+    #[test]
+    #[cfg(feature = "rayon")]
+    fn par_take_finite_matches_the_serial_diagonal() {
+        let diagonal = InfiniteDiagonal::new(|i| 1.0 / (i + 1) as f64);
+        let serial: Vec<f64> = (0..30).map(|i| diagonal.get(i, i)).collect();
+        assert_eq!(diagonal.par_take_finite(30), serial);
+    }
+}
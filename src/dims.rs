@@ -0,0 +1,132 @@
+//! Type-level dimension markers.
+//!
+//! [`Inf`] and [`Finite<N>`] record the sizes of an array's dimensions in its type,
+//! so a routine can demand e.g. an ∞×n operator at compile time instead of checking
+//! shapes at run time. [`Dims`] attaches the markers to an existing array or
+//! operator without changing how it is evaluated.
+
+use crate::display;
+use crate::infinity::Size;
+use core::fmt;
+use core::marker::PhantomData;
+use core::ops::Deref;
+
+/// A dimension size known at compile time
+pub trait Dim: Copy + Default + Send + Sync + 'static {
+    const SIZE: Size;
+}
+
+/// Marker for a dimension of size ∞
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Inf;
+
+/// Marker for a dimension of size `N`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Finite<const N: usize>;
+
+/// Dimensions with a finite size
+pub trait FiniteDim: Dim {
+    const LEN: usize;
+}
+
+impl Dim for Inf {
+    const SIZE: Size = Size::Infinite;
+}
+
+impl<const N: usize> Dim for Finite<N> {
+    const SIZE: Size = Size::Finite(N);
+}
+
+impl<const N: usize> FiniteDim for Finite<N> {
+    const LEN: usize = N;
+}
+
+/// An array or operator whose row and column sizes are the markers `R` and `C`
+///
+/// Dereferences to the wrapped value. Constructors on the wrapped types, such as
+/// [`crate::InfiniteDiagonal::typed`] and [`crate::iqr::InfiniteOperator::first_columns`],
+/// pick the markers that match what they build.
+pub struct Dims<A, R: Dim, C: Dim> {
+    inner: A,
+    dims: PhantomData<(R, C)>,
+}
+
+impl<A, R: Dim, C: Dim> Dims<A, R, C> {
+    /// Attach the markers; the caller vouches that `inner` has these sizes
+    pub fn new(inner: A) -> Self {
+        Dims { inner, dims: PhantomData }
+    }
+
+    pub fn nrows(&self) -> Size {
+        R::SIZE
+    }
+
+    pub fn ncols(&self) -> Size {
+        C::SIZE
+    }
+
+    pub fn into_inner(self) -> A {
+        self.inner
+    }
+}
+
+impl<A, R: Dim, C: Dim> Deref for Dims<A, R, C> {
+    type Target = A;
+
+    fn deref(&self) -> &A {
+        &self.inner
+    }
+}
+
+impl<A: Clone, R: Dim, C: Dim> Clone for Dims<A, R, C> {
+    fn clone(&self) -> Self {
+        Dims::new(self.inner.clone())
+    }
+}
+
+/// The sizes, e.g. `∞×3` (`infx3` in ASCII)
+impl<A, R: Dim, C: Dim> fmt::Debug for Dims<A, R, C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Dims({}{}{})", R::SIZE, display::times(), C::SIZE)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::display::Ascii;
+    use alloc::format;
+
+    /// Accepts only operators with a finite number of columns
+    fn column_count<A, R: Dim, C: FiniteDim>(_: &Dims<A, R, C>) -> usize {
+        C::LEN
+    }
+
+    #[test]
+    fn markers_carry_their_sizes() {
+        assert_eq!(Inf::SIZE, Size::Infinite);
+        assert_eq!(Finite::<3>::SIZE, Size::Finite(3));
+        assert_eq!(Finite::<3>::LEN, 3);
+
+        let typed: Dims<[i32; 2], Inf, Finite<3>> = Dims::new([4, 5]);
+        assert_eq!(typed.nrows(), Size::Infinite);
+        assert_eq!(typed.ncols(), Size::Finite(3));
+        assert_eq!(column_count(&typed), 3);
+        assert_eq!(typed[1], 5);
+        assert_eq!(typed.clone().into_inner(), [4, 5]);
+        assert_eq!(format!("{:?}", Ascii(&typed)), "Dims(infx3)");
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn first_columns_are_typed_and_share_the_operator() {
+        use num_complex::Complex64;
+
+        let operator = crate::iqr::InfiniteOperator::new(|i, j| Complex64::new(i as f64, j as f64));
+        let columns = operator.first_columns::<2>();
+        assert_eq!(column_count(&columns), 2);
+        assert_eq!(columns.nrows(), Size::Infinite);
+        assert_eq!(columns.get(7, 1), Complex64::new(7.0, 1.0));
+        assert_eq!(format!("{:?}", Ascii(&operator.typed())), "Dims(infxinf)");
+    }
+}
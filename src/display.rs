@@ -0,0 +1,184 @@
+//! Symbols used when printing infinities, ranges and arrays.
+//!
+//! The printers use Unicode (`∞`, `⋮`, `⋅`, `λ`, `±`) by default. Terminals and log pipelines
+//! that mangle it can switch the whole crate to ASCII (`inf`, `...`, `.`) with
+//! [`set_charset`], or print a single value in ASCII through [`Ascii`].
+
+#[cfg(feature = "std")]
+use core::cell::Cell;
+use core::fmt;
+use core::sync::atomic::{AtomicBool, Ordering};
+#[cfg(not(feature = "std"))]
+use core::sync::atomic::AtomicUsize;
+
+/// Character set of the `Display` impls
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Charset {
+    #[default]
+    Unicode,
+    Ascii,
+}
+
+static ASCII: AtomicBool = AtomicBool::new(false);
+
+// Whether an `Ascii` wrapper is being printed: per thread with `std`, and as a
+// process-wide count without it, where printing is assumed not to run concurrently.
+#[cfg(feature = "std")]
+std::thread_local! {
+    static IN_ASCII: Cell<bool> = const { Cell::new(false) };
+}
+
+#[cfg(not(feature = "std"))]
+static IN_ASCII: AtomicUsize = AtomicUsize::new(0);
+
+#[cfg(feature = "std")]
+fn in_ascii(print: impl FnOnce() -> fmt::Result) -> fmt::Result {
+    let previous = IN_ASCII.with(|flag| flag.replace(true));
+    let result = print();
+    IN_ASCII.with(|flag| flag.set(previous));
+    result
+}
+
+#[cfg(not(feature = "std"))]
+fn in_ascii(print: impl FnOnce() -> fmt::Result) -> fmt::Result {
+    IN_ASCII.fetch_add(1, Ordering::Relaxed);
+    let result = print();
+    IN_ASCII.fetch_sub(1, Ordering::Relaxed);
+    result
+}
+
+fn ascii_override() -> bool {
+    #[cfg(feature = "std")]
+    return IN_ASCII.with(Cell::get);
+    #[cfg(not(feature = "std"))]
+    return IN_ASCII.load(Ordering::Relaxed) > 0;
+}
+
+/// Set the character set used crate-wide
+pub fn set_charset(charset: Charset) {
+    ASCII.store(charset == Charset::Ascii, Ordering::Relaxed);
+}
+
+/// The character set in effect, taking [`Ascii`] into account
+pub fn charset() -> Charset {
+    if ascii_override() || ASCII.load(Ordering::Relaxed) {
+        Charset::Ascii
+    } else {
+        Charset::Unicode
+    }
+}
+
+fn pick(unicode: &'static str, ascii: &'static str) -> &'static str {
+    match charset() {
+        Charset::Unicode => unicode,
+        Charset::Ascii => ascii,
+    }
+}
+
+/// ∞ or `inf`
+pub(crate) fn infinity() -> &'static str {
+    pick("∞", "inf")
+}
+
+/// ⋮ below truncated columns
+pub(crate) fn vertical_ellipsis() -> &'static str {
+    pick("⋮", "...")
+}
+
+/// … after truncated rows
+pub(crate) fn ellipsis() -> &'static str {
+    pick("…", "...")
+}
+
+/// ⋅ for structural zeros
+pub(crate) fn structural_zero() -> &'static str {
+    pick("⋅", ".")
+}
+
+/// × between dimension sizes
+pub(crate) fn times() -> &'static str {
+    pick("×", "x")
+}
+
+/// λ for eigenvalues
+pub(crate) fn lambda() -> &'static str {
+    pick("λ", "lambda")
+}
+
+/// κ for condition numbers
+#[cfg(feature = "std")]
+pub(crate) fn kappa() -> &'static str {
+    pick("κ", "kappa")
+}
+
+/// ± before error bounds
+#[cfg(feature = "std")]
+pub(crate) fn plus_minus() -> &'static str {
+    pick("±", "+/-")
+}
+
+/// ↔ between matched values
+#[cfg(feature = "std")]
+pub(crate) fn matched() -> &'static str {
+    pick("↔", "<->")
+}
+
+/// Δ for differences
+#[cfg(feature = "std")]
+pub(crate) fn delta() -> &'static str {
+    pick("Δ", "delta")
+}
+
+/// Prints the wrapped value in ASCII regardless of the crate-wide setting,
+/// e.g. `println!("{}", Ascii(&range))`; works with both `{}` and `{:?}`
+pub struct Ascii<T>(pub T);
+
+impl<T: fmt::Display> fmt::Display for Ascii<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        in_ascii(|| fmt::Display::fmt(&self.0, f))
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Ascii<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        in_ascii(|| fmt::Debug::fmt(&self.0, f))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infinity::{Infinity, SignedInfinity};
+    use crate::ranges::{InfStepRange, OneToInf};
+    use alloc::string::ToString;
+
+    /// Records the character set in effect while it is printed
+    struct Probe;
+
+    impl fmt::Display for Probe {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "{:?}", charset())
+        }
+    }
+
+    // The crate-wide setting is left alone here, as other tests print in parallel.
+
+    #[test]
+    fn ascii_prints_infinities_and_ranges_without_unicode() {
+        assert_eq!(Infinity.to_string(), "∞");
+        assert_eq!(Ascii(Infinity).to_string(), "inf");
+        assert_eq!(Ascii(SignedInfinity::Negative).to_string(), "-inf");
+        assert_eq!(Ascii(OneToInf).to_string(), "1:inf");
+        assert_eq!(Ascii(InfStepRange::new(5, -2)).to_string(), "5:-2:-inf");
+        assert!(Ascii(InfStepRange::new(5, -2)).to_string().is_ascii());
+    }
+
+    #[test]
+    fn ascii_only_applies_while_its_value_is_printed() {
+        assert_eq!(Probe.to_string(), "Unicode");
+        assert_eq!(Ascii(Probe).to_string(), "Ascii");
+        assert_eq!(Ascii(Ascii(Probe)).to_string(), "Ascii");
+        assert_eq!(Probe.to_string(), "Unicode");
+        assert_eq!(alloc::format!("{:?}", Ascii(Some(Infinity))), "Some(inf)");
+    }
+}
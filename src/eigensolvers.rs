@@ -0,0 +1,796 @@
+//! Iterative eigensolvers for infinite operators.
+//!
+//! These methods only need products with finitely supported vectors (see
+//! [`MatVec`]), so for banded operators they avoid dense truncations altogether.
+
+use crate::error::InfiniteArraysError;
+use crate::infinity::Size;
+use crate::iqr::{InfiniteOperator, MatVec, SortBy};
+use crate::linalg;
+use crate::sync::{Lock, LockValues};
+use num_complex::Complex64;
+
+/// Relative size of the first nudge applied to a shift that makes a section singular
+const SHIFT_NUDGE: f64 = 1e-8;
+
+/// Nudges, each ten times larger than the last, before a singular section is an error
+const MAX_SHIFT_NUDGES: i32 = 3;
+
+/// Shift-and-invert transformation (A − σ)⁻¹
+///
+/// Eigenvalues λ of A near σ become the dominant eigenvalues 1/(λ − σ), so power and
+/// Krylov iterations on this map find interior eigenvalues quickly. Each application
+/// solves (A − σ) x = b on sections that double in size until the solution settles
+/// to within `tol`, using a banded solver when the bandwidths are known.
+///
+/// A shift that is exactly an eigenvalue of a section makes that section singular.
+/// The shift is then moved by 10⁻⁸ max(1, |σ|) along the real axis (growing tenfold
+/// up to three times) and the moved shift is kept for all later solves, so the map
+/// stays the same linear map throughout an iteration; [`ShiftInvert::shift`] and
+/// [`ShiftInvert::eigenvalue_of_operator`] use it.
+pub struct ShiftInvert<'a> {
+    operator: &'a InfiniteOperator,
+    shift: Lock<Complex64>,
+    tol: f64,
+    max_n: usize,
+    failure: Lock<Option<InfiniteArraysError>>,
+}
+
+impl<'a> ShiftInvert<'a> {
+    pub fn new(operator: &'a InfiniteOperator, shift: Complex64, tol: f64) -> Self {
+        ShiftInvert {
+            operator,
+            shift: Lock::new(shift),
+            tol,
+            max_n: 1024,
+            failure: Lock::new(None),
+        }
+    }
+    
+    /// Limit the section size used by the adaptive solves (default 1024)
+    pub fn with_max_n(mut self, max_n: usize) -> Self {
+        self.max_n = max_n;
+        self
+    }
+    
+    /// The shift σ in use, which differs from the requested one after a nudge
+    pub fn shift(&self) -> Complex64 {
+        *self.shift.lock_values()
+    }
+    
+    /// Map an eigenvalue μ of (A − σ)⁻¹ back to the eigenvalue σ + 1/μ of A
+    pub fn eigenvalue_of_operator(&self, mu: Complex64) -> Complex64 {
+        self.shift() + Complex64::new(1.0, 0.0) / mu
+    }
+    
+    /// The error of the last failed [`MatVec::matvec`], if any
+    pub fn failure(&self) -> Option<InfiniteArraysError> {
+        self.failure.lock_values().clone()
+    }
+    
+    /// Solve (A − σ) x = b, nudging σ off an eigenvalue of a section; fails with
+    /// [`InfiniteArraysError::SingularSection`] if the sections stay singular
+    pub fn solve(&self, b: &[Complex64]) -> Result<Vec<Complex64>, InfiniteArraysError> {
+        let mut nudges = 0;
+        loop {
+            let shift = self.shift();
+            match self.solve_shifted(b, shift) {
+                Err(InfiniteArraysError::SingularSection { .. }) if nudges < MAX_SHIFT_NUDGES => {
+                    let nudge = SHIFT_NUDGE * 10f64.powi(nudges) * shift.norm().max(1.0);
+                    *self.shift.lock_values() = shift + nudge;
+                    nudges += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+    
+    fn solve_shifted(&self, b: &[Complex64], shift: Complex64) -> Result<Vec<Complex64>, InfiniteArraysError> {
+        let mut n = (2 * b.len()).max(16).min(self.max_n.max(b.len()));
+        let mut previous: Option<Vec<Complex64>> = None;
+        loop {
+            let mut section = self.operator.get_truncation(n);
+            for i in 0..n {
+                section[(i, i)] -= shift;
+            }
+            let mut rhs = vec![Complex64::new(0.0, 0.0); n];
+            rhs[..b.len()].copy_from_slice(b);
+            let (lower, upper) = self.operator.bandwidths().unwrap_or((n, n));
+            let x = linalg::banded_solve(section, rhs, lower, upper).ok_or(InfiniteArraysError::SingularSection { n })?;
+            
+            if let Some(prev) = &previous {
+                if linalg::padded_distance(prev, &x) <= self.tol * linalg::norm(&x) {
+                    return Ok(x);
+                }
+            }
+            if n >= self.max_n {
+                return Ok(x);
+            }
+            previous = Some(x);
+            n = (2 * n).min(self.max_n);
+        }
+    }
+}
+
+impl MatVec for ShiftInvert<'_> {
+    /// Apply (A − σ)⁻¹
+    ///
+    /// [`MatVec`] has no way to report failure, so where [`ShiftInvert::solve`]
+    /// returns an error this returns zero, which ends Krylov and power iterations, and
+    /// records the error for [`ShiftInvert::failure`].
+    fn matvec(&self, x: &[Complex64]) -> Vec<Complex64> {
+        self.solve(x).unwrap_or_else(|error| {
+            *self.failure.lock_values() = Some(error);
+            vec![Complex64::new(0.0, 0.0); x.len()]
+        })
+    }
+}
+
+/// Result of a Krylov eigensolver
+#[derive(Debug, Clone)]
+pub struct KrylovResult {
+    /// Ritz values, largest magnitude first
+    pub eigenvalues: Vec<Complex64>,
+    /// Ritz vectors (finitely supported) matching `eigenvalues`
+    pub eigenvectors: Vec<Vec<Complex64>>,
+    /// Residual norms ‖A x − θ x‖ of the Ritz pairs
+    pub residuals: Vec<f64>,
+    pub restarts: usize,
+    pub converged: bool,
+}
+
+impl KrylovResult {
+    /// Reorder the Ritz pairs, keeping values, vectors and residuals together
+    pub fn sort(&mut self, by: SortBy) {
+        let order = by.order(&self.eigenvalues);
+        self.eigenvalues = permute(&self.eigenvalues, &order);
+        self.eigenvectors = permute(&self.eigenvectors, &order);
+        self.residuals = permute(&self.residuals, &order);
+    }
+}
+
+/// `values` rearranged so that entry i is `values[order[i]]`
+fn permute<T: Clone>(values: &[T], order: &[usize]) -> Vec<T> {
+    order.iter().map(|&k| values[k].clone()).collect()
+}
+
+/// Restarted Arnoldi iteration for the `nev` largest-magnitude eigenvalues
+///
+/// Builds a Krylov basis of dimension `krylov_dim` from `start` using only
+/// `op.matvec`, extracts Ritz pairs from the Hessenberg projection and restarts from
+/// the sum of the wanted Ritz vectors until all their residuals are below `tol`.
+/// Combine with [`ShiftInvert`] to target interior eigenvalues.
+pub fn arnoldi<M: MatVec + ?Sized>(
+    op: &M,
+    start: &[Complex64],
+    nev: usize,
+    krylov_dim: usize,
+    max_restarts: usize,
+    tol: f64,
+) -> KrylovResult {
+    let krylov_dim = krylov_dim.max(nev + 1);
+    let mut v0 = start.to_vec();
+    let mut restarts = 0;
+    
+    loop {
+        let (basis, hessenberg, beta) = arnoldi_factorization(op, &v0, krylov_dim);
+        let m = basis.len();
+        let (values, vectors) = linalg::eigenpairs(&hessenberg.slice(ndarray::s![..m, ..m]).to_owned());
+        
+        let mut order: Vec<usize> = (0..values.len()).collect();
+        order.sort_by(|&a, &b| values[b].norm().total_cmp(&values[a].norm()));
+        order.truncate(nev);
+        
+        let eigenvalues: Vec<Complex64> = order.iter().map(|&k| values[k]).collect();
+        let residuals: Vec<f64> = order.iter().map(|&k| beta * vectors[(m - 1, k)].norm()).collect();
+        let eigenvectors: Vec<Vec<Complex64>> = order
+            .iter()
+            .map(|&k| {
+                let mut x = Vec::new();
+                for (j, q) in basis.iter().enumerate() {
+                    linalg::axpy(&mut x, vectors[(j, k)], q);
+                }
+                x
+            })
+            .collect();
+        
+        let converged = eigenvalues.len() == nev
+            && residuals
+                .iter()
+                .zip(&eigenvalues)
+                .all(|(&r, lambda)| r <= tol * lambda.norm().max(1.0));
+        if converged || restarts >= max_restarts {
+            return KrylovResult {
+                eigenvalues,
+                eigenvectors,
+                residuals,
+                restarts,
+                converged,
+            };
+        }
+        
+        v0 = Vec::new();
+        for x in &eigenvectors {
+            linalg::axpy(&mut v0, Complex64::new(1.0, 0.0), x);
+        }
+        restarts += 1;
+    }
+}
+
+/// m-step Arnoldi factorization A Q = Q H + β q e_mᵀ
+///
+/// Returns the orthonormal basis, the (m+1)×m Hessenberg matrix and β. Stops early
+/// on breakdown, when the Krylov space is invariant.
+fn arnoldi_factorization<M: MatVec + ?Sized>(
+    op: &M,
+    start: &[Complex64],
+    m: usize,
+) -> (Vec<Vec<Complex64>>, ndarray::Array2<Complex64>, f64) {
+    let mut hessenberg = ndarray::Array2::<Complex64>::zeros((m + 1, m));
+    let mut q0 = start.to_vec();
+    if linalg::normalize(&mut q0) == 0.0 {
+        q0 = vec![Complex64::new(1.0, 0.0)];
+    }
+    let mut basis = vec![q0];
+    
+    for j in 0..m {
+        let mut w = op.matvec(&basis[j]);
+        // Classical Gram-Schmidt applied twice keeps the basis orthogonal
+        for _ in 0..2 {
+            for (i, q) in basis.iter().enumerate() {
+                let h = linalg::dot(q, &w);
+                hessenberg[(i, j)] += h;
+                linalg::axpy(&mut w, -h, q);
+            }
+        }
+        let beta = linalg::normalize(&mut w);
+        let scale = (0..=j).map(|i| hessenberg[(i, j)].norm()).fold(0.0, f64::max).max(1.0);
+        if beta <= 1e-14 * scale || j + 1 == m {
+            return (basis, hessenberg, beta);
+        }
+        hessenberg[(j + 1, j)] = Complex64::new(beta, 0.0);
+        basis.push(w);
+    }
+    unreachable!("the loop returns at j + 1 == m")
+}
+
+/// Reorthogonalization strategy for the Lanczos iteration
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reorthogonalization {
+    /// Orthogonalize every new vector against the whole basis
+    Full,
+    /// Orthogonalize only against Ritz vectors that have converged (Parlett–Scott),
+    /// which is where orthogonality is lost
+    Selective,
+}
+
+/// Result of the Lanczos iteration
+#[derive(Debug, Clone)]
+pub struct LanczosResult {
+    /// Ritz values in increasing order
+    pub eigenvalues: Vec<f64>,
+    /// Ritz vectors (finitely supported) matching `eigenvalues`
+    pub eigenvectors: Vec<Vec<Complex64>>,
+    /// Error bounds β_m |s_m| on the Ritz values
+    pub error_bounds: Vec<f64>,
+    /// Diagonal of the tridiagonal (Jacobi) projection
+    pub alpha: Vec<f64>,
+    /// Off-diagonal of the tridiagonal (Jacobi) projection
+    pub beta: Vec<f64>,
+}
+
+/// Lanczos iteration for a self-adjoint operator
+///
+/// Runs at most `steps` steps from `start`, projecting the operator onto a
+/// tridiagonal Jacobi matrix whose eigenpairs give the Ritz values and vectors.
+/// Stops early if the Krylov space becomes invariant.
+pub fn lanczos<M: MatVec + ?Sized>(
+    op: &M,
+    start: &[Complex64],
+    steps: usize,
+    reorthogonalization: Reorthogonalization,
+) -> LanczosResult {
+    let mut q = start.to_vec();
+    if linalg::normalize(&mut q) == 0.0 {
+        q = vec![Complex64::new(1.0, 0.0)];
+    }
+    let mut basis: Vec<Vec<Complex64>> = vec![q];
+    let mut alpha: Vec<f64> = Vec::new();
+    let mut beta: Vec<f64> = Vec::new();
+    
+    for j in 0..steps.max(1) {
+        let mut w = op.matvec(&basis[j]);
+        let a = linalg::dot(&basis[j], &w).re;
+        linalg::axpy(&mut w, Complex64::new(-a, 0.0), &basis[j]);
+        if j > 0 {
+            linalg::axpy(&mut w, Complex64::new(-beta[j - 1], 0.0), &basis[j - 1]);
+        }
+        alpha.push(a);
+        
+        match reorthogonalization {
+            Reorthogonalization::Full => {
+                for q in &basis {
+                    let h = linalg::dot(q, &w);
+                    linalg::axpy(&mut w, -h, q);
+                }
+            }
+            Reorthogonalization::Selective => {
+                let b = linalg::norm(&w);
+                let (values, vectors) = tridiagonal_eigen(&alpha, &beta);
+                let scale = values.iter().fold(0.0_f64, |m, v| m.max(v.abs())).max(1.0);
+                for k in 0..values.len() {
+                    if b * vectors[(j, k)].abs() <= f64::EPSILON.sqrt() * scale {
+                        let ritz = ritz_vector(&basis, &vectors, k);
+                        let h = linalg::dot(&ritz, &w);
+                        linalg::axpy(&mut w, -h, &ritz);
+                    }
+                }
+            }
+        }
+        
+        let b = linalg::normalize(&mut w);
+        if j + 1 == steps || b <= 1e-14 * a.abs().max(1.0) {
+            beta.push(b);
+            break;
+        }
+        beta.push(b);
+        basis.push(w);
+    }
+    
+    let m = alpha.len();
+    let residual_beta = beta[m - 1];
+    let (values, vectors) = tridiagonal_eigen(&alpha, &beta[..m - 1]);
+    let error_bounds = (0..m).map(|k| residual_beta * vectors[(m - 1, k)].abs()).collect();
+    let eigenvectors = (0..m).map(|k| ritz_vector(&basis, &vectors, k)).collect();
+    beta.truncate(m - 1);
+    
+    LanczosResult {
+        eigenvalues: values,
+        eigenvectors,
+        error_bounds,
+        alpha,
+        beta,
+    }
+}
+
+/// Eigenpairs of the symmetric tridiagonal matrix with diagonal `alpha` and
+/// off-diagonal `beta` (only its first `alpha.len() - 1` entries are used), in
+/// increasing order of eigenvalue
+fn tridiagonal_eigen(alpha: &[f64], beta: &[f64]) -> (Vec<f64>, nalgebra::DMatrix<f64>) {
+    let m = alpha.len();
+    let t = nalgebra::DMatrix::from_fn(m, m, |i, j| {
+        if i == j {
+            alpha[i]
+        } else if i == j + 1 || j == i + 1 {
+            beta[i.min(j)]
+        } else {
+            0.0
+        }
+    });
+    let eigen = t.symmetric_eigen();
+    let mut order: Vec<usize> = (0..m).collect();
+    order.sort_by(|&a, &b| eigen.eigenvalues[a].total_cmp(&eigen.eigenvalues[b]));
+    let values = order.iter().map(|&k| eigen.eigenvalues[k]).collect();
+    let vectors = nalgebra::DMatrix::from_fn(m, m, |i, k| eigen.eigenvectors[(i, order[k])]);
+    (values, vectors)
+}
+
+/// Ritz vector Σ_j s_{j,k} q_j built from column k of the projected eigenvectors
+fn ritz_vector(basis: &[Vec<Complex64>], vectors: &nalgebra::DMatrix<f64>, k: usize) -> Vec<Complex64> {
+    let mut x = Vec::new();
+    for (j, q) in basis.iter().enumerate().take(vectors.nrows()) {
+        linalg::axpy(&mut x, Complex64::new(vectors[(j, k)], 0.0), q);
+    }
+    x
+}
+
+/// Result of the power iteration
+#[derive(Debug, Clone)]
+pub struct PowerIterationResult {
+    pub eigenvalue: Complex64,
+    pub eigenvector: Vec<Complex64>,
+    /// Rayleigh quotient after every iteration
+    pub history: Vec<Complex64>,
+    pub residual: f64,
+    pub iterations: usize,
+    pub converged: bool,
+}
+
+/// Power iteration for the dominant eigenvalue
+///
+/// With a finite `max_n` (a count, `Some(n)` or `Size::Finite(n)`) the iterates are
+/// cut to their first n entries, i.e. the iteration runs on the n×n compression of
+/// the operator. With `None` or `INFINITY` the support grows with each product,
+/// which is exact for banded operators. Stops when the residual ‖Ax − θx‖ falls
+/// below `tol · |θ|`.
+pub fn power_iteration<M: MatVec + ?Sized>(
+    op: &M,
+    start: &[Complex64],
+    max_n: impl Into<Size>,
+    tol: f64,
+    max_iter: usize,
+) -> PowerIterationResult {
+    let max_n = max_n.into();
+    let truncate = |x: &mut Vec<Complex64>| {
+        if let Size::Finite(n) = max_n {
+            x.truncate(n);
+        }
+    };
+    let mut x = start.to_vec();
+    truncate(&mut x);
+    if linalg::normalize(&mut x) == 0.0 {
+        x = vec![Complex64::new(1.0, 0.0)];
+    }
+    
+    let mut history = Vec::new();
+    let mut eigenvalue = Complex64::new(0.0, 0.0);
+    let mut residual = f64::INFINITY;
+    let mut converged = false;
+    
+    for _ in 0..max_iter {
+        let mut y = op.matvec(&x);
+        truncate(&mut y);
+        eigenvalue = linalg::dot(&x, &y);
+        history.push(eigenvalue);
+        
+        let mut r = y.clone();
+        linalg::axpy(&mut r, -eigenvalue, &x);
+        residual = linalg::norm(&r);
+        if residual <= tol * eigenvalue.norm() {
+            converged = true;
+            break;
+        }
+        if linalg::normalize(&mut y) == 0.0 {
+            break;
+        }
+        x = y;
+    }
+    
+    PowerIterationResult {
+        eigenvalue,
+        eigenvector: x,
+        iterations: history.len(),
+        history,
+        residual,
+        converged,
+    }
+}
+
+/// Largest section size used by [`refine_eigenpair`]
+const MAX_REFINEMENT_N: usize = 4096;
+
+/// An eigenpair polished by Rayleigh quotient iteration
+#[derive(Debug, Clone)]
+pub struct RefinedEigenpair {
+    pub eigenvalue: Complex64,
+    pub eigenvector: Vec<Complex64>,
+    /// ‖Ax − λx‖ for the returned pair
+    pub residual: f64,
+    pub iterations: usize,
+    /// Size of the final section
+    pub n: usize,
+    pub converged: bool,
+}
+
+/// Polish an approximate eigenpair (for example from a coarse IQR pass)
+///
+/// Each Rayleigh quotient step solves (Aₙ − θ) y = x on the current section; the
+/// section doubles whenever the eigenvector has visible weight in its last quarter.
+/// Iteration stops when ‖Ax − θx‖ ≤ `tol · max(1, |θ|)`, the residual being
+/// evaluated with the exact product for banded operators.
+pub fn refine_eigenpair(
+    operator: &InfiniteOperator,
+    lambda0: Complex64,
+    v0: &[Complex64],
+    tol: f64,
+    max_iter: usize,
+) -> RefinedEigenpair {
+    let mut n = (2 * v0.len()).max(16);
+    let mut x = v0.to_vec();
+    if linalg::normalize(&mut x) == 0.0 {
+        x = vec![Complex64::new(1.0, 0.0)];
+    }
+    let mut theta = lambda0;
+    let mut residual = f64::INFINITY;
+    let mut iterations = 0;
+    let mut converged = false;
+    
+    while iterations < max_iter {
+        iterations += 1;
+        let truncation = operator.get_truncation(n);
+        let (lower, upper) = operator.bandwidths().map_or((n, n), |(lower, upper)| (lower.min(n), upper.min(n)));
+        // An exact eigenvalue θ of the section makes it singular; nudge θ off it as
+        // ShiftInvert::solve does, which leaves y dominated by the eigenvector
+        let mut solution = None;
+        for nudges in 0..=MAX_SHIFT_NUDGES {
+            let shift = if nudges == 0 {
+                theta
+            } else {
+                theta + SHIFT_NUDGE * 10f64.powi(nudges - 1) * theta.norm().max(1.0)
+            };
+            let mut section = truncation.clone();
+            for i in 0..n {
+                section[(i, i)] -= shift;
+            }
+            let mut rhs = vec![Complex64::new(0.0, 0.0); n];
+            for (r, &v) in rhs.iter_mut().zip(&x) {
+                *r = v;
+            }
+            solution = linalg::banded_solve(section, rhs, lower, upper);
+            if solution.is_some() {
+                break;
+            }
+        }
+        let Some(mut y) = solution else {
+            break;
+        };
+        if linalg::normalize(&mut y) == 0.0 || y.iter().any(|v| !v.is_finite()) {
+            break;
+        }
+        
+        let ay = operator.matvec(&y);
+        theta = linalg::dot(&y, &ay);
+        let mut r = ay;
+        linalg::axpy(&mut r, -theta, &y);
+        residual = linalg::norm(&r);
+        x = y;
+        
+        let tail = linalg::norm(&x[3 * n / 4..]);
+        if tail > tol && n < MAX_REFINEMENT_N {
+            n = (2 * n).min(MAX_REFINEMENT_N);
+            continue;
+        }
+        if residual <= tol * theta.norm().max(1.0) {
+            converged = true;
+            break;
+        }
+    }
+    
+    RefinedEigenpair {
+        eigenvalue: theta,
+        eigenvector: x,
+        residual,
+        iterations,
+        n,
+        converged,
+    }
+}
+
+/// Result of subspace iteration
+#[derive(Debug, Clone)]
+pub struct SubspaceIterationResult {
+    /// Largest magnitude first
+    pub eigenvalues: Vec<Complex64>,
+    pub eigenvectors: Vec<Vec<Complex64>>,
+    pub residuals: Vec<f64>,
+    pub iterations: usize,
+    pub converged: bool,
+}
+
+impl SubspaceIterationResult {
+    /// Reorder the eigenpairs, keeping values, vectors and residuals together
+    pub fn sort(&mut self, by: SortBy) {
+        let order = by.order(&self.eigenvalues);
+        self.eigenvalues = permute(&self.eigenvalues, &order);
+        self.eigenvectors = permute(&self.eigenvectors, &order);
+        self.residuals = permute(&self.residuals, &order);
+    }
+}
+
+/// Block (orthogonal) subspace iteration for the k dominant eigenvalues
+///
+/// Iterates a block of k plus a few guard vectors with Rayleigh–Ritz extraction.
+/// Leading Ritz pairs are locked (deflated) as soon as their residual drops below
+/// `tol · max(1, |θ|)`, after which only the remaining vectors are multiplied.
+/// `max_n` truncates the iterates as in [`power_iteration`].
+pub fn subspace_iteration<M: MatVec + ?Sized>(
+    op: &M,
+    k: usize,
+    max_n: impl Into<Size>,
+    tol: f64,
+    max_iter: usize,
+) -> SubspaceIterationResult {
+    let max_n = max_n.into();
+    let apply = |x: &[Complex64]| {
+        let mut y = op.matvec(x);
+        if let Size::Finite(n) = max_n {
+            y.truncate(n);
+        }
+        y
+    };
+    let block = k + (k / 2).max(2);
+    let start_len = max_n.min_count(2 * block);
+    let mut active: Vec<Vec<Complex64>> = (0..block)
+        .map(|j| {
+            (0..start_len)
+                .map(|i| Complex64::new(((i + 1) as f64 * (j + 1) as f64).cos(), 0.0))
+                .collect()
+        })
+        .collect();
+    let mut locked: Vec<(Complex64, Vec<Complex64>, f64)> = Vec::new();
+    let mut iterations = 0;
+    
+    while locked.len() < k && iterations < max_iter && !active.is_empty() {
+        iterations += 1;
+        let mut w: Vec<Vec<Complex64>> = active.iter().map(|v| apply(v)).collect();
+        w = orthonormalize(w, locked.iter().map(|(_, v, _)| v.as_slice()));
+        if w.is_empty() {
+            break;
+        }
+        
+        let aw: Vec<Vec<Complex64>> = w.iter().map(|v| apply(v)).collect();
+        let m = w.len();
+        let projected = ndarray::Array2::from_shape_fn((m, m), |(i, j)| linalg::dot(&w[i], &aw[j]));
+        let (values, vectors) = linalg::eigenpairs(&projected);
+        let mut order: Vec<usize> = (0..m).collect();
+        order.sort_by(|&a, &b| values[b].norm().total_cmp(&values[a].norm()));
+        
+        let mut ritz = Vec::with_capacity(m);
+        for &c in &order {
+            let (mut x, mut ax) = (Vec::new(), Vec::new());
+            for j in 0..m {
+                linalg::axpy(&mut x, vectors[(j, c)], &w[j]);
+                linalg::axpy(&mut ax, vectors[(j, c)], &aw[j]);
+            }
+            linalg::axpy(&mut ax, -values[c], &x);
+            ritz.push((values[c], x, linalg::norm(&ax)));
+        }
+        
+        let mut remaining = ritz.into_iter().peekable();
+        while let Some((theta, _, residual)) = remaining.peek() {
+            if locked.len() >= k || *residual > tol * theta.norm().max(1.0) {
+                break;
+            }
+            locked.push(remaining.next().expect("peeked"));
+        }
+        active = remaining.map(|(_, x, _)| x).collect();
+    }
+    
+    let converged = locked.len() >= k;
+    if !converged {
+        // Report the best current estimates for the missing pairs
+        let missing = k - locked.len();
+        for x in active.into_iter().take(missing) {
+            let ax = apply(&x);
+            let theta = linalg::dot(&x, &ax);
+            let mut r = ax;
+            linalg::axpy(&mut r, -theta, &x);
+            locked.push((theta, x, linalg::norm(&r)));
+        }
+    }
+    locked.sort_by(|a, b| b.0.norm().total_cmp(&a.0.norm()));
+    
+    SubspaceIterationResult {
+        eigenvalues: locked.iter().map(|(theta, _, _)| *theta).collect(),
+        residuals: locked.iter().map(|(_, _, r)| *r).collect(),
+        eigenvectors: locked.into_iter().map(|(_, x, _)| x).collect(),
+        iterations,
+        converged,
+    }
+}
+
+/// Orthonormalize vectors against a fixed orthonormal set and each other
+///
+/// Uses modified Gram–Schmidt applied twice and drops vectors that become
+/// numerically dependent.
+fn orthonormalize<'a>(
+    vectors: Vec<Vec<Complex64>>,
+    fixed: impl Iterator<Item = &'a [Complex64]> + Clone,
+) -> Vec<Vec<Complex64>> {
+    let mut basis: Vec<Vec<Complex64>> = Vec::with_capacity(vectors.len());
+    for mut v in vectors {
+        let original = linalg::norm(&v);
+        for _ in 0..2 {
+            for q in fixed.clone() {
+                let h = linalg::dot(q, &v);
+                linalg::axpy(&mut v, -h, q);
+            }
+            for q in &basis {
+                let h = linalg::dot(q, &v);
+                linalg::axpy(&mut v, -h, q);
+            }
+        }
+        if linalg::normalize(&mut v) > 1e-12 * original {
+            basis.push(v);
+        }
+    }
+    basis
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::iqr::create_diagonal_operator;
+    use crate::test_fixtures::{laplacian, real};
+
+    #[test]
+    fn refine_eigenpair_accepts_an_exact_eigenvalue() {
+        let operator = create_diagonal_operator(|i| Complex64::new((i + 1) as f64, 0.0));
+        let refined = refine_eigenpair(&operator, Complex64::new(1.0, 0.0), &[Complex64::new(1.0, 0.0)], 1e-12, 10);
+        assert!(refined.converged);
+        assert!((refined.eigenvalue - 1.0).norm() < 1e-12);
+        assert!(refined.residual < 1e-12);
+    }
+
+    #[test]
+    fn refine_eigenpair_from_an_exact_eigenvalue_finds_its_eigenvector() {
+        // θ = 2 makes every section singular, and x is not the eigenvector e₁
+        let operator = create_diagonal_operator(|i| Complex64::new((i + 1) as f64, 0.0));
+        let x = [Complex64::new(1.0, 0.0), Complex64::new(1.0, 0.0)];
+        let refined = refine_eigenpair(&operator, Complex64::new(2.0, 0.0), &x, 1e-12, 10);
+        assert!(refined.converged);
+        assert!((refined.eigenvalue - 2.0).norm() < 1e-12);
+        assert!(refined.residual < 1e-12);
+        assert!(refined.eigenvector[1].norm() > 1.0 - 1e-12);
+    }
+
+    #[test]
+    fn shift_invert_at_an_eigenvalue_of_a_section_nudges_the_shift() {
+        let operator = create_diagonal_operator(|i| Complex64::new((i + 1) as f64, 0.0));
+        let shift_invert = ShiftInvert::new(&operator, Complex64::new(2.0, 0.0), 1e-12);
+        let start = vec![Complex64::new(1.0, 0.0); 4];
+        let result = power_iteration(&shift_invert, &start, Size::Infinite, 1e-10, 50);
+        assert!(result.converged);
+        assert!(shift_invert.failure().is_none());
+        assert!((shift_invert.shift() - 2.0).norm() > 0.0);
+        assert!((shift_invert.eigenvalue_of_operator(result.eigenvalue) - 2.0).norm() < 1e-6);
+        // The eigenvector is e₁
+        assert!(result.eigenvector[1].norm() > 1.0 - 1e-8);
+    }
+
+    /// diag(1, 1/2, 1/3, ...)
+    fn harmonic() -> InfiniteOperator {
+        create_diagonal_operator(|i| real(1.0 / (i + 1) as f64))
+    }
+
+    #[test]
+    fn arnoldi_finds_the_largest_eigenvalues() {
+        let start = vec![real(1.0); 20];
+        let result = arnoldi(&harmonic(), &start, 2, 10, 50, 1e-10);
+        assert!(result.converged);
+        assert!((result.eigenvalues[0] - 1.0).norm() < 1e-10);
+        assert!((result.eigenvalues[1] - 0.5).norm() < 1e-10);
+        assert!(result.residuals.iter().take(2).all(|&r| r < 1e-10));
+        assert!(result.eigenvectors[0][0].norm() > 1.0 - 1e-10);
+    }
+
+    #[test]
+    fn lanczos_from_e0_reproduces_the_laplacian_section() {
+        // The Krylov spaces of e₀ are the leading coordinates, so the Jacobi matrix is
+        // the section itself, with eigenvalues −2 + 2 cos(kπ/(m + 1))
+        let laplacian = laplacian();
+        let m = 8;
+        for reorthogonalization in [Reorthogonalization::Full, Reorthogonalization::Selective] {
+            let result = lanczos(&laplacian, &[real(1.0)], m, reorthogonalization);
+            assert!(result.alpha.iter().all(|&a| (a + 2.0).abs() < 1e-12));
+            assert!(result.beta.iter().all(|&b| (b - 1.0).abs() < 1e-12));
+            for (k, &theta) in result.eigenvalues.iter().enumerate() {
+                let exact = -2.0 + 2.0 * ((m - k) as f64 * std::f64::consts::PI / (m + 1) as f64).cos();
+                assert!((theta - exact).abs() < 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    fn power_iteration_finds_the_dominant_eigenvalue() {
+        let start = vec![real(1.0); 4];
+        let result = power_iteration(&harmonic(), &start, None, 1e-10, 200);
+        assert!(result.converged);
+        assert!((result.eigenvalue - 1.0).norm() < 1e-10);
+        assert_eq!(result.history.len(), result.iterations);
+        // A compression that leaves out e₀ finds the next eigenvalue instead
+        let start = [real(0.0), real(1.0), real(1.0)];
+        let result = power_iteration(&harmonic(), &start, 3, 1e-10, 200);
+        assert!((result.eigenvalue - 0.5).norm() < 1e-8);
+    }
+
+    #[test]
+    fn subspace_iteration_finds_the_leading_eigenvalues() {
+        let mut result = subspace_iteration(&harmonic(), 3, 32, 1e-10, 500);
+        assert!(result.converged);
+        result.sort(SortBy::Magnitude);
+        for (k, lambda) in result.eigenvalues.iter().take(3).enumerate() {
+            assert!((lambda - 1.0 / (k + 1) as f64).norm() < 1e-8);
+        }
+    }
+}
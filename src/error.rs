@@ -0,0 +1,139 @@
+//! Error type shared by the fallible entry points of the crate.
+
+use num_complex::Complex64;
+use alloc::format;
+use alloc::string::String;
+use core::fmt;
+use crate::display;
+
+/// Why a computation could not be carried out
+///
+/// Failure to converge is not an error: results report it through their
+/// `converged` flag together with the best estimates found.
+#[derive(Debug, Clone, PartialEq)]
+pub enum InfiniteArraysError {
+    /// An operator entry evaluated to NaN or an infinity
+    NonFiniteElement { row: usize, column: usize, value: Complex64 },
+    /// A truncation of size zero, or no truncation size at all, was requested
+    EmptyTruncation,
+    /// det(Aₙ − λBₙ) vanishes identically, so the pencil has no well-defined eigenvalues
+    SingularPencil,
+    /// A banded factorization or solver was given an operator without bandwidths
+    /// (see `InfiniteOperator::with_bandwidths`)
+    NotBanded,
+    /// A Cholesky factorization met a nonpositive pivot
+    NotPositiveDefinite { pivot: usize, value: f64 },
+    /// The n×n section of `A − σ` is singular, i.e. σ is one of its eigenvalues
+    SingularSection { n: usize },
+    /// A right-hand side has more entries than the section a factorization was taken from
+    RightHandSideTooLong { length: usize, section: usize },
+    /// A rectangular section with fewer rows than columns
+    InvalidSectionShape { rows: usize, columns: usize },
+}
+
+impl fmt::Display for InfiniteArraysError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InfiniteArraysError::NonFiniteElement { row, column, value } => {
+                write!(f, "operator entry ({}, {}) is not finite: {}", row, column, value)
+            }
+            InfiniteArraysError::EmptyTruncation => write!(f, "truncation size must be positive"),
+            InfiniteArraysError::SingularPencil => {
+                let lambda = display::lambda();
+                write!(f, "the pencil A - {} B is singular for every {}", lambda, lambda)
+            }
+            InfiniteArraysError::NotBanded => write!(f, "operator has no bandwidths; see InfiniteOperator::with_bandwidths"),
+            InfiniteArraysError::NotPositiveDefinite { pivot, value } => {
+                write!(f, "operator is not positive definite (pivot {} is {})", pivot, value)
+            }
+            InfiniteArraysError::SingularSection { n } => write!(f, "the {}{}{} section is singular", n, display::times(), n),
+            InfiniteArraysError::RightHandSideTooLong { length, section } => {
+                write!(f, "right-hand side of length {} exceeds the section size {}", length, section)
+            }
+            InfiniteArraysError::InvalidSectionShape { rows, columns } => {
+                write!(f, "a {}{}{} section has fewer rows than columns", rows, display::times(), columns)
+            }
+        }
+    }
+}
+
+impl core::error::Error for InfiniteArraysError {}
+
+/// A descriptor that cannot be turned into an array or operator
+#[cfg(feature = "serde")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DescriptorError {
+    /// A `sequence` name missing from the [`crate::descriptor::SequenceRegistry`]
+    UnknownSequence(String),
+    /// A `cyclic` array with an empty period
+    EmptyPeriod,
+    /// A banded operator with the same diagonal offset listed twice
+    DuplicateDiagonal(isize),
+}
+
+#[cfg(feature = "serde")]
+impl fmt::Display for DescriptorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DescriptorError::UnknownSequence(name) => write!(f, "unknown sequence {:?}", name),
+            DescriptorError::EmptyPeriod => write!(f, "a cyclic array needs a non-empty period"),
+            DescriptorError::DuplicateDiagonal(offset) => write!(f, "diagonal {} is given twice", offset),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl core::error::Error for DescriptorError {}
+
+/// A value looked up in a range that does not contain it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RangeError {
+    /// The value that was looked up
+    pub value: i128,
+    /// The range, as printed by its `Debug` impl
+    pub range: String,
+}
+
+impl RangeError {
+    pub(crate) fn new(value: impl Into<i128>, range: &impl fmt::Debug) -> Self {
+        RangeError {
+            value: value.into(),
+            range: format!("{:?}", range),
+        }
+    }
+}
+
+impl fmt::Display for RangeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} not in {}", self.value, self.range)
+    }
+}
+
+impl core::error::Error for RangeError {}
+
+/// An infinite size converted to a count
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InfiniteSizeError;
+
+impl fmt::Display for InfiniteSizeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "infinite size has no usize value; truncate it to a finite length first")
+    }
+}
+
+impl core::error::Error for InfiniteSizeError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn display_is_plain_ascii_under_ascii() {
+        let singular = InfiniteArraysError::SingularSection { n: 4 };
+        assert_eq!(singular.to_string(), "the 4×4 section is singular");
+        assert_eq!(display::Ascii(&singular).to_string(), "the 4x4 section is singular");
+        let pencil = display::Ascii(&InfiniteArraysError::SingularPencil).to_string();
+        assert_eq!(pencil, "the pencil A - lambda B is singular for every lambda");
+    }
+}
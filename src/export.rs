@@ -0,0 +1,433 @@
+//! Writers for NumPy `.npy` / `.npz` and MATLAB v5 `.mat` files.
+//!
+//! Truncations from [`crate::iqr::InfiniteOperator::get_truncation`] and finite
+//! prefixes of infinite arrays can be written out and loaded with `numpy.load` or
+//! `scipy.io.loadmat` to cross-check results against reference implementations.
+//! The formats are simple enough to write directly: `.npz` archives are stored
+//! uncompressed and neither format uses 64-bit extensions, so archives and
+//! variables are limited to 4 GiB.
+
+use crate::arrays::InfiniteArray;
+use ndarray::{Array1, ArrayBase, Data, Dimension};
+use num_complex::Complex64;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+/// Element types that can be exported
+pub trait ExportElement: Copy {
+    /// NumPy dtype descriptor of the little-endian encoding
+    const NPY_DESCR: &'static str;
+    /// Whether the element has an imaginary part
+    const COMPLEX: bool;
+    fn re(self) -> f64;
+    fn im(self) -> f64;
+}
+
+impl ExportElement for f64 {
+    const NPY_DESCR: &'static str = "<f8";
+    const COMPLEX: bool = false;
+    fn re(self) -> f64 {
+        self
+    }
+    fn im(self) -> f64 {
+        0.0
+    }
+}
+
+impl ExportElement for Complex64 {
+    const NPY_DESCR: &'static str = "<c16";
+    const COMPLEX: bool = true;
+    fn re(self) -> f64 {
+        self.re
+    }
+    fn im(self) -> f64 {
+        self.im
+    }
+}
+
+/// First `n` entries of an infinite array
+pub fn prefix(array: &dyn InfiniteArray, n: usize) -> Array1<f64> {
+    Array1::from_iter((0..n).map(|i| array.get(i)))
+}
+
+/// Write an array in NumPy `.npy` format (version 1.0, C order)
+pub fn write_npy_to<W, T, S, D>(mut writer: W, array: &ArrayBase<S, D>) -> io::Result<()>
+where
+    W: Write,
+    T: ExportElement,
+    S: Data<Elem = T>,
+    D: Dimension,
+{
+    let shape = match array.shape() {
+        [n] => format!("({},)", n),
+        dims => format!("({})", dims.iter().map(|d| d.to_string()).collect::<Vec<_>>().join(", ")),
+    };
+    let mut header = format!("{{'descr': '{}', 'fortran_order': False, 'shape': {}, }}", T::NPY_DESCR, shape);
+    // Magic, version and header length take 10 bytes; the header is padded with
+    // spaces and a newline so the data starts on a 64-byte boundary
+    let padding = 63 - (10 + header.len()) % 64;
+    header.extend(std::iter::repeat(' ').take(padding));
+    header.push('\n');
+    let header_len = u16::try_from(header.len()).map_err(|_| invalid_input("array has too many dimensions"))?;
+
+    writer.write_all(b"\x93NUMPY\x01\x00")?;
+    writer.write_all(&header_len.to_le_bytes())?;
+    writer.write_all(header.as_bytes())?;
+    for &value in array.iter() {
+        writer.write_all(&value.re().to_le_bytes())?;
+        if T::COMPLEX {
+            writer.write_all(&value.im().to_le_bytes())?;
+        }
+    }
+    writer.flush()
+}
+
+/// [`write_npy_to`] into a file, replacing it if it exists
+pub fn write_npy<T, S, D>(path: impl AsRef<Path>, array: &ArrayBase<S, D>) -> io::Result<()>
+where
+    T: ExportElement,
+    S: Data<Elem = T>,
+    D: Dimension,
+{
+    write_npy_to(BufWriter::new(File::create(path)?), array)
+}
+
+/// Writer for NumPy `.npz` archives holding several named arrays
+///
+/// Each array becomes an uncompressed `<name>.npy` member; call
+/// [`NpzWriter::finish`] to write the archive directory.
+pub struct NpzWriter<W: Write> {
+    writer: W,
+    offset: u64,
+    directory: Vec<u8>,
+    entries: u16,
+}
+
+impl NpzWriter<BufWriter<File>> {
+    /// Create an archive file, replacing it if it exists
+    pub fn create(path: impl AsRef<Path>) -> io::Result<Self> {
+        Ok(Self::new(BufWriter::new(File::create(path)?)))
+    }
+}
+
+impl<W: Write> NpzWriter<W> {
+    pub fn new(writer: W) -> Self {
+        NpzWriter {
+            writer,
+            offset: 0,
+            directory: Vec::new(),
+            entries: 0,
+        }
+    }
+
+    /// Add an array, loaded back under `name` by `numpy.load`
+    pub fn add<T, S, D>(&mut self, name: &str, array: &ArrayBase<S, D>) -> io::Result<()>
+    where
+        T: ExportElement,
+        S: Data<Elem = T>,
+        D: Dimension,
+    {
+        let mut data = Vec::new();
+        write_npy_to(&mut data, array)?;
+        let file_name = format!("{}.npy", name);
+        let name_len = u16::try_from(file_name.len()).map_err(|_| invalid_input("array name is too long"))?;
+        let size = u32::try_from(data.len()).map_err(|_| invalid_input("array exceeds 4 GiB"))?;
+        let offset = u32::try_from(self.offset).map_err(|_| invalid_input("archive exceeds 4 GiB"))?;
+        self.entries = self.entries.checked_add(1).ok_or_else(|| invalid_input("too many arrays"))?;
+        let crc = crc32(&data);
+
+        // Fields shared by the local header and the directory entry: version needed,
+        // flags, method (stored), time, date (1980-01-01), CRC, sizes, name length
+        let mut common = Vec::with_capacity(26);
+        for field in [20u16, 0, 0, 0, 0x21] {
+            common.extend_from_slice(&field.to_le_bytes());
+        }
+        for field in [crc, size, size] {
+            common.extend_from_slice(&field.to_le_bytes());
+        }
+        common.extend_from_slice(&name_len.to_le_bytes());
+
+        self.writer.write_all(&0x0403_4b50u32.to_le_bytes())?;
+        self.writer.write_all(&common)?;
+        self.writer.write_all(&0u16.to_le_bytes())?;
+        self.writer.write_all(file_name.as_bytes())?;
+        self.writer.write_all(&data)?;
+        self.offset += 30 + file_name.len() as u64 + data.len() as u64;
+
+        self.directory.extend_from_slice(&0x0201_4b50u32.to_le_bytes());
+        self.directory.extend_from_slice(&20u16.to_le_bytes());
+        self.directory.extend_from_slice(&common);
+        // Extra field and comment lengths, disk number, internal and external attributes
+        self.directory.extend_from_slice(&[0; 12]);
+        self.directory.extend_from_slice(&offset.to_le_bytes());
+        self.directory.extend_from_slice(file_name.as_bytes());
+        Ok(())
+    }
+
+    /// Write the archive directory and return the underlying writer
+    pub fn finish(mut self) -> io::Result<W> {
+        let directory_offset = u32::try_from(self.offset).map_err(|_| invalid_input("archive exceeds 4 GiB"))?;
+        let directory_size = u32::try_from(self.directory.len()).map_err(|_| invalid_input("archive exceeds 4 GiB"))?;
+        self.writer.write_all(&self.directory)?;
+        self.writer.write_all(&0x0605_4b50u32.to_le_bytes())?;
+        self.writer.write_all(&[0; 4])?;
+        self.writer.write_all(&self.entries.to_le_bytes())?;
+        self.writer.write_all(&self.entries.to_le_bytes())?;
+        self.writer.write_all(&directory_size.to_le_bytes())?;
+        self.writer.write_all(&directory_offset.to_le_bytes())?;
+        self.writer.write_all(&0u16.to_le_bytes())?;
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+/// Writer for MATLAB v5 `.mat` files holding several named double arrays
+///
+/// One-dimensional arrays are stored as row vectors, as `scipy.io.savemat` does.
+pub struct MatWriter<W: Write> {
+    writer: W,
+}
+
+const MI_INT8: u32 = 1;
+const MI_INT32: u32 = 5;
+const MI_UINT32: u32 = 6;
+const MI_DOUBLE: u32 = 9;
+const MI_MATRIX: u32 = 14;
+const MX_DOUBLE_CLASS: u32 = 6;
+const MAT_COMPLEX_FLAG: u32 = 0x0800;
+
+impl MatWriter<BufWriter<File>> {
+    /// Create a `.mat` file, replacing it if it exists
+    pub fn create(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::new(BufWriter::new(File::create(path)?))
+    }
+}
+
+impl<W: Write> MatWriter<W> {
+    /// Start a file by writing the 128-byte header
+    pub fn new(mut writer: W) -> io::Result<Self> {
+        let mut text = format!(
+            "MATLAB 5.0 MAT-file, Platform: {}, Created by: infinite-arrays {}",
+            std::env::consts::OS,
+            env!("CARGO_PKG_VERSION")
+        )
+        .into_bytes();
+        text.resize(116, b' ');
+        writer.write_all(&text)?;
+        writer.write_all(&[0; 8])?;
+        writer.write_all(&0x0100u16.to_le_bytes())?;
+        writer.write_all(b"IM")?;
+        Ok(MatWriter { writer })
+    }
+
+    /// Add a variable; `name` must be a valid MATLAB identifier
+    pub fn add<T, S, D>(&mut self, name: &str, array: &ArrayBase<S, D>) -> io::Result<()>
+    where
+        T: ExportElement,
+        S: Data<Elem = T>,
+        D: Dimension,
+    {
+        let valid_name = name.len() <= 63
+            && name.starts_with(|c: char| c.is_ascii_alphabetic())
+            && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !valid_name {
+            return Err(invalid_input("not a valid MATLAB variable name"));
+        }
+        let dims: Vec<usize> = match array.shape() {
+            [] => vec![1, 1],
+            &[n] => vec![1, n],
+            dims => dims.to_vec(),
+        };
+        let dims = dims
+            .into_iter()
+            .map(i32::try_from)
+            .collect::<Result<Vec<i32>, _>>()
+            .map_err(|_| invalid_input("array dimension exceeds the MAT-file limit"))?;
+
+        let mut body = Vec::new();
+        let flags = MX_DOUBLE_CLASS | if T::COMPLEX { MAT_COMPLEX_FLAG } else { 0 };
+        push_element(&mut body, MI_UINT32, &[flags.to_le_bytes(), [0; 4]].concat());
+        let dim_bytes: Vec<u8> = dims.iter().flat_map(|d| d.to_le_bytes()).collect();
+        push_element(&mut body, MI_INT32, &dim_bytes);
+        push_element(&mut body, MI_INT8, name.as_bytes());
+        // MATLAB stores arrays in column-major order, which is the C order of the
+        // array with its axes reversed
+        let values = array.view().reversed_axes();
+        let real: Vec<u8> = values.iter().flat_map(|v| v.re().to_le_bytes()).collect();
+        push_element(&mut body, MI_DOUBLE, &real);
+        if T::COMPLEX {
+            let imaginary: Vec<u8> = values.iter().flat_map(|v| v.im().to_le_bytes()).collect();
+            push_element(&mut body, MI_DOUBLE, &imaginary);
+        }
+
+        let size = u32::try_from(body.len()).map_err(|_| invalid_input("variable exceeds 4 GiB"))?;
+        self.writer.write_all(&MI_MATRIX.to_le_bytes())?;
+        self.writer.write_all(&size.to_le_bytes())?;
+        self.writer.write_all(&body)
+    }
+
+    /// Flush the file and return the underlying writer
+    pub fn finish(mut self) -> io::Result<W> {
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+/// Append a MAT-file data element: type, byte count, data padded to 8 bytes
+fn push_element(out: &mut Vec<u8>, data_type: u32, data: &[u8]) {
+    out.extend_from_slice(&data_type.to_le_bytes());
+    out.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    out.extend_from_slice(data);
+    out.resize(out.len().next_multiple_of(8), 0);
+}
+
+fn invalid_input(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, message.to_string())
+}
+
+/// Lookup table of the reflected CRC-32 polynomial used by zip archives
+const CRC_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 { 0xedb8_8320 ^ (crc >> 1) } else { crc >> 1 };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+fn crc32(data: &[u8]) -> u32 {
+    !data
+        .iter()
+        .fold(!0u32, |crc, &byte| CRC_TABLE[((crc ^ byte as u32) & 0xff) as usize] ^ (crc >> 8))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ndarray::array;
+
+    fn u16_at(bytes: &[u8], offset: usize) -> u16 {
+        u16::from_le_bytes(bytes[offset..offset + 2].try_into().unwrap())
+    }
+
+    fn u32_at(bytes: &[u8], offset: usize) -> u32 {
+        u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+    }
+
+    fn f64_at(bytes: &[u8], offset: usize) -> f64 {
+        f64::from_le_bytes(bytes[offset..offset + 8].try_into().unwrap())
+    }
+
+    #[test]
+    fn crc32_matches_the_check_value() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+        assert_eq!(crc32(b""), 0);
+    }
+
+    #[test]
+    fn npy_header_is_padded_to_64_bytes() {
+        let mut bytes = Vec::new();
+        write_npy_to(&mut bytes, &array![1.0, 2.0]).unwrap();
+        assert_eq!(&bytes[..8], b"\x93NUMPY\x01\x00");
+        let header_len = u16_at(&bytes, 8) as usize;
+        assert_eq!((10 + header_len) % 64, 0);
+        assert_eq!(header_len, 118);
+        let dict = "{'descr': '<f8', 'fortran_order': False, 'shape': (2,), }";
+        let header = std::str::from_utf8(&bytes[10..10 + header_len]).unwrap();
+        assert_eq!(header, format!("{:<117}\n", dict));
+        assert_eq!(bytes.len(), 10 + header_len + 16);
+        assert_eq!((f64_at(&bytes, 128), f64_at(&bytes, 136)), (1.0, 2.0));
+
+        let mut bytes = Vec::new();
+        write_npy_to(&mut bytes, &array![[Complex64::new(1.0, 2.0)]]).unwrap();
+        let header_len = u16_at(&bytes, 8) as usize;
+        assert_eq!((10 + header_len) % 64, 0);
+        let header = std::str::from_utf8(&bytes[10..10 + header_len]).unwrap();
+        assert!(header.starts_with("{'descr': '<c16', 'fortran_order': False, 'shape': (1, 1), }"));
+        assert_eq!((f64_at(&bytes, 10 + header_len), f64_at(&bytes, 18 + header_len)), (1.0, 2.0));
+    }
+
+    #[test]
+    fn npz_headers_describe_the_stored_member() {
+        let x = array![1.0, 2.0];
+        let mut data = Vec::new();
+        write_npy_to(&mut data, &x).unwrap();
+        let mut npz = NpzWriter::new(Vec::new());
+        npz.add("x", &x).unwrap();
+        let bytes = npz.finish().unwrap();
+
+        // Local header, then the name and the stored .npy data
+        assert_eq!(u32_at(&bytes, 0), 0x0403_4b50);
+        assert_eq!((u16_at(&bytes, 4), u16_at(&bytes, 6), u16_at(&bytes, 8)), (20, 0, 0));
+        assert_eq!(u16_at(&bytes, 12), 0x21);
+        assert_eq!(u32_at(&bytes, 14), crc32(&data));
+        assert_eq!((u32_at(&bytes, 18), u32_at(&bytes, 22)), (data.len() as u32, data.len() as u32));
+        assert_eq!((u16_at(&bytes, 26), u16_at(&bytes, 28)), (5, 0));
+        assert_eq!(&bytes[30..35], b"x.npy");
+        assert_eq!(&bytes[35..35 + data.len()], &data[..]);
+
+        // Central directory entry pointing back at the local header
+        let central = 35 + data.len();
+        assert_eq!(u32_at(&bytes, central), 0x0201_4b50);
+        assert_eq!((u16_at(&bytes, central + 4), u16_at(&bytes, central + 6)), (20, 20));
+        assert_eq!(u16_at(&bytes, central + 10), 0);
+        assert_eq!(u32_at(&bytes, central + 16), crc32(&data));
+        assert_eq!(u32_at(&bytes, central + 24), data.len() as u32);
+        assert_eq!(u16_at(&bytes, central + 28), 5);
+        assert_eq!(u32_at(&bytes, central + 42), 0);
+        assert_eq!(&bytes[central + 46..central + 51], b"x.npy");
+
+        // End of central directory record
+        let end = central + 51;
+        assert_eq!(u32_at(&bytes, end), 0x0605_4b50);
+        assert_eq!((u16_at(&bytes, end + 8), u16_at(&bytes, end + 10)), (1, 1));
+        assert_eq!((u32_at(&bytes, end + 12), u32_at(&bytes, end + 16)), (51, central as u32));
+        assert_eq!(u16_at(&bytes, end + 20), 0);
+        assert_eq!(bytes.len(), end + 22);
+    }
+
+    #[test]
+    fn mat_file_stores_a_complex_row_vector() {
+        let mut mat = MatWriter::new(Vec::new()).unwrap();
+        mat.add("z", &array![Complex64::new(1.0, 2.0), Complex64::new(3.0, 4.0)]).unwrap();
+        let bytes = mat.finish().unwrap();
+
+        assert!(bytes.starts_with(b"MATLAB 5.0 MAT-file"));
+        assert_eq!(&bytes[116..124], &[0; 8]);
+        assert_eq!(u16_at(&bytes, 124), 0x0100);
+        assert_eq!(&bytes[126..128], b"IM");
+
+        assert_eq!((u32_at(&bytes, 128), u32_at(&bytes, 132)), (MI_MATRIX, 96));
+        let body = &bytes[136..];
+        assert_eq!(body.len(), 96);
+        // Array flags: double class with the complex bit
+        assert_eq!((u32_at(body, 0), u32_at(body, 4)), (MI_UINT32, 8));
+        assert_eq!((u32_at(body, 8), u32_at(body, 12)), (MX_DOUBLE_CLASS | MAT_COMPLEX_FLAG, 0));
+        // Dimensions 1×2
+        assert_eq!((u32_at(body, 16), u32_at(body, 20)), (MI_INT32, 8));
+        assert_eq!((u32_at(body, 24), u32_at(body, 28)), (1, 2));
+        // Name, padded to 8 bytes
+        assert_eq!((u32_at(body, 32), u32_at(body, 36)), (MI_INT8, 1));
+        assert_eq!(&body[40..48], b"z\0\0\0\0\0\0\0");
+        // Real, then imaginary parts
+        assert_eq!((u32_at(body, 48), u32_at(body, 52)), (MI_DOUBLE, 16));
+        assert_eq!((f64_at(body, 56), f64_at(body, 64)), (1.0, 3.0));
+        assert_eq!((u32_at(body, 72), u32_at(body, 76)), (MI_DOUBLE, 16));
+        assert_eq!((f64_at(body, 80), f64_at(body, 88)), (2.0, 4.0));
+    }
+
+    #[test]
+    fn mat_variable_names_are_validated() {
+        let mut mat = MatWriter::new(Vec::new()).unwrap();
+        let error = mat.add("1x", &array![1.0]).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+    }
+}
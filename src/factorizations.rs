@@ -0,0 +1,1215 @@
+//! Adaptive factorizations of banded infinite operators.
+//!
+//! A banded operator can be factored one column at a time: the k-th column only
+//! interacts with finitely many rows, so the leading part of the factors is exact
+//! and can be extended on demand. Solving A x = b then proceeds until the part of
+//! the transformed right-hand side that the factored columns cannot reach drops
+//! below the tolerance (Olver & Townsend, SIAM Review 55, 2013).
+
+use crate::arrays::Shape;
+use crate::broadcasting::BroadcastArray;
+use crate::cache::CachedArray;
+use crate::dims::{Dims, Finite, Inf};
+use crate::error::InfiniteArraysError;
+use crate::infinity::Size;
+use crate::iqr::InfiniteOperator;
+use crate::linalg;
+use crate::sync::Lock;
+use ndarray::Array2;
+use num_complex::Complex64;
+use std::borrow::Cow;
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::sync::Arc;
+
+/// Largest number of columns an adaptive solve will factor
+const MAX_ADAPTIVE_N: usize = 1 << 20;
+
+/// Rows of a banded matrix, each stored with its own column offset
+///
+/// Row i of the operator is loaded over the window i − below ..= i + above; rows
+/// grow when written outside their window and can be swapped wholesale, which
+/// pivoting needs.
+#[derive(Clone)]
+struct BandedRows {
+    rows: Vec<(usize, Vec<Complex64>)>,
+    below: usize,
+    above: usize,
+}
+
+impl BandedRows {
+    fn new(below: usize, above: usize) -> Self {
+        BandedRows {
+            rows: Vec::new(),
+            below,
+            above,
+        }
+    }
+    
+    /// Load rows of `A − shift` up to and including row i
+    fn load_through(&mut self, operator: &InfiniteOperator, shift: Complex64, i: usize) {
+        while self.rows.len() <= i {
+            let row = self.rows.len();
+            let offset = row.saturating_sub(self.below);
+            let end = row + self.above + 1;
+            let values = (offset..end)
+                .map(|j| if j == row { operator.get(row, j) - shift } else { operator.get(row, j) })
+                .collect();
+            self.rows.push((offset, values));
+        }
+    }
+    
+    fn get(&self, i: usize, j: usize) -> Complex64 {
+        let (offset, values) = &self.rows[i];
+        if j < *offset {
+            return Complex64::new(0.0, 0.0);
+        }
+        values.get(j - offset).copied().unwrap_or(Complex64::new(0.0, 0.0))
+    }
+    
+    fn set(&mut self, i: usize, j: usize, value: Complex64) {
+        let (offset, values) = &mut self.rows[i];
+        if j < *offset {
+            if value.norm() == 0.0 {
+                return;
+            }
+            values.splice(0..0, std::iter::repeat(Complex64::new(0.0, 0.0)).take(*offset - j));
+            *offset = j;
+        }
+        if j - *offset >= values.len() {
+            if value.norm() == 0.0 {
+                return;
+            }
+            values.resize(j - *offset + 1, Complex64::new(0.0, 0.0));
+        }
+        values[j - *offset] = value;
+    }
+    
+    fn swap(&mut self, i: usize, k: usize) {
+        self.rows.swap(i, k);
+    }
+}
+
+/// Adaptive QR factorization A = QR of a banded infinite operator
+///
+/// Columns are factored on demand with Givens rotations. R is upper triangular with
+/// upper bandwidth `lower + upper`, and Q is kept as the list of rotations so that
+/// Qᴴ can be applied to new right-hand sides.
+pub struct AdaptiveQr<'a> {
+    operator: Cow<'a, InfiniteOperator>,
+    lower: usize,
+    upper: usize,
+    rows: BandedRows,
+    /// Rotations applied while factoring each column, as (row pair top, c, s)
+    rotations: Vec<Vec<(usize, f64, Complex64)>>,
+}
+
+impl<'a> AdaptiveQr<'a> {
+    /// Start a factorization; fails with [`InfiniteArraysError::NotBanded`] if the
+    /// operator has no bandwidths
+    pub fn new(operator: &'a InfiniteOperator) -> Result<Self, InfiniteArraysError> {
+        let (lower, upper) = operator.bandwidths().ok_or(InfiniteArraysError::NotBanded)?;
+        Ok(AdaptiveQr {
+            operator: Cow::Borrowed(operator),
+            lower,
+            upper,
+            rows: BandedRows::new(lower, lower + upper),
+            rotations: Vec::new(),
+        })
+    }
+    
+    /// Number of columns factored so far
+    pub fn factored_columns(&self) -> usize {
+        self.rotations.len()
+    }
+    
+    /// Factor columns until at least `n` are done
+    pub fn factor_to(&mut self, n: usize) {
+        while self.rotations.len() < n {
+            self.factor_next_column();
+        }
+    }
+    
+    fn factor_next_column(&mut self) {
+        let k = self.rotations.len();
+        self.rows.load_through(&self.operator, Complex64::new(0.0, 0.0), k + self.lower);
+        let last_col = k + self.lower + self.upper;
+        let mut column_rotations = Vec::with_capacity(self.lower);
+        for i in (k + 1..=k + self.lower).rev() {
+            let b = self.rows.get(i, k);
+            if b.norm() == 0.0 {
+                continue;
+            }
+            let (c, s) = linalg::givens(self.rows.get(i - 1, k), b);
+            for j in k..=last_col {
+                let x = self.rows.get(i - 1, j);
+                let y = self.rows.get(i, j);
+                self.rows.set(i - 1, j, x * c + s * y);
+                self.rows.set(i, j, -s.conj() * x + y * c);
+            }
+            column_rotations.push((i - 1, c, s));
+        }
+        self.rotations.push(column_rotations);
+    }
+    
+    /// Entry R[i, j] of the triangular factor; factors columns as needed
+    pub fn r(&mut self, i: usize, j: usize) -> Complex64 {
+        if j < i || j > i + self.lower + self.upper {
+            return Complex64::new(0.0, 0.0);
+        }
+        self.factor_to(i + 1);
+        self.rows.get(i, j)
+    }
+    
+    /// Solve A x = b for a finitely supported b
+    ///
+    /// Factors columns until the residual of the least-squares problem on the
+    /// factored columns is at most `tol · ‖b‖`, then back-substitutes. If b is not in
+    /// the range of A the residual instead stagnates at its least-squares minimum; the
+    /// solve also stops once it changes by at most `tol · ‖b‖` while the number of
+    /// columns doubles. Fails with [`InfiniteArraysError::SingularSection`] if R has
+    /// a zero diagonal entry among the factored columns.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all, fields(tol)))]
+    pub fn solve(&mut self, b: &[Complex64], tol: f64) -> Result<AdaptiveSolution, InfiniteArraysError> {
+        let b_norm = linalg::norm(b);
+        let mut rhs = b.to_vec();
+        let mut k = 0;
+        let mut residual;
+        let mut checkpoint = (b.len().max(1), f64::INFINITY);
+        loop {
+            self.rotate(&mut rhs, k);
+            residual = linalg::norm(&rhs[k + 1..]);
+            k += 1;
+            if (k >= b.len() && residual <= tol * b_norm) || k >= MAX_ADAPTIVE_N {
+                break;
+            }
+            if k == 2 * checkpoint.0 {
+                if (checkpoint.1 - residual).abs() <= tol * b_norm {
+                    break;
+                }
+                checkpoint = (k, residual);
+            }
+        }
+        debug_event!(columns = k, residual, "adaptive QR solve finished");
+        
+        let coefficients = self.back_substitute(&rhs, k)?;
+        let extension = QrExtension {
+            qr: self.detach(),
+            rhs,
+            coefficients: coefficients.clone(),
+        };
+        Ok(AdaptiveSolution {
+            coefficients,
+            residual,
+            extension: Some(Arc::new(Lock::new(extension))),
+        })
+    }
+    
+    /// Factor column k and apply its rotations to `rhs`, which already carries
+    /// those of columns 0..k
+    fn rotate(&mut self, rhs: &mut Vec<Complex64>, k: usize) {
+        self.factor_to(k + 1);
+        if rhs.len() < k + self.lower + 1 {
+            rhs.resize(k + self.lower + 1, Complex64::new(0.0, 0.0));
+        }
+        for &(top, c, s) in &self.rotations[k] {
+            let (x, y) = (rhs[top], rhs[top + 1]);
+            rhs[top] = x * c + s * y;
+            rhs[top + 1] = -s.conj() * x + y * c;
+        }
+    }
+    
+    /// Solve R x = rhs on the first k (factored) columns
+    fn back_substitute(&self, rhs: &[Complex64], k: usize) -> Result<Vec<Complex64>, InfiniteArraysError> {
+        let bandwidth = self.lower + self.upper;
+        let mut x = vec![Complex64::new(0.0, 0.0); k];
+        for j in (0..k).rev() {
+            let tail: Complex64 = (j + 1..(j + bandwidth + 1).min(k)).map(|m| self.rows.get(j, m) * x[m]).sum();
+            x[j] = (rhs[j] - tail) / checked_pivot(self.rows.get(j, j), k)?;
+        }
+        Ok(x)
+    }
+    
+    /// Copy of the factorization that owns its (shared) operator
+    fn detach(&self) -> AdaptiveQr<'static> {
+        AdaptiveQr {
+            operator: Cow::Owned(InfiniteOperator::clone(&self.operator)),
+            lower: self.lower,
+            upper: self.upper,
+            rows: self.rows.clone(),
+            rotations: self.rotations.clone(),
+        }
+    }
+}
+
+/// State kept by an adaptive QR solution to extend x past its factored columns
+struct QrExtension {
+    qr: AdaptiveQr<'static>,
+    /// Qᴴb through the factored columns
+    rhs: Vec<Complex64>,
+    coefficients: Vec<Complex64>,
+}
+
+impl QrExtension {
+    /// Coefficient i of x, factoring at least i + 1 columns (doubling the count, so
+    /// repeated extensions stay linear) and back-substituting again if needed; zero
+    /// if the further columns make R singular
+    fn coefficient(&mut self, i: usize) -> Complex64 {
+        let k = self.coefficients.len();
+        if i >= k {
+            let columns = (i + 1).max(2 * k);
+            for m in k..columns {
+                self.qr.rotate(&mut self.rhs, m);
+            }
+            match self.qr.back_substitute(&self.rhs, columns) {
+                Ok(coefficients) => self.coefficients = coefficients,
+                Err(_) => return Complex64::new(0.0, 0.0),
+            }
+        }
+        self.coefficients[i]
+    }
+}
+
+/// Solve A x = b for a banded operator with the adaptive QR factorization
+pub fn adaptive_solve(operator: &InfiniteOperator, b: &[Complex64], tol: f64) -> Result<AdaptiveSolution, InfiniteArraysError> {
+    AdaptiveQr::new(operator)?.solve(b, tol)
+}
+
+/// Solution of an adaptive solve
+#[derive(Clone)]
+pub struct AdaptiveSolution {
+    /// Leading coefficients of x, enough to meet the tolerance
+    pub coefficients: Vec<Complex64>,
+    /// Residual of the least-squares problem on the factored columns
+    pub residual: f64,
+    /// Set by the adaptive QR solve, whose factorization can be continued
+    extension: Option<Arc<Lock<QrExtension>>>,
+}
+
+impl AdaptiveSolution {
+    /// Real part of x as a lazily extendable cached array
+    ///
+    /// For solutions of [`adaptive_solve`] and [`AdaptiveQr::solve`], reading past
+    /// `coefficients` factors further columns and back-substitutes again; the new
+    /// entries are at the level of the tolerance. Other solutions are zero there.
+    pub fn real_part(&self) -> CachedArray {
+        self.part(|c| c.re)
+    }
+    
+    /// Imaginary part of x, extended like [`AdaptiveSolution::real_part`]
+    pub fn imag_part(&self) -> CachedArray {
+        self.part(|c| c.im)
+    }
+    
+    fn part(&self, component: fn(Complex64) -> f64) -> CachedArray {
+        let coefficients = self.coefficients.clone();
+        let extension = self.extension.clone();
+        let entry = move |i: usize| match (coefficients.get(i), &extension) {
+            (Some(&c), _) => component(c),
+            (None, Some(extension)) => component(extension.lock().unwrap().coefficient(i)),
+            (None, None) => 0.0,
+        };
+        CachedArray::new(Arc::new(BroadcastArray::new(entry, Shape::OneD(Size::Infinite))))
+    }
+}
+
+impl fmt::Debug for AdaptiveSolution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AdaptiveSolution")
+            .field("coefficients", &self.coefficients)
+            .field("residual", &self.residual)
+            .field("extendable", &self.extension.is_some())
+            .finish()
+    }
+}
+
+/// QL factorization of the n×n section of `A − shift`, computed bottom-up
+///
+/// Returns the rows of L (window i − l − u ..= i + u, zero above the diagonal) and
+/// the rotations in the order they were applied. `n` must be positive.
+fn ql_section(
+    operator: &InfiniteOperator,
+    shift: Complex64,
+    lower: usize,
+    upper: usize,
+    n: usize,
+) -> (BandedRows, Vec<(usize, f64, Complex64)>) {
+    let mut rows = BandedRows::new(lower + upper, upper);
+    rows.load_through(operator, shift, n - 1);
+    let mut rotations = Vec::new();
+    for j in (1..n).rev() {
+        // Zero column j above the diagonal, pushing each entry one row down
+        for i in j.saturating_sub(upper)..j {
+            let a = rows.get(i, j);
+            if a.norm() == 0.0 {
+                continue;
+            }
+            let (c, s) = linalg::givens(rows.get(i + 1, j), a);
+            let first = (i + 1).saturating_sub(lower + upper);
+            for m in first..=j {
+                let x = rows.get(i + 1, m);
+                let y = rows.get(i, m);
+                rows.set(i + 1, m, x * c + s * y);
+                rows.set(i, m, -s.conj() * x + y * c);
+            }
+            rotations.push((i, c, s));
+        }
+    }
+    (rows, rotations)
+}
+
+/// Leading n×n lower triangular block of factored rows
+fn lower_block(rows: &BandedRows, n: usize) -> Array2<Complex64> {
+    Array2::from_shape_fn((n, n), |(i, j)| if j <= i { rows.get(i, j) } else { Complex64::new(0.0, 0.0) })
+}
+
+/// Whether a leading block changed by at most `tol` (relative) since the previous section
+fn block_settled(block: &Array2<Complex64>, previous: &Array2<Complex64>, tol: f64) -> bool {
+    let change = (block - previous).iter().map(|v| v.norm_sqr()).sum::<f64>().sqrt();
+    let scale = block.iter().map(|v| v.norm_sqr()).sum::<f64>().sqrt();
+    change <= tol * scale.max(f64::MIN_POSITIVE)
+}
+
+/// Leading n×n block of the QL factorization of `A − shift`, converged in the section size
+fn converged_ql(
+    operator: &InfiniteOperator,
+    shift: Complex64,
+    n: usize,
+    tol: f64,
+    max_n: usize,
+) -> Result<QlFactorization, InfiniteArraysError> {
+    if n == 0 {
+        return Err(InfiniteArraysError::EmptyTruncation);
+    }
+    let (lower, upper) = operator.bandwidths().ok_or(InfiniteArraysError::NotBanded)?;
+    let mut section = (2 * n).max(n + 4 * (lower + upper + 1)).min(max_n.max(n));
+    let mut previous: Option<Array2<Complex64>> = None;
+    loop {
+        let (rows, rotations) = ql_section(operator, shift, lower, upper, section);
+        let l = lower_block(&rows, n);
+        let converged = previous.as_ref().is_some_and(|p| block_settled(&l, p, tol));
+        if converged || section >= max_n {
+            return Ok(QlFactorization {
+                l,
+                n,
+                section,
+                converged,
+                rotations,
+            });
+        }
+        previous = Some(l);
+        section = (2 * section).min(max_n);
+    }
+}
+
+/// QL factorization A = QL of a banded infinite operator
+///
+/// L is computed "from infinity": QL factorizations of growing sections are taken
+/// until their leading n×n blocks agree to `tol`, which happens when the bottom-up
+/// rotations settle to their fixed point. This is the case e.g. for Toeplitz-plus-compact
+/// operators whose symbol has nonpositive winding number about 0 (Webb & Olver,
+/// Commun. Math. Phys. 2021). `max_n` caps the section size. Fails for an operator
+/// without bandwidths or n = 0.
+pub fn ql(operator: &InfiniteOperator, n: usize, tol: f64, max_n: usize) -> Result<QlFactorization, InfiniteArraysError> {
+    converged_ql(operator, Complex64::new(0.0, 0.0), n, tol, max_n)
+}
+
+/// |L[0, 0]| of the QL factorization of `A − z`
+///
+/// Outside the essential spectrum of a Toeplitz-plus-compact operator the diagonal of
+/// L is bounded away from zero except where `A − z` has a kernel, which shows up in
+/// the top-left entry. Zeros of this function in z are therefore the discrete
+/// eigenvalues.
+pub fn ql_spectral_indicator(
+    operator: &InfiniteOperator,
+    z: Complex64,
+    tol: f64,
+    max_n: usize,
+) -> Result<f64, InfiniteArraysError> {
+    Ok(converged_ql(operator, z, 1, tol, max_n)?.l[(0, 0)].norm())
+}
+
+/// Leading block of an infinite QL factorization
+#[derive(Debug, Clone)]
+pub struct QlFactorization {
+    /// Leading n×n block of the lower triangular factor
+    pub l: Array2<Complex64>,
+    pub n: usize,
+    /// Section size the block was taken from
+    pub section: usize,
+    pub converged: bool,
+    rotations: Vec<(usize, f64, Complex64)>,
+}
+
+impl QlFactorization {
+    /// Diagonal of the leading block of L
+    pub fn diagonal(&self) -> Vec<Complex64> {
+        (0..self.n).map(|i| self.l[(i, i)]).collect()
+    }
+    
+    /// First n coefficients of the solution of A x = b
+    ///
+    /// Applies Qᴴ to b and forward-substitutes with L; b must be supported within the
+    /// section the factorization was taken from. Fails with
+    /// [`InfiniteArraysError::SingularSection`] if L has a zero diagonal entry.
+    pub fn solve(&self, b: &[Complex64]) -> Result<Vec<Complex64>, InfiniteArraysError> {
+        if b.len() > self.section {
+            return Err(InfiniteArraysError::RightHandSideTooLong { length: b.len(), section: self.section });
+        }
+        let mut rhs = b.to_vec();
+        rhs.resize(self.section, Complex64::new(0.0, 0.0));
+        for &(i, c, s) in &self.rotations {
+            let (x, y) = (rhs[i + 1], rhs[i]);
+            rhs[i + 1] = x * c + s * y;
+            rhs[i] = -s.conj() * x + y * c;
+        }
+        let mut x = vec![Complex64::new(0.0, 0.0); self.n];
+        for i in 0..self.n {
+            let head: Complex64 = (0..i).map(|j| self.l[(i, j)] * x[j]).sum();
+            x[i] = (rhs[i] - head) / checked_pivot(self.l[(i, i)], self.section)?;
+        }
+        Ok(x)
+    }
+}
+
+/// One step of a banded elimination: swap rows `column` and `pivot`, then subtract
+/// `m` times row `column` from each listed row
+#[derive(Debug, Clone)]
+struct Elimination {
+    column: usize,
+    pivot: usize,
+    multipliers: Vec<(usize, Complex64)>,
+}
+
+impl Elimination {
+    /// Apply the step to a right-hand side
+    fn apply(&self, rhs: &mut [Complex64]) {
+        rhs.swap(self.column, self.pivot);
+        let pivot_value = rhs[self.column];
+        for &(i, m) in &self.multipliers {
+            rhs[i] -= m * pivot_value;
+        }
+    }
+}
+
+/// Pivot on the largest entry of column j among `candidates` and eliminate the others
+fn eliminate(rows: &mut BandedRows, j: usize, candidates: RangeInclusive<usize>) -> Elimination {
+    let pivot = candidates
+        .clone()
+        .max_by(|&a, &b| rows.get(a, j).norm().total_cmp(&rows.get(b, j).norm()))
+        .unwrap_or(j);
+    rows.swap(j, pivot);
+    let pivot_value = rows.get(j, j);
+    let (offset, values) = rows.rows[j].clone();
+    let mut multipliers = Vec::new();
+    for i in candidates.filter(|&i| i != j) {
+        let a = rows.get(i, j);
+        if a.norm() == 0.0 || pivot_value.norm() == 0.0 {
+            continue;
+        }
+        let m = a / pivot_value;
+        for (c, &v) in (offset..).zip(&values).filter(|&(c, _)| c != j) {
+            let value = rows.get(i, c) - m * v;
+            rows.set(i, c, value);
+        }
+        rows.set(i, j, Complex64::new(0.0, 0.0));
+        multipliers.push((i, m));
+    }
+    Elimination {
+        column: j,
+        pivot,
+        multipliers,
+    }
+}
+
+/// Adaptive LU factorization with partial pivoting of a banded operator `A − z`
+///
+/// Columns are eliminated on demand; column k only pivots among rows k ..= k + l, so
+/// the leading part of the factors is that of the infinite operator. U is upper
+/// triangular with upper bandwidth l + u. L is kept in the banded (LINPACK) form
+/// P₀L₀P₁L₁⋯: a row swap followed by at most l multipliers per column.
+pub struct AdaptiveLu<'a> {
+    operator: &'a InfiniteOperator,
+    shift: Complex64,
+    lower: usize,
+    upper: usize,
+    rows: BandedRows,
+    eliminations: Vec<Elimination>,
+    /// Factor only the n×n section instead of the infinite operator
+    section: Option<usize>,
+}
+
+impl<'a> AdaptiveLu<'a> {
+    /// Start a factorization of A; fails if the operator has no bandwidths
+    pub fn new(operator: &'a InfiniteOperator) -> Result<Self, InfiniteArraysError> {
+        Self::shifted(operator, Complex64::new(0.0, 0.0))
+    }
+    
+    /// Start a factorization of `A − z`
+    pub fn shifted(operator: &'a InfiniteOperator, z: Complex64) -> Result<Self, InfiniteArraysError> {
+        let (lower, upper) = operator.bandwidths().ok_or(InfiniteArraysError::NotBanded)?;
+        Ok(AdaptiveLu {
+            operator,
+            shift: z,
+            lower,
+            upper,
+            rows: BandedRows::new(lower, lower + upper),
+            eliminations: Vec::new(),
+            section: None,
+        })
+    }
+    
+    /// Number of columns factored so far
+    pub fn factored_columns(&self) -> usize {
+        self.eliminations.len()
+    }
+    
+    /// Factor columns until at least `n` are done
+    pub fn factor_to(&mut self, n: usize) {
+        while self.eliminations.len() < n {
+            let k = self.eliminations.len();
+            let last_row = self.section.map_or(k + self.lower, |size| (k + self.lower).min(size - 1));
+            self.rows.load_through(self.operator, self.shift, last_row);
+            let step = eliminate(&mut self.rows, k, k..=last_row);
+            self.eliminations.push(step);
+        }
+    }
+    
+    /// Entry U[i, j] of the upper triangular factor; factors columns as needed
+    pub fn u(&mut self, i: usize, j: usize) -> Complex64 {
+        if j < i || j > i + self.lower + self.upper {
+            return Complex64::new(0.0, 0.0);
+        }
+        self.factor_to(i + 1);
+        self.rows.get(i, j)
+    }
+    
+    /// Row swapped into position k when column k was factored
+    pub fn pivot(&mut self, k: usize) -> usize {
+        self.factor_to(k + 1);
+        self.eliminations[k].pivot
+    }
+    
+    /// Multiplier of Lₖ for row i (zero outside k + 1 ..= k + l)
+    pub fn multiplier(&mut self, i: usize, k: usize) -> Complex64 {
+        self.factor_to(k + 1);
+        self.eliminations[k]
+            .multipliers
+            .iter()
+            .find(|&&(row, _)| row == i)
+            .map_or(Complex64::new(0.0, 0.0), |&(_, m)| m)
+    }
+}
+
+/// Diagonal entry of a triangular factor to divide by; a zero or non-finite one
+/// means the n×n section is singular
+fn checked_pivot(value: Complex64, n: usize) -> Result<Complex64, InfiniteArraysError> {
+    if value.norm() == 0.0 || !value.is_finite() {
+        return Err(InfiniteArraysError::SingularSection { n });
+    }
+    Ok(value)
+}
+
+/// Solve the n×n section system (A − z)ₙ x = b with a banded LU factorization
+///
+/// Fails with [`InfiniteArraysError::SingularSection`] if the section is singular.
+fn section_lu_solve(
+    operator: &InfiniteOperator,
+    z: Complex64,
+    b: &[Complex64],
+    n: usize,
+) -> Result<Vec<Complex64>, InfiniteArraysError> {
+    let mut lu = AdaptiveLu::shifted(operator, z)?;
+    lu.section = Some(n);
+    lu.factor_to(n);
+    let mut rhs = b.to_vec();
+    rhs.resize(n, Complex64::new(0.0, 0.0));
+    for step in &lu.eliminations {
+        step.apply(&mut rhs);
+    }
+    let bandwidth = lu.lower + lu.upper;
+    let mut x = vec![Complex64::new(0.0, 0.0); n];
+    for j in (0..n).rev() {
+        let tail: Complex64 = (j + 1..(j + bandwidth + 1).min(n)).map(|m| lu.rows.get(j, m) * x[m]).sum();
+        x[j] = (rhs[j] - tail) / checked_pivot(lu.rows.get(j, j), n)?;
+    }
+    Ok(x)
+}
+
+/// Apply the resolvent (A − z)⁻¹ to a finitely supported b
+///
+/// Solves banded LU-factored sections of doubling size until consecutive solutions
+/// agree to `tol` (relative) or `max_n` is reached; no dense truncation is formed.
+/// The reported residual is ‖(A − z)x − b‖ for the returned x. Square sections
+/// only converge when `A − z` has Fredholm index zero; otherwise use [`adaptive_solve`].
+/// Fails with [`InfiniteArraysError::SingularSection`] if a section of `A − z` is singular.
+pub fn resolvent_solve(
+    operator: &InfiniteOperator,
+    z: Complex64,
+    b: &[Complex64],
+    tol: f64,
+    max_n: usize,
+) -> Result<AdaptiveSolution, InfiniteArraysError> {
+    let (lower, upper) = operator.bandwidths().ok_or(InfiniteArraysError::NotBanded)?;
+    let mut n = (2 * b.len()).max(4 * (lower + upper + 1)).min(max_n.max(b.len()));
+    let mut x = section_lu_solve(operator, z, b, n)?;
+    while n < max_n {
+        n = (2 * n).min(max_n);
+        let next = section_lu_solve(operator, z, b, n)?;
+        let settled = linalg::padded_distance(&next, &x) <= tol * linalg::norm(&next);
+        x = next;
+        if settled {
+            break;
+        }
+    }
+    
+    let mut residual_vector = operator.matvec(&x);
+    linalg::axpy(&mut residual_vector, -z, &x);
+    linalg::axpy(&mut residual_vector, Complex64::new(-1.0, 0.0), b);
+    Ok(AdaptiveSolution {
+        residual: linalg::norm(&residual_vector),
+        coefficients: x,
+        extension: None,
+    })
+}
+
+/// UL factorization of the n×n section of `A − shift` with partial pivoting,
+/// bottom-up; `n` must be positive
+fn ul_section(operator: &InfiniteOperator, shift: Complex64, lower: usize, upper: usize, n: usize) -> (BandedRows, Vec<Elimination>) {
+    let mut rows = BandedRows::new(lower, upper);
+    rows.load_through(operator, shift, n - 1);
+    let mut eliminations = Vec::with_capacity(n);
+    for j in (0..n).rev() {
+        eliminations.push(eliminate(&mut rows, j, j.saturating_sub(upper)..=j));
+    }
+    (rows, eliminations)
+}
+
+/// UL factorization A = UL of a banded infinite operator, with partial pivoting
+///
+/// Computed from infinity like [`ql`]: UL factorizations of growing sections are
+/// taken until the leading n×n blocks of L agree to `tol`. It exists for instance
+/// for Toeplitz operators whose symbol has nonpositive winding number about 0. U is
+/// kept in the banded form of row swaps and multipliers. Fails for an operator
+/// without bandwidths or n = 0.
+pub fn ul(operator: &InfiniteOperator, n: usize, tol: f64, max_n: usize) -> Result<UlFactorization, InfiniteArraysError> {
+    if n == 0 {
+        return Err(InfiniteArraysError::EmptyTruncation);
+    }
+    let (lower, upper) = operator.bandwidths().ok_or(InfiniteArraysError::NotBanded)?;
+    let mut section = (2 * n).max(n + 4 * (lower + upper + 1)).min(max_n.max(n));
+    let mut previous: Option<Array2<Complex64>> = None;
+    loop {
+        let (rows, eliminations) = ul_section(operator, Complex64::new(0.0, 0.0), lower, upper, section);
+        let l = lower_block(&rows, n);
+        let converged = previous.as_ref().is_some_and(|p| block_settled(&l, p, tol));
+        if converged || section >= max_n {
+            return Ok(UlFactorization {
+                l,
+                n,
+                section,
+                converged,
+                eliminations,
+            });
+        }
+        previous = Some(l);
+        section = (2 * section).min(max_n);
+    }
+}
+
+/// Leading block of an infinite UL factorization
+#[derive(Debug, Clone)]
+pub struct UlFactorization {
+    /// Leading n×n block of the lower triangular factor
+    pub l: Array2<Complex64>,
+    pub n: usize,
+    /// Section size the block was taken from
+    pub section: usize,
+    pub converged: bool,
+    eliminations: Vec<Elimination>,
+}
+
+impl UlFactorization {
+    /// First n coefficients of the solution of A x = b
+    ///
+    /// Applies U⁻¹ to b and forward-substitutes with L; b must be supported within the
+    /// section the factorization was taken from. Fails with
+    /// [`InfiniteArraysError::SingularSection`] if L has a zero diagonal entry.
+    pub fn solve(&self, b: &[Complex64]) -> Result<Vec<Complex64>, InfiniteArraysError> {
+        if b.len() > self.section {
+            return Err(InfiniteArraysError::RightHandSideTooLong { length: b.len(), section: self.section });
+        }
+        let mut rhs = b.to_vec();
+        rhs.resize(self.section, Complex64::new(0.0, 0.0));
+        for step in &self.eliminations {
+            step.apply(&mut rhs);
+        }
+        let mut x = vec![Complex64::new(0.0, 0.0); self.n];
+        for i in 0..self.n {
+            let head: Complex64 = (0..i).map(|j| self.l[(i, j)] * x[j]).sum();
+            x[i] = (rhs[i] - head) / checked_pivot(self.l[(i, i)], self.section)?;
+        }
+        Ok(x)
+    }
+}
+
+/// Adaptive Cholesky factorization A = LLᴴ of a self-adjoint positive definite banded operator
+///
+/// Column k of L only depends on the entries of A up to row k + b, so the factor is
+/// computed column by column on demand and its leading n×n block is exactly the
+/// Cholesky factor of the n×n section.
+pub struct AdaptiveCholesky<'a> {
+    operator: &'a InfiniteOperator,
+    bandwidth: usize,
+    /// Column k holds L[k ..= k + b, k]
+    columns: Vec<Vec<Complex64>>,
+}
+
+impl<'a> AdaptiveCholesky<'a> {
+    /// Start a factorization; fails if the operator has no bandwidths
+    pub fn new(operator: &'a InfiniteOperator) -> Result<Self, InfiniteArraysError> {
+        let (lower, upper) = operator.bandwidths().ok_or(InfiniteArraysError::NotBanded)?;
+        Ok(AdaptiveCholesky {
+            operator,
+            bandwidth: lower.max(upper),
+            columns: Vec::new(),
+        })
+    }
+    
+    /// Number of columns factored so far
+    pub fn factored_columns(&self) -> usize {
+        self.columns.len()
+    }
+    
+    /// Factor columns until at least `n` are done
+    ///
+    /// Fails with [`InfiniteArraysError::NotPositiveDefinite`] at the first nonpositive
+    /// pivot; the columns before it stay factored.
+    pub fn factor_to(&mut self, n: usize) -> Result<(), InfiniteArraysError> {
+        while self.columns.len() < n {
+            let k = self.columns.len();
+            let first = k.saturating_sub(self.bandwidth);
+            let pivot = self.operator.get(k, k).re - (first..k).map(|m| self.entry(k, m).norm_sqr()).sum::<f64>();
+            if pivot.is_nan() || pivot <= 0.0 {
+                return Err(InfiniteArraysError::NotPositiveDefinite { pivot: k, value: pivot });
+            }
+            let diagonal = pivot.sqrt();
+            let mut column = vec![Complex64::new(diagonal, 0.0)];
+            for i in k + 1..=k + self.bandwidth {
+                let sum: Complex64 = (i.saturating_sub(self.bandwidth).max(first)..k)
+                    .map(|m| self.entry(i, m) * self.entry(k, m).conj())
+                    .sum();
+                column.push((self.operator.get(i, k) - sum) / diagonal);
+            }
+            self.columns.push(column);
+        }
+        Ok(())
+    }
+    
+    /// Factored entry L[i, j], zero outside the band
+    fn entry(&self, i: usize, j: usize) -> Complex64 {
+        if i < j || i > j + self.bandwidth {
+            return Complex64::new(0.0, 0.0);
+        }
+        self.columns[j][i - j]
+    }
+    
+    /// Entry L[i, j] of the Cholesky factor; factors columns as needed
+    pub fn l(&mut self, i: usize, j: usize) -> Result<Complex64, InfiniteArraysError> {
+        self.factor_to(j + 1)?;
+        Ok(self.entry(i, j))
+    }
+    
+    /// log det of the n×n section, 2 Σ log L[k, k]
+    pub fn logdet(&mut self, n: usize) -> Result<f64, InfiniteArraysError> {
+        self.factor_to(n)?;
+        Ok(2.0 * (0..n).map(|k| self.columns[k][0].re.ln()).sum::<f64>())
+    }
+    
+    /// Solve the n×n section system Aₙ x = b
+    fn section_solve(&mut self, b: &[Complex64], n: usize) -> Result<Vec<Complex64>, InfiniteArraysError> {
+        self.factor_to(n)?;
+        let mut x = b.to_vec();
+        x.resize(n, Complex64::new(0.0, 0.0));
+        for i in 0..n {
+            let head: Complex64 = (i.saturating_sub(self.bandwidth)..i).map(|m| self.entry(i, m) * x[m]).sum();
+            x[i] = (x[i] - head) / self.columns[i][0];
+        }
+        for i in (0..n).rev() {
+            let tail: Complex64 = (i + 1..(i + self.bandwidth + 1).min(n)).map(|m| self.entry(m, i).conj() * x[m]).sum();
+            x[i] = (x[i] - tail) / self.columns[i][0];
+        }
+        Ok(x)
+    }
+    
+    /// Solve A x = b for a finitely supported b
+    ///
+    /// Solves sections of doubling size, reusing the factored columns, until
+    /// consecutive solutions agree to `tol` (relative) or `max_n` is reached. The
+    /// reported residual is ‖Ax − b‖ for the returned x.
+    pub fn solve(&mut self, b: &[Complex64], tol: f64, max_n: usize) -> Result<AdaptiveSolution, InfiniteArraysError> {
+        let mut n = (2 * b.len()).max(4 * (self.bandwidth + 1)).min(max_n.max(b.len()));
+        let mut x = self.section_solve(b, n)?;
+        while n < max_n {
+            n = (2 * n).min(max_n);
+            let next = self.section_solve(b, n)?;
+            let settled = linalg::padded_distance(&next, &x) <= tol * linalg::norm(&next);
+            x = next;
+            if settled {
+                break;
+            }
+        }
+        
+        let mut residual_vector = self.operator.matvec(&x);
+        linalg::axpy(&mut residual_vector, Complex64::new(-1.0, 0.0), b);
+        Ok(AdaptiveSolution {
+            residual: linalg::norm(&residual_vector),
+            coefficients: x,
+            extension: None,
+        })
+    }
+}
+
+/// Least-squares solution of A x = b from rectangular sections
+#[derive(Debug, Clone)]
+pub struct LsqSolution {
+    pub coefficients: Vec<Complex64>,
+    /// ‖Ax − b‖ for the returned x
+    pub residual: f64,
+    /// Section shape the solution came from
+    pub m: usize,
+    pub n: usize,
+    /// (m, n, residual) for every section tried
+    pub history: Vec<(usize, usize, f64)>,
+    pub converged: bool,
+}
+
+/// Solve min ‖A[..m, ..n] x − b‖ on the m×n section
+///
+/// Uses a QR factorization, falling back to the minimum-norm solution from an SVD when
+/// a diagonal entry of R is below `n · ε` relative to the largest, so rank-deficient
+/// sections (a zero first column, as for the backward shift) are solved as well.
+/// The residual ‖A[.., ..n] x − b‖ is taken over every row that x or b reaches: all
+/// m section rows, the rows of b beyond them, and for banded operators the first
+/// n + l rows.
+fn section_lsq(operator: &InfiniteOperator, b: &[Complex64], m: usize, n: usize) -> (Vec<Complex64>, f64) {
+    let section = nalgebra::DMatrix::from_fn(m, n, |i, j| operator.get(i, j));
+    let rhs = nalgebra::DVector::from_fn(m, |i, _| b.get(i).copied().unwrap_or(Complex64::new(0.0, 0.0)));
+    let qr = section.clone().qr();
+    let r = qr.r();
+    let largest = r.diagonal().iter().map(|v| v.norm()).fold(0.0, f64::max);
+    let rank_tol = n as f64 * f64::EPSILON * largest;
+    let full_rank = largest > 0.0 && r.diagonal().iter().all(|v| v.norm() > rank_tol);
+    let x = if full_rank { r.solve_upper_triangular(&(qr.q().adjoint() * &rhs)) } else { None };
+    let x = x.unwrap_or_else(|| {
+        section
+            .svd(true, true)
+            .solve(&rhs, rank_tol)
+            .unwrap_or_else(|_| nalgebra::DVector::zeros(n))
+    });
+    
+    let reach = operator.bandwidths().map_or(0, |(lower, _)| n + lower);
+    let rows = m.max(b.len()).max(reach);
+    let residual = (0..rows)
+        .map(|i| {
+            let row: Complex64 = (0..n).map(|j| operator.get(i, j) * x[j]).sum();
+            (row - b.get(i).copied().unwrap_or(Complex64::new(0.0, 0.0))).norm_sqr()
+        })
+        .sum::<f64>()
+        .sqrt();
+    (x.iter().copied().collect(), residual)
+}
+
+/// Least-squares solve of A x = b with rectangular (m > n) sections
+///
+/// Rectangular sections keep the rows that the first n columns reach, which is how
+/// boundary-value problems are discretized correctly in infinite dimensions (square
+/// sections throw away equations). Starting from `shape`, m and n are doubled until
+/// the residual ‖Ax − b‖ changes by at most `tol · ‖b‖` or n would exceed `max_n`.
+/// The shape must have m ≥ n > 0.
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all, fields(tol, max_n)))]
+pub fn lsq_solve(
+    operator: &InfiniteOperator,
+    b: &[Complex64],
+    shape: (usize, usize),
+    tol: f64,
+    max_n: usize,
+) -> Result<LsqSolution, InfiniteArraysError> {
+    let (mut m, mut n) = shape;
+    if n == 0 {
+        return Err(InfiniteArraysError::EmptyTruncation);
+    }
+    if m < n {
+        return Err(InfiniteArraysError::InvalidSectionShape { rows: m, columns: n });
+    }
+    let b_norm = linalg::norm(b);
+    let mut history: Vec<(usize, usize, f64)> = Vec::new();
+    let mut converged = false;
+    let (coefficients, residual) = loop {
+        let (x, residual) = section_lsq(operator, b, m, n);
+        let settled = history.last().is_some_and(|&(_, _, previous)| (residual - previous).abs() <= tol * b_norm);
+        debug_event!(m, n, residual, "least-squares section solved");
+        history.push((m, n, residual));
+        if settled {
+            converged = true;
+            break (x, residual);
+        }
+        if 2 * n > max_n {
+            break (x, residual);
+        }
+        m *= 2;
+        n *= 2;
+    };
+    
+    Ok(LsqSolution {
+        coefficients,
+        residual,
+        m,
+        n,
+        history,
+        converged,
+    })
+}
+
+/// Least-squares solve of A x = b for an ∞×N operator
+///
+/// The column count is fixed by the type, so only the row count m of the m×N
+/// sections grows: it starts at the rows the N columns reach (N + lower bandwidth
+/// for banded operators, where that section is already exact) or 2N, and doubles
+/// until the residual ‖A[..m, ..] x − b‖ changes by at most `tol · ‖b‖` or m would
+/// exceed `max_m`. N = 0 is an [`InfiniteArraysError::EmptyTruncation`].
+pub fn tall_lsq_solve<const N: usize>(
+    operator: &Dims<InfiniteOperator, Inf, Finite<N>>,
+    b: &[Complex64],
+    tol: f64,
+    max_m: usize,
+) -> Result<LsqSolution, InfiniteArraysError> {
+    if N == 0 {
+        return Err(InfiniteArraysError::EmptyTruncation);
+    }
+    let reach = operator.bandwidths().map(|(lower, _)| N + lower);
+    let mut m = reach.unwrap_or(2 * N).max(b.len()).max(N);
+    let b_norm = linalg::norm(b);
+    let mut history: Vec<(usize, usize, f64)> = Vec::new();
+    let mut converged = false;
+    let (coefficients, residual) = loop {
+        let (x, residual) = section_lsq(operator, b, m, N);
+        let exact = reach.is_some_and(|reach| m >= reach);
+        let settled = exact || history.last().is_some_and(|&(_, _, previous)| (residual - previous).abs() <= tol * b_norm);
+        debug_event!(m, residual, "tall least-squares section solved");
+        history.push((m, N, residual));
+        if settled {
+            converged = true;
+            break (x, residual);
+        }
+        if 2 * m > max_m {
+            break (x, residual);
+        }
+        m *= 2;
+    };
+    
+    Ok(LsqSolution {
+        coefficients,
+        residual,
+        m,
+        n: N,
+        history,
+        converged,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arrays::InfiniteArray;
+    use crate::test_fixtures::{laplacian, real, tridiagonal, tridiagonal_solution};
+    use approx::{assert_abs_diff_eq, assert_relative_eq};
+
+    fn assert_leading_coefficients(x: &[Complex64], n: usize, epsilon: f64) {
+        assert!(x.len() >= n);
+        for (k, value) in x.iter().take(n).enumerate() {
+            assert_abs_diff_eq!(value.re, tridiagonal_solution(k), epsilon = epsilon);
+            assert_abs_diff_eq!(value.im, 0.0, epsilon = epsilon);
+        }
+    }
+
+    /// ‖A x − b‖
+    fn residual(operator: &InfiniteOperator, x: &[Complex64], b: &[Complex64]) -> f64 {
+        let mut r = operator.matvec(x);
+        linalg::axpy(&mut r, real(-1.0), b);
+        linalg::norm(&r)
+    }
+
+    #[test]
+    fn adaptive_qr_solves_a_tridiagonal_system() {
+        let a = tridiagonal();
+        let solution = adaptive_solve(&a, &[real(1.0)], 1e-12).unwrap();
+        assert!(solution.residual <= 1e-12);
+        assert!(residual(&a, &solution.coefficients, &[real(1.0)]) <= 1e-11);
+        assert_leading_coefficients(&solution.coefficients, 10, 1e-12);
+
+        // R is upper triangular with bandwidth 2 and A = QR
+        let mut qr = AdaptiveQr::new(&a).unwrap();
+        assert_eq!(qr.r(3, 2), real(0.0));
+        assert_eq!(qr.r(0, 3), real(0.0));
+        assert_abs_diff_eq!(qr.r(0, 0).norm(), 10f64.sqrt(), epsilon = 1e-12);
+    }
+
+    #[test]
+    fn ql_matches_the_infinite_solution() {
+        let factorization = ql(&tridiagonal(), 8, 1e-13, 1024).unwrap();
+        assert!(factorization.converged);
+        assert_leading_coefficients(&factorization.solve(&[real(1.0)]).unwrap(), 8, 1e-10);
+    }
+
+    #[test]
+    fn ql_spectral_indicator_vanishes_at_an_eigenvalue() {
+        // Toeplitz tridiag(1, 0, 1) plus 2 in the corner has the eigenvalue 2 + 1/2
+        // outside its essential spectrum [−2, 2]
+        let a = crate::banded_operator! { -1 => |_| 1.0, 0 => |i| if i == 0 { 2.0 } else { 0.0 }, 1 => |_| 1.0 };
+        let at_eigenvalue = ql_spectral_indicator(&a, real(2.5), 1e-13, 2048).unwrap();
+        let elsewhere = ql_spectral_indicator(&a, real(3.0), 1e-13, 2048).unwrap();
+        assert!(at_eigenvalue < 1e-8);
+        assert!(elsewhere > 0.1);
+    }
+
+    #[test]
+    fn ul_and_lu_solves_match_the_infinite_solution() {
+        let a = tridiagonal();
+        let e0 = [real(1.0)];
+        let factorization = ul(&a, 8, 1e-13, 1024).unwrap();
+        assert!(factorization.converged);
+        assert_leading_coefficients(&factorization.solve(&e0).unwrap(), 8, 1e-10);
+
+        let solution = resolvent_solve(&a, real(0.0), &e0, 1e-13, 1024).unwrap();
+        assert!(solution.residual <= 1e-12);
+        assert_leading_coefficients(&solution.coefficients, 10, 1e-12);
+
+        // A complex shift off the spectrum [1, 5]
+        let z = Complex64::new(0.5, 0.5);
+        let solution = resolvent_solve(&a, z, &e0, 1e-13, 1024).unwrap();
+        let mut shifted = a.matvec(&solution.coefficients);
+        linalg::axpy(&mut shifted, -z, &solution.coefficients);
+        linalg::axpy(&mut shifted, real(-1.0), &e0);
+        assert!(linalg::norm(&shifted) <= 1e-12);
+    }
+
+    #[test]
+    fn singular_sections_are_an_error() {
+        // diag(0, 1, 2, ...) − 2 vanishes in row 2, so every section past it is singular
+        let diagonal = InfiniteOperator::from_diagonals(vec![(0, crate::operators::diagonal_fn(|i| i as f64))]);
+        assert!(matches!(
+            resolvent_solve(&diagonal, real(2.0), &[real(1.0)], 1e-12, 64),
+            Err(InfiniteArraysError::SingularSection { .. })
+        ));
+
+        let factorization = ul(&diagonal, 4, 1e-12, 64).unwrap();
+        assert!(matches!(
+            factorization.solve(&[real(1.0)]),
+            Err(InfiniteArraysError::SingularSection { .. })
+        ));
+    }
+
+    #[test]
+    fn ql_solve_with_a_zero_pivot_is_an_error() {
+        let diagonal = InfiniteOperator::from_diagonals(vec![(0, crate::operators::diagonal_fn(|i| i as f64))]);
+        let factorization = ql(&diagonal, 4, 1e-12, 64).unwrap();
+        assert!(matches!(
+            factorization.solve(&[real(1.0)]),
+            Err(InfiniteArraysError::SingularSection { .. })
+        ));
+    }
+
+    #[test]
+    fn adaptive_qr_solve_with_a_zero_pivot_is_an_error() {
+        // R = diag(0, 1, 2, ...) has R[0, 0] = 0
+        let diagonal = InfiniteOperator::from_diagonals(vec![(0, crate::operators::diagonal_fn(|i| i as f64))]);
+        assert_eq!(
+            adaptive_solve(&diagonal, &[real(1.0)], 1e-12).err(),
+            Some(InfiniteArraysError::SingularSection { n: 1 })
+        );
+    }
+
+    #[test]
+    fn cholesky_solve_matches_the_infinite_solution() {
+        let a = tridiagonal();
+        let mut cholesky = AdaptiveCholesky::new(&a).unwrap();
+        let solution = cholesky.solve(&[real(1.0)], 1e-13, 1024).unwrap();
+        assert!(solution.residual <= 1e-12);
+        assert_leading_coefficients(&solution.coefficients, 10, 1e-12);
+        // L L* = A: L[0, 0]² = 3 and L[1, 0] L[0, 0] = −1
+        assert_abs_diff_eq!(cholesky.l(0, 0).unwrap().re, 3f64.sqrt(), epsilon = 1e-14);
+        assert_abs_diff_eq!(cholesky.l(1, 0).unwrap().re, -1.0 / 3f64.sqrt(), epsilon = 1e-14);
+    }
+
+    #[test]
+    fn lsq_solve_converges_to_the_infinite_solution() {
+        let a = tridiagonal();
+        let solution = lsq_solve(&a, &[real(1.0)], (8, 4), 1e-12, 256).unwrap();
+        assert!(solution.converged);
+        assert!(solution.residual <= 1e-10);
+        assert_leading_coefficients(&solution.coefficients, 6, 1e-10);
+    }
+
+    #[test]
+    fn degenerate_input_is_an_error() {
+        let dense = InfiniteOperator::new(|i, j| real(1.0 / (i + j + 1) as f64));
+        assert_eq!(AdaptiveQr::new(&dense).err(), Some(InfiniteArraysError::NotBanded));
+        assert_eq!(ql(&dense, 4, 1e-10, 64).err(), Some(InfiniteArraysError::NotBanded));
+
+        let laplacian = laplacian();
+        assert_eq!(ql(&laplacian, 0, 1e-10, 64).err(), Some(InfiniteArraysError::EmptyTruncation));
+        assert_eq!(ul(&laplacian, 0, 1e-10, 64).err(), Some(InfiniteArraysError::EmptyTruncation));
+        let factorization = ql(&laplacian, 2, 1e-10, 64).unwrap();
+        let too_long = vec![real(1.0); factorization.section + 1];
+        assert!(matches!(factorization.solve(&too_long), Err(InfiniteArraysError::RightHandSideTooLong { .. })));
+
+        // −Δ is positive definite, Δ is not
+        let mut cholesky = AdaptiveCholesky::new(&laplacian).unwrap();
+        assert_eq!(
+            cholesky.factor_to(1),
+            Err(InfiniteArraysError::NotPositiveDefinite { pivot: 0, value: -2.0 })
+        );
+
+        assert_eq!(
+            lsq_solve(&laplacian, &[real(1.0)], (2, 3), 1e-10, 64).err(),
+            Some(InfiniteArraysError::InvalidSectionShape { rows: 2, columns: 3 })
+        );
+    }
+
+    #[test]
+    fn adaptive_solution_extends_past_its_coefficients() {
+        // (I − S/2) x = e₀ with S the forward shift has x_k = 2⁻ᵏ
+        let operator = crate::banded_operator! { -1 => |_| -0.5, 0 => |_| 1.0 };
+        let solution = adaptive_solve(&operator, &[real(1.0)], 1e-3).unwrap();
+        let n = solution.coefficients.len();
+        let x = solution.real_part();
+        for k in [n, n + 5, 3 * n] {
+            assert_relative_eq!(x.get(k), 0.5f64.powi(k as i32), max_relative = 1e-2);
+        }
+        assert_abs_diff_eq!(solution.imag_part().get(2 * n), 0.0);
+    }
+
+    #[test]
+    fn lsq_solve_handles_a_zero_first_column() {
+        // Backward shift: column 0 vanishes, so every section is rank deficient
+        let shift = InfiniteOperator::from_diagonals(vec![(1, crate::operators::diagonal_fn(|_| 1.0))]);
+        let solution = lsq_solve(&shift, &[real(1.0), real(2.0)], (4, 2), 1e-12, 64).unwrap();
+        assert_abs_diff_eq!(solution.residual, 0.0, epsilon = 1e-12);
+        // Minimum-norm solution: nothing in the kernel direction e₀
+        assert_abs_diff_eq!(solution.coefficients[0].norm(), 0.0, epsilon = 1e-12);
+        assert_abs_diff_eq!(solution.coefficients[1].re, 1.0, epsilon = 1e-12);
+        assert_abs_diff_eq!(solution.coefficients[2].re, 2.0, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn lsq_solve_residual_covers_all_section_rows() {
+        // A single non-banded column a = (1, 1/2, 1/4, ...): the least-squares
+        // solution of a x = e₀ on m rows leaves residual² = 1 − 1/‖a[..m]‖²
+        let column = InfiniteOperator::new(|i, j| if j == 0 { real(0.5f64.powi(i as i32)) } else { real(0.0) });
+        let solution = lsq_solve(&column, &[real(1.0)], (8, 1), 1e-12, 1).unwrap();
+        assert_eq!((solution.m, solution.n), (8, 1));
+        let norm_sqr: f64 = (0..8).map(|i| 0.25f64.powi(i)).sum();
+        assert_abs_diff_eq!(solution.residual, (1.0 - 1.0 / norm_sqr).sqrt(), epsilon = 1e-12);
+    }
+}
@@ -0,0 +1,289 @@
+//! GPU kernel for the QR steps of large truncations, available with the `gpu` feature.
+//!
+//! The Householder RQ step of [`iqr`](crate::iqr) runs as `wgpu` compute shaders in
+//! complex double precision, so it needs an adapter with 64-bit float shaders
+//! ([`wgpu::Features::SHADER_F64`]: Vulkan or DirectX 12 on most discrete GPUs; not
+//! Metal, WebGPU or OpenGL). The iterate is uploaded and read back around every step,
+//! which only pays off for large truncations; see [`Backend::Gpu`](crate::iqr::Backend::Gpu).
+//! Without a suitable adapter the IQR entry points fall back to the CPU kernel.
+
+use ndarray::Array2;
+use num_complex::Complex64;
+use std::num::NonZeroU64;
+use std::sync::OnceLock;
+
+const SHADER: &str = include_str!("qr.wgsl");
+
+/// Invocations per workgroup of the one-dimensional kernels in `qr.wgsl`
+const WORKGROUP: u32 = 64;
+
+/// Side of the square workgroups of the `update` kernel
+const TILE: u32 = 8;
+
+/// Whether a GPU with 64-bit float shaders was found
+///
+/// The adapter is looked for once per process; later calls return the same answer.
+pub fn is_available() -> bool {
+    context().is_some()
+}
+
+/// Device, queue and pipelines shared by every [`GpuQr`]
+struct Context {
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    layout: wgpu::BindGroupLayout,
+    reflector: wgpu::ComputePipeline,
+    column_sums: wgpu::ComputePipeline,
+    update: wgpu::ComputePipeline,
+    reflect_rows: wgpu::ComputePipeline,
+}
+
+/// Number of QR steps run on the GPU, so tests can tell the kernel from the CPU fallback
+#[cfg(test)]
+pub(crate) static STEPS: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+
+fn context() -> Option<&'static Context> {
+    static CONTEXT: OnceLock<Option<Context>> = OnceLock::new();
+    CONTEXT.get_or_init(Context::new).as_ref()
+}
+
+impl Context {
+    fn new() -> Option<Self> {
+        let instance = wgpu::Instance::new(wgpu::InstanceDescriptor::default());
+        let adapter = instance
+            .enumerate_adapters(wgpu::Backends::all())
+            .into_iter()
+            .find(|adapter| adapter.features().contains(wgpu::Features::SHADER_F64))?;
+        debug_event!(adapter = %adapter.get_info().name, "GPU adapter for QR steps");
+        let (device, queue) = pollster::block_on(adapter.request_device(
+            &wgpu::DeviceDescriptor {
+                label: Some("infinite-arrays"),
+                required_features: wgpu::Features::SHADER_F64,
+                required_limits: adapter.limits(),
+                memory_hints: wgpu::MemoryHints::Performance,
+            },
+            None,
+        ))
+        .ok()?;
+        let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("qr"),
+            source: wgpu::ShaderSource::Wgsl(SHADER.into()),
+        });
+        let storage = |binding| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStages::COMPUTE,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Storage { read_only: false },
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        };
+        let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("qr"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: true,
+                        min_binding_size: NonZeroU64::new(PARAMS_SIZE),
+                    },
+                    count: None,
+                },
+                storage(1),
+                storage(2),
+                storage(3),
+                storage(4),
+                storage(5),
+            ],
+        });
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("qr"),
+            bind_group_layouts: &[&layout],
+            push_constant_ranges: &[],
+        });
+        let pipeline = |entry_point| {
+            device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+                label: Some(entry_point),
+                layout: Some(&pipeline_layout),
+                module: &module,
+                entry_point,
+                compilation_options: Default::default(),
+                cache: None,
+            })
+        };
+        Some(Context {
+            reflector: pipeline("reflector"),
+            column_sums: pipeline("column_sums"),
+            update: pipeline("update"),
+            reflect_rows: pipeline("reflect_rows"),
+            device,
+            queue,
+            layout,
+        })
+    }
+}
+
+fn entry(binding: u32, buffer: &wgpu::Buffer) -> wgpu::BindGroupEntry<'_> {
+    wgpu::BindGroupEntry { binding, resource: buffer.as_entire_binding() }
+}
+
+/// Bytes of the `Params` uniform (`n`, `k`)
+const PARAMS_SIZE: u64 = 8;
+
+/// Buffers of one truncation size, the GPU counterpart of the CPU `RqWorkspace`
+pub(crate) struct GpuQr {
+    context: &'static Context,
+    n: usize,
+    /// Distance between the `Params` of consecutive steps k
+    params_stride: u32,
+    iterate: wgpu::Buffer,
+    eigenvectors: wgpu::Buffer,
+    staging: wgpu::Buffer,
+    iterate_bindings: wgpu::BindGroup,
+    eigenvector_bindings: wgpu::BindGroup,
+}
+
+impl GpuQr {
+    /// Buffers for n×n iterates; `None` without a GPU or if a matrix exceeds the
+    /// device's storage buffer limit
+    pub(crate) fn new(n: usize) -> Option<Self> {
+        let context = context()?;
+        let device = &context.device;
+        let limits = device.limits();
+        let matrix_size = (n * n * size_of::<Complex64>()) as u64;
+        if n < 2 || matrix_size > u64::from(limits.max_storage_buffer_binding_size) {
+            return None;
+        }
+        let params_stride = limits.min_uniform_buffer_offset_alignment.max(PARAMS_SIZE as u32);
+        let mut params = vec![0u8; n * params_stride as usize];
+        for k in 0..n {
+            let offset = k * params_stride as usize;
+            params[offset..offset + 4].copy_from_slice(&(n as u32).to_le_bytes());
+            params[offset + 4..offset + 8].copy_from_slice(&(k as u32).to_le_bytes());
+        }
+        let buffer = |label, size, usage| {
+            device.create_buffer(&wgpu::BufferDescriptor { label: Some(label), size, usage, mapped_at_creation: false })
+        };
+        let matrix_usage = wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::COPY_SRC;
+        let vector_size = (n * size_of::<Complex64>()) as u64;
+        let params_buffer = buffer("params", params.len() as u64, wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST);
+        let iterate = buffer("iterate", matrix_size, matrix_usage);
+        let eigenvectors = buffer("eigenvectors", matrix_size, matrix_usage);
+        let reflectors = buffer("reflectors", matrix_size, wgpu::BufferUsages::STORAGE);
+        let betas = buffer("betas", (n * size_of::<f64>()) as u64, wgpu::BufferUsages::STORAGE);
+        let alphas = buffer("alphas", vector_size, wgpu::BufferUsages::STORAGE);
+        let sums = buffer("sums", vector_size, wgpu::BufferUsages::STORAGE);
+        let staging = buffer("staging", matrix_size, wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST);
+        context.queue.write_buffer(&params_buffer, 0, &params);
+        let bindings = |matrix: &wgpu::Buffer| {
+            device.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: Some("qr"),
+                layout: &context.layout,
+                entries: &[
+                    wgpu::BindGroupEntry {
+                        binding: 0,
+                        resource: wgpu::BindingResource::Buffer(wgpu::BufferBinding {
+                            buffer: &params_buffer,
+                            offset: 0,
+                            size: NonZeroU64::new(PARAMS_SIZE),
+                        }),
+                    },
+                    entry(1, matrix),
+                    entry(2, &reflectors),
+                    entry(3, &betas),
+                    entry(4, &alphas),
+                    entry(5, &sums),
+                ],
+            })
+        };
+        Some(GpuQr {
+            context,
+            n,
+            params_stride,
+            iterate_bindings: bindings(&iterate),
+            eigenvector_bindings: bindings(&eigenvectors),
+            iterate,
+            eigenvectors,
+            staging,
+        })
+    }
+
+    /// Overwrite `a` with RQ, where QR = a, multiplying `q_total` by Q
+    ///
+    /// Same reflections as the CPU kernel; sums are accumulated in a different
+    /// order, so results agree to rounding rather than bit for bit. Both matrices
+    /// must be n×n in standard layout.
+    pub(crate) fn rq_step(&mut self, a: &mut Array2<Complex64>, q_total: Option<&mut Array2<Complex64>>) {
+        #[cfg(test)]
+        STEPS.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        let Context { device, queue, reflector, column_sums, update, reflect_rows, .. } = self.context;
+        let n = self.n as u32;
+        let a = a.as_slice_mut().expect("iterate is in standard layout");
+        let mut q = q_total.map(|q| q.as_slice_mut().expect("eigenvector matrix is in standard layout"));
+        queue.write_buffer(&self.iterate, 0, bytemuck::cast_slice(a));
+        if let Some(q) = &q {
+            queue.write_buffer(&self.eigenvectors, 0, bytemuck::cast_slice(q));
+        }
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: Some("qr step") });
+        {
+            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor { label: Some("qr step"), timestamp_writes: None });
+            // a ← R, one reflection per column
+            for k in 0..n - 1 {
+                pass.set_bind_group(0, &self.iterate_bindings, &[k * self.params_stride]);
+                pass.set_pipeline(reflector);
+                pass.dispatch_workgroups(1, 1, 1);
+                pass.set_pipeline(column_sums);
+                pass.dispatch_workgroups((n - k - 1).div_ceil(WORKGROUP), 1, 1);
+                pass.set_pipeline(update);
+                pass.dispatch_workgroups((n - k).div_ceil(TILE), (n - k).div_ceil(TILE), 1);
+            }
+            // a ← RQ, then q_total ← q_total Q
+            pass.set_pipeline(reflect_rows);
+            pass.set_bind_group(0, &self.iterate_bindings, &[0]);
+            pass.dispatch_workgroups(n.div_ceil(WORKGROUP), 1, 1);
+            if q.is_some() {
+                pass.set_bind_group(0, &self.eigenvector_bindings, &[0]);
+                pass.dispatch_workgroups(n.div_ceil(WORKGROUP), 1, 1);
+            }
+        }
+        queue.submit([encoder.finish()]);
+
+        self.read(&self.iterate, a);
+        if let Some(q) = q.as_mut() {
+            self.read(&self.eigenvectors, q);
+        }
+    }
+
+    /// Copy a matrix buffer back into host memory, waiting for the GPU
+    fn read(&self, buffer: &wgpu::Buffer, out: &mut [Complex64]) {
+        let Context { device, queue, .. } = self.context;
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: Some("readback") });
+        encoder.copy_buffer_to_buffer(buffer, 0, &self.staging, 0, self.staging.size());
+        queue.submit([encoder.finish()]);
+        let slice = self.staging.slice(..);
+        slice.map_async(wgpu::MapMode::Read, |result| result.expect("GPU readback buffer could not be mapped"));
+        device.poll(wgpu::Maintain::Wait);
+        bytemuck::cast_slice_mut::<Complex64, u8>(out).copy_from_slice(&slice.get_mapped_range());
+        self.staging.unmap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wgpu::naga;
+
+    #[test]
+    fn shaders_validate_with_f64_support() {
+        let module = naga::front::wgsl::parse_str(SHADER).unwrap();
+        naga::valid::Validator::new(naga::valid::ValidationFlags::all(), naga::valid::Capabilities::FLOAT64)
+            .validate(&module)
+            .unwrap();
+        let entry_points: Vec<&str> = module.entry_points.iter().map(|entry| entry.name.as_str()).collect();
+        assert_eq!(entry_points, ["reflector", "column_sums", "update", "reflect_rows"]);
+    }
+}
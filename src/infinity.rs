@@ -1,27 +1,35 @@
 //! Infinity constant and related utilities.
 
-use std::fmt;
-use std::cmp::Ordering;
+use crate::display;
+use crate::error::InfiniteSizeError;
+use core::fmt;
+use core::cmp::Ordering;
+use core::ops::{Add, Mul, Neg, Sub};
 
-/// Represents infinity for array dimensions.
+/// The cardinal ∞ of array dimensions, the analogue of ℵ₀ in InfiniteArrays.jl.
+///
+/// It counts and compares with sizes, and compares with `f64` as `f64::INFINITY`
+/// (above every finite float, equal to `f64::INFINITY`, unordered with NaN). It
+/// does not convert to or compute with floats: value-level work goes through the
+/// extended real [`SignedInfinity`], reached explicitly with [`Infinity::to_real`].
 #[derive(Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Infinity;
 
 impl fmt::Display for Infinity {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "∞")
+        write!(f, "{}", display::infinity())
     }
 }
 
 impl fmt::Debug for Infinity {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "∞")
+        write!(f, "{}", display::infinity())
     }
 }
 
 impl PartialOrd for Infinity {
-    fn partial_cmp(&self, _other: &Infinity) -> Option<Ordering> {
-        Some(Ordering::Equal)
+    fn partial_cmp(&self, other: &Infinity) -> Option<Ordering> {
+        Some(self.cmp(other))
     }
 }
 
@@ -31,22 +39,705 @@ impl Ord for Infinity {
     }
 }
 
+/// Serialized as the string "∞", so `Some(Infinity)` stays distinct from `None`
+/// in formats such as JSON where a unit struct would be written as null
+#[cfg(feature = "serde")]
+impl serde::Serialize for Infinity {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str("∞")
+    }
+}
+
+/// Accepts "∞", "inf" and "Infinity"
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Infinity {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let text = <alloc::borrow::Cow<'de, str>>::deserialize(deserializer)?;
+        match text.as_ref() {
+            "∞" | "inf" | "Infinity" => Ok(Infinity),
+            other => Err(serde::de::Error::invalid_value(serde::de::Unexpected::Str(other), &"∞")),
+        }
+    }
+}
+
 impl Infinity {
     /// Check if a value is infinity
-    pub fn is_infinity(value: &dyn std::any::Any) -> bool {
+    #[deprecated(note = "only recognises the `Infinity` type itself; use `MaybeInfinite::is_infinite`")]
+    pub fn is_infinity(value: &dyn core::any::Any) -> bool {
         value.is::<Infinity>()
     }
+    
+    /// The extended real +∞, e.g. `f64::from(INFINITY.to_real())`
+    pub fn to_real(self) -> SignedInfinity {
+        SignedInfinity::Positive
+    }
+}
+
+/// Always fails: ∞ is not a count
+impl TryFrom<Infinity> for usize {
+    type Error = InfiniteSizeError;
+    
+    fn try_from(_: Infinity) -> Result<usize, InfiniteSizeError> {
+        Err(InfiniteSizeError)
+    }
+}
+
+
+// Size arithmetic with a count n: ∞ + n = n + ∞ = ∞ − n = ∞, and ∞ · n = ∞ for n > 0
+// while 0 · ∞ = 0.
+
+impl Add<usize> for Infinity {
+    type Output = Infinity;
+    
+    fn add(self, _n: usize) -> Infinity {
+        Infinity
+    }
+}
+
+impl Add<Infinity> for usize {
+    type Output = Infinity;
+    
+    fn add(self, _infinity: Infinity) -> Infinity {
+        Infinity
+    }
+}
+
+impl Sub<usize> for Infinity {
+    type Output = Infinity;
+    
+    fn sub(self, _n: usize) -> Infinity {
+        Infinity
+    }
+}
+
+/// A [`Size`] rather than `Infinity`, since 0 · ∞ = 0 as for [`Size::checked_mul`]
+impl Mul<usize> for Infinity {
+    type Output = Size;
+    
+    fn mul(self, n: usize) -> Size {
+        Size::Infinite * n
+    }
+}
+
+impl Mul<Infinity> for usize {
+    type Output = Size;
+    
+    fn mul(self, infinity: Infinity) -> Size {
+        infinity * self
+    }
 }
 
-impl From<Infinity> for f64 {
+/// The extended reals −∞ and +∞
+///
+/// Describes ends of ranges unbounded below or in both directions, directions of
+/// limits such as n → −∞, and value-level comparisons with floats. Ordered with −∞
+/// below every integer and +∞ above; arithmetic with f64 follows IEEE, so ∞ − ∞ and
+/// ∞ · 0 are NaN.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum SignedInfinity {
+    Negative,
+    Positive,
+}
+
+impl SignedInfinity {
+    /// +1 or −1
+    pub fn signum(&self) -> isize {
+        match self {
+            SignedInfinity::Negative => -1,
+            SignedInfinity::Positive => 1,
+        }
+    }
+    
+    pub fn is_positive(&self) -> bool {
+        matches!(self, SignedInfinity::Positive)
+    }
+    
+    pub fn is_negative(&self) -> bool {
+        !self.is_positive()
+    }
+    
+    /// The cardinal ∞ for +∞; −∞ is not a size
+    pub fn to_cardinal(self) -> Option<Infinity> {
+        self.is_positive().then_some(Infinity)
+    }
+}
+
+impl fmt::Display for SignedInfinity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SignedInfinity::Negative => write!(f, "-{}", display::infinity()),
+            SignedInfinity::Positive => write!(f, "{}", display::infinity()),
+        }
+    }
+}
+
+impl From<Infinity> for SignedInfinity {
     fn from(_: Infinity) -> Self {
-        f64::INFINITY
+        SignedInfinity::Positive
+    }
+}
+
+/// ±f64::INFINITY
+impl From<SignedInfinity> for f64 {
+    fn from(infinity: SignedInfinity) -> Self {
+        infinity.signum() as f64 * f64::INFINITY
+    }
+}
+
+impl Neg for Infinity {
+    type Output = SignedInfinity;
+    
+    fn neg(self) -> SignedInfinity {
+        SignedInfinity::Negative
+    }
+}
+
+impl Neg for SignedInfinity {
+    type Output = SignedInfinity;
+    
+    fn neg(self) -> SignedInfinity {
+        match self {
+            SignedInfinity::Negative => SignedInfinity::Positive,
+            SignedInfinity::Positive => SignedInfinity::Negative,
+        }
+    }
+}
+
+impl Add<f64> for SignedInfinity {
+    type Output = f64;
+    
+    fn add(self, x: f64) -> f64 {
+        f64::from(self) + x
+    }
+}
+
+impl Add<SignedInfinity> for f64 {
+    type Output = f64;
+    
+    fn add(self, infinity: SignedInfinity) -> f64 {
+        self + f64::from(infinity)
+    }
+}
+
+impl Sub<f64> for SignedInfinity {
+    type Output = f64;
+    
+    fn sub(self, x: f64) -> f64 {
+        f64::from(self) - x
+    }
+}
+
+impl Sub<SignedInfinity> for f64 {
+    type Output = f64;
+    
+    fn sub(self, infinity: SignedInfinity) -> f64 {
+        self - f64::from(infinity)
     }
 }
 
-impl From<Infinity> for usize {
+impl Mul<f64> for SignedInfinity {
+    type Output = f64;
+    
+    fn mul(self, x: f64) -> f64 {
+        f64::from(self) * x
+    }
+}
+
+impl Mul<SignedInfinity> for f64 {
+    type Output = f64;
+    
+    fn mul(self, infinity: SignedInfinity) -> f64 {
+        self * f64::from(infinity)
+    }
+}
+
+impl PartialEq<Infinity> for SignedInfinity {
+    fn eq(&self, _infinity: &Infinity) -> bool {
+        self.is_positive()
+    }
+}
+
+impl PartialOrd<Infinity> for SignedInfinity {
+    fn partial_cmp(&self, _infinity: &Infinity) -> Option<Ordering> {
+        self.partial_cmp(&SignedInfinity::Positive)
+    }
+}
+
+/// Never equal to an integer
+impl PartialEq<isize> for SignedInfinity {
+    fn eq(&self, _n: &isize) -> bool {
+        false
+    }
+}
+
+impl PartialOrd<isize> for SignedInfinity {
+    fn partial_cmp(&self, _n: &isize) -> Option<Ordering> {
+        Some(match self {
+            SignedInfinity::Negative => Ordering::Less,
+            SignedInfinity::Positive => Ordering::Greater,
+        })
+    }
+}
+
+/// Compared as ±f64::INFINITY
+impl PartialEq<f64> for SignedInfinity {
+    fn eq(&self, x: &f64) -> bool {
+        f64::from(*self) == *x
+    }
+}
+
+impl PartialOrd<f64> for SignedInfinity {
+    fn partial_cmp(&self, x: &f64) -> Option<Ordering> {
+        f64::from(*self).partial_cmp(x)
+    }
+}
+
+/// Length of a dimension, sequence or truncation: a count or ∞
+///
+/// Ordered with every count below ∞. Arithmetic is that of cardinals: ∞ absorbs
+/// counts under addition and nonzero counts under multiplication, 0 · ∞ = 0, and
+/// subtracting ∞ or overflowing a count panics instead of wrapping.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Size {
+    Finite(usize),
+    Infinite,
+}
+
+impl Size {
+    pub fn is_infinite(&self) -> bool {
+        matches!(self, Size::Infinite)
+    }
+    
+    pub fn is_finite(&self) -> bool {
+        !self.is_infinite()
+    }
+    
+    /// The count, or `None` for an infinite size
+    pub fn to_finite(self) -> Option<usize> {
+        match self {
+            Size::Finite(n) => Some(n),
+            Size::Infinite => None,
+        }
+    }
+    
+    /// Sum, or `None` if a count overflows
+    pub fn checked_add(self, other: impl Into<Size>) -> Option<Size> {
+        match (self, other.into()) {
+            (Size::Finite(a), Size::Finite(b)) => a.checked_add(b).map(Size::Finite),
+            _ => Some(Size::Infinite),
+        }
+    }
+    
+    /// Difference, or `None` when subtracting ∞ or a larger count
+    pub fn checked_sub(self, other: impl Into<Size>) -> Option<Size> {
+        match (self, other.into()) {
+            (Size::Finite(a), Size::Finite(b)) => a.checked_sub(b).map(Size::Finite),
+            (Size::Infinite, Size::Finite(_)) => Some(Size::Infinite),
+            (_, Size::Infinite) => None,
+        }
+    }
+    
+    /// Product, or `None` if a count overflows; 0 · ∞ = 0
+    pub fn checked_mul(self, other: impl Into<Size>) -> Option<Size> {
+        match (self, other.into()) {
+            (Size::Finite(a), Size::Finite(b)) => a.checked_mul(b).map(Size::Finite),
+            (Size::Finite(0), Size::Infinite) | (Size::Infinite, Size::Finite(0)) => Some(Size::Finite(0)),
+            _ => Some(Size::Infinite),
+        }
+    }
+    
+    /// The smaller of the size and a count, which is always finite
+    pub fn min_count(self, n: usize) -> usize {
+        match self {
+            Size::Finite(m) => m.min(n),
+            Size::Infinite => n,
+        }
+    }
+}
+
+impl From<usize> for Size {
+    fn from(n: usize) -> Self {
+        Size::Finite(n)
+    }
+}
+
+impl From<Infinity> for Size {
     fn from(_: Infinity) -> Self {
-        usize::MAX
+        Size::Infinite
+    }
+}
+
+/// `None` is the unbounded size, as in the truncation arguments `max_n: Option<usize>`
+impl From<Option<usize>> for Size {
+    fn from(n: Option<usize>) -> Self {
+        n.map_or(Size::Infinite, Size::Finite)
+    }
+}
+
+impl TryFrom<Size> for usize {
+    type Error = InfiniteSizeError;
+    
+    fn try_from(size: Size) -> Result<usize, InfiniteSizeError> {
+        size.to_finite().ok_or(InfiniteSizeError)
+    }
+}
+
+impl fmt::Display for Size {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Size::Finite(n) => write!(f, "{}", n),
+            Size::Infinite => write!(f, "{}", display::infinity()),
+        }
+    }
+}
+
+impl<T: Into<Size>> Add<T> for Size {
+    type Output = Size;
+    
+    fn add(self, other: T) -> Size {
+        self.checked_add(other).expect("size overflow")
+    }
+}
+
+/// Panics when subtracting ∞ or a larger count
+impl<T: Into<Size>> Sub<T> for Size {
+    type Output = Size;
+    
+    fn sub(self, other: T) -> Size {
+        let other = other.into();
+        if other.is_infinite() {
+            panic!("cannot subtract ∞ from a size");
+        }
+        self.checked_sub(other).expect("size underflow")
+    }
+}
+
+impl<T: Into<Size>> Mul<T> for Size {
+    type Output = Size;
+    
+    fn mul(self, other: T) -> Size {
+        self.checked_mul(other).expect("size overflow")
+    }
+}
+
+impl Add<Size> for usize {
+    type Output = Size;
+    
+    fn add(self, size: Size) -> Size {
+        size + self
+    }
+}
+
+impl Mul<Size> for usize {
+    type Output = Size;
+    
+    fn mul(self, size: Size) -> Size {
+        size * self
+    }
+}
+
+// Comparisons with primitives in both directions, so that e.g. `n < INFINITY` and
+// `size >= 10` read naturally. ∞ exceeds every count and compares with floats as
+// f64::INFINITY; a finite size compares as its count.
+
+impl Size {
+    fn cmp_count(&self, n: u128) -> Ordering {
+        match self {
+            Size::Finite(m) => (*m as u128).cmp(&n),
+            Size::Infinite => Ordering::Greater,
+        }
+    }
+    
+    fn as_f64(&self) -> f64 {
+        match self {
+            Size::Finite(m) => *m as f64,
+            Size::Infinite => f64::INFINITY,
+        }
+    }
+}
+
+macro_rules! compare_with_counts {
+    ($($count:ty),+) => {
+        $(
+            impl PartialEq<$count> for Infinity {
+                fn eq(&self, _n: &$count) -> bool {
+                    false
+                }
+            }
+            
+            impl PartialOrd<$count> for Infinity {
+                fn partial_cmp(&self, _n: &$count) -> Option<Ordering> {
+                    Some(Ordering::Greater)
+                }
+            }
+            
+            impl PartialEq<Infinity> for $count {
+                fn eq(&self, _infinity: &Infinity) -> bool {
+                    false
+                }
+            }
+            
+            impl PartialOrd<Infinity> for $count {
+                fn partial_cmp(&self, _infinity: &Infinity) -> Option<Ordering> {
+                    Some(Ordering::Less)
+                }
+            }
+            
+            impl PartialEq<$count> for Size {
+                fn eq(&self, n: &$count) -> bool {
+                    self.cmp_count(*n as u128) == Ordering::Equal
+                }
+            }
+            
+            impl PartialOrd<$count> for Size {
+                fn partial_cmp(&self, n: &$count) -> Option<Ordering> {
+                    Some(self.cmp_count(*n as u128))
+                }
+            }
+            
+            impl PartialEq<Size> for $count {
+                fn eq(&self, size: &Size) -> bool {
+                    size == self
+                }
+            }
+            
+            impl PartialOrd<Size> for $count {
+                fn partial_cmp(&self, size: &Size) -> Option<Ordering> {
+                    size.partial_cmp(self).map(Ordering::reverse)
+                }
+            }
+        )+
+    };
+}
+
+compare_with_counts!(usize, u64);
+
+impl PartialEq<f64> for Infinity {
+    fn eq(&self, x: &f64) -> bool {
+        *x == f64::INFINITY
+    }
+}
+
+impl PartialOrd<f64> for Infinity {
+    fn partial_cmp(&self, x: &f64) -> Option<Ordering> {
+        f64::INFINITY.partial_cmp(x)
+    }
+}
+
+impl PartialEq<Infinity> for f64 {
+    fn eq(&self, _infinity: &Infinity) -> bool {
+        *self == f64::INFINITY
+    }
+}
+
+impl PartialOrd<Infinity> for f64 {
+    fn partial_cmp(&self, _infinity: &Infinity) -> Option<Ordering> {
+        self.partial_cmp(&f64::INFINITY)
+    }
+}
+
+impl PartialEq<f64> for Size {
+    fn eq(&self, x: &f64) -> bool {
+        self.as_f64() == *x
+    }
+}
+
+impl PartialOrd<f64> for Size {
+    fn partial_cmp(&self, x: &f64) -> Option<Ordering> {
+        self.as_f64().partial_cmp(x)
+    }
+}
+
+impl PartialEq<Size> for f64 {
+    fn eq(&self, size: &Size) -> bool {
+        *self == size.as_f64()
+    }
+}
+
+impl PartialOrd<Size> for f64 {
+    fn partial_cmp(&self, size: &Size) -> Option<Ordering> {
+        self.partial_cmp(&size.as_f64())
+    }
+}
+
+impl PartialEq<Infinity> for Size {
+    fn eq(&self, _infinity: &Infinity) -> bool {
+        self.is_infinite()
+    }
+}
+
+impl PartialOrd<Infinity> for Size {
+    fn partial_cmp(&self, _infinity: &Infinity) -> Option<Ordering> {
+        Some(if self.is_infinite() { Ordering::Equal } else { Ordering::Less })
+    }
+}
+
+impl PartialEq<Size> for Infinity {
+    fn eq(&self, size: &Size) -> bool {
+        size.is_infinite()
+    }
+}
+
+impl PartialOrd<Size> for Infinity {
+    fn partial_cmp(&self, size: &Size) -> Option<Ordering> {
+        size.partial_cmp(self).map(Ordering::reverse)
+    }
+}
+
+/// Values that may stand for an infinite quantity
+///
+/// Replaces type-based checks: sizes and infinities answer directly, floats by
+/// [`f64::is_infinite`], and `Option<usize>` is infinite when `None`. Plain counts
+/// are always finite; `usize::MAX` is not an "unbounded" sentinel, use
+/// [`Size::Infinite`] or `None` instead.
+pub trait MaybeInfinite {
+    fn is_infinite(&self) -> bool;
+    
+    fn is_finite(&self) -> bool {
+        !self.is_infinite()
+    }
+}
+
+impl MaybeInfinite for Infinity {
+    fn is_infinite(&self) -> bool {
+        true
     }
 }
 
+impl MaybeInfinite for SignedInfinity {
+    fn is_infinite(&self) -> bool {
+        true
+    }
+}
+
+impl MaybeInfinite for Size {
+    fn is_infinite(&self) -> bool {
+        Size::is_infinite(self)
+    }
+}
+
+impl MaybeInfinite for f64 {
+    fn is_infinite(&self) -> bool {
+        f64::is_infinite(*self)
+    }
+}
+
+impl MaybeInfinite for f32 {
+    fn is_infinite(&self) -> bool {
+        f32::is_infinite(*self)
+    }
+}
+
+impl MaybeInfinite for usize {
+    fn is_infinite(&self) -> bool {
+        false
+    }
+}
+
+impl MaybeInfinite for u64 {
+    fn is_infinite(&self) -> bool {
+        false
+    }
+}
+
+/// `None` is the unbounded value, as for [`Size::from`]
+impl MaybeInfinite for Option<usize> {
+    fn is_infinite(&self) -> bool {
+        self.is_none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn infinity_absorbs_finite_numbers() {
+        assert_eq!(Infinity + 5, Infinity);
+        assert_eq!(5 + Infinity, Infinity);
+        assert_eq!(5 * Infinity, Infinity);
+        assert_eq!(Infinity * 5, Size::Infinite);
+    }
+
+    #[test]
+    fn zero_times_infinity_is_zero_as_for_sizes() {
+        assert_eq!(0 * Infinity, Size::Finite(0));
+        assert_eq!(Infinity * 0, Size::Finite(0));
+        assert_eq!(Infinity * 0, Size::Infinite.checked_mul(0).unwrap());
+    }
+
+    #[test]
+    fn size_arithmetic() {
+        let n = Size::Finite(3);
+        assert_eq!(n + 4, Size::Finite(7));
+        assert_eq!(Size::Infinite + 4, Size::Infinite);
+        assert_eq!(Size::Infinite - 4, Size::Infinite);
+        assert_eq!(n * Infinity, Size::Infinite);
+        assert_eq!(Size::Finite(0) * Infinity, Size::Finite(0));
+        assert_eq!(2 * n, Size::Finite(6));
+        assert_eq!(n.checked_sub(4), None);
+        assert_eq!(Size::Infinite.checked_sub(Infinity), None);
+        assert_eq!(Size::Finite(usize::MAX).checked_add(1), None);
+        assert_eq!(Size::Infinite.min_count(10), 10);
+        assert_eq!(usize::try_from(Size::Infinite), Err(InfiniteSizeError));
+    }
+
+    #[test]
+    fn signed_infinity_arithmetic() {
+        assert_eq!(-Infinity, SignedInfinity::Negative);
+        assert_eq!(-SignedInfinity::Negative, SignedInfinity::Positive);
+        assert_eq!(f64::from(SignedInfinity::Positive) - 1.0, f64::INFINITY);
+        assert_eq!(SignedInfinity::Negative * -2.0, f64::INFINITY);
+        assert!((SignedInfinity::Positive + f64::NEG_INFINITY).is_nan());
+    }
+
+    #[test]
+    fn cardinal_infinity_converts_to_positive_real_infinity() {
+        assert_eq!(Infinity.to_real(), SignedInfinity::Positive);
+        assert_eq!(Infinity.to_real().signum(), 1);
+    }
+
+    #[test]
+    fn maybe_infinite_recognises_every_representation() {
+        assert!(MaybeInfinite::is_infinite(&Infinity));
+        assert!(MaybeInfinite::is_finite(&usize::MAX));
+        assert!(MaybeInfinite::is_finite(&u64::MAX));
+        assert!(MaybeInfinite::is_infinite(&None::<usize>));
+        assert!(MaybeInfinite::is_finite(&Size::Finite(3)));
+        assert!(MaybeInfinite::is_finite(&1.0f64));
+    }
+
+    #[test]
+    fn infinities_compare_with_floats_and_counts() {
+        assert!(1e300 < Infinity);
+        assert!(Infinity > f64::MAX);
+        assert_eq!(Infinity, f64::INFINITY);
+        assert_eq!(f64::INFINITY, Infinity);
+        assert_eq!(Infinity.partial_cmp(&f64::NAN), None);
+        assert_eq!(f64::NAN.partial_cmp(&Infinity), None);
+        assert!(Size::Finite(3) < Infinity && usize::MAX < Infinity);
+        assert!(Size::Infinite > 1e300 && Size::Infinite == f64::INFINITY);
+        assert!(Size::Finite(3) == 3.0 && 2.5 < Size::Finite(3));
+        assert!(SignedInfinity::Negative < isize::MIN);
+        assert!(SignedInfinity::Positive > isize::MAX);
+        assert!(SignedInfinity::Negative < f64::MIN && SignedInfinity::Positive == f64::INFINITY);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn infinity_is_serialized_as_the_infinity_sign() {
+        assert_eq!(serde_json::to_string(&Infinity).unwrap(), r#""∞""#);
+        assert_eq!(serde_json::to_string(&Some(Infinity)).unwrap(), r#""∞""#);
+        assert_eq!(serde_json::to_string(&None::<Infinity>).unwrap(), "null");
+        for text in [r#""∞""#, r#""inf""#, r#""Infinity""#] {
+            assert_eq!(serde_json::from_str::<Infinity>(text).unwrap(), Infinity);
+        }
+        assert!(serde_json::from_str::<Infinity>(r#""infinite""#).is_err());
+
+        for size in [Size::Finite(0), Size::Finite(7), Size::Infinite] {
+            let json = serde_json::to_string(&size).unwrap();
+            assert_eq!(serde_json::from_str::<Size>(&json).unwrap(), size);
+        }
+    }
+}
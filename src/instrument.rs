@@ -0,0 +1,34 @@
+//! Optional `tracing` instrumentation.
+//!
+//! With the `tracing` feature the macros below forward to `tracing`; without it
+//! they expand to nothing, so the instrumented loops carry no cost.
+
+/// Per-iteration event (shifts, residuals), emitted at TRACE level
+#[cfg(feature = "tracing")]
+macro_rules! trace_event {
+    ($($arg:tt)*) => { tracing::trace!($($arg)*) };
+}
+#[cfg(not(feature = "tracing"))]
+macro_rules! trace_event {
+    ($($arg:tt)*) => {};
+}
+
+/// Summary event (convergence, restarts, truncation sizes), emitted at DEBUG level
+#[cfg(feature = "tracing")]
+macro_rules! debug_event {
+    ($($arg:tt)*) => { tracing::debug!($($arg)*) };
+}
+#[cfg(not(feature = "tracing"))]
+macro_rules! debug_event {
+    ($($arg:tt)*) => {};
+}
+
+/// Reliability problem worth surfacing, emitted at WARN level
+#[cfg(feature = "tracing")]
+macro_rules! warn_event {
+    ($($arg:tt)*) => { tracing::warn!($($arg)*) };
+}
+#[cfg(not(feature = "tracing"))]
+macro_rules! warn_event {
+    ($($arg:tt)*) => {};
+}
@@ -1,324 +1,2989 @@
-//! Infinite-dimensional QR algorithm implementation.
-//!
-//! This module implements the infinite-dimensional QR (IQR) algorithm as described in:
-//! Colbrook, M.J. & Hansen, A.C. "On the infinite-dimensional QR algorithm"
-//! Numer. Math. 143, 17-83 (2019).
-
-use ndarray::Array2;
-use num_complex::Complex64;
-use std::collections::HashMap;
-use std::sync::{Arc, Mutex};
-
-/// Represents an infinite-dimensional operator on l^2(N)
-pub struct InfiniteOperator {
-    matrix_func: Arc<dyn Fn(usize, usize) -> Complex64 + Send + Sync>,
-    cache: Arc<Mutex<HashMap<(usize, usize), Complex64>>>,
-}
-
-impl InfiniteOperator {
-    pub fn new<F>(matrix_func: F) -> Self
-    where
-        F: Fn(usize, usize) -> Complex64 + Send + Sync + 'static,
-    {
-        InfiniteOperator {
-            matrix_func: Arc::new(matrix_func),
-            cache: Arc::new(Mutex::new(HashMap::new())),
-        }
-    }
-    
-    /// Get matrix element at position (i, j)
-    pub fn get(&self, i: usize, j: usize) -> Complex64 {
-        // Check cache
-        {
-            let cache = self.cache.lock().unwrap();
-            if let Some(&value) = cache.get(&(i, j)) {
-                return value;
-            }
-        }
-        
-        // Compute and cache
-        let value = (self.matrix_func)(i, j);
-        {
-            let mut cache = self.cache.lock().unwrap();
-            cache.insert((i, j), value);
-        }
-        
-        value
-    }
-    
-    /// Get a finite n×n truncation of the operator
-    pub fn get_truncation(&self, n: usize) -> Array2<Complex64> {
-        let mut matrix = Array2::<Complex64>::zeros((n, n));
-        for i in 0..n {
-            for j in 0..n {
-                matrix[(i, j)] = self.get(i, j);
-            }
-        }
-        matrix
-    }
-}
-
-/// Result of IQR algorithm
-#[derive(Debug, Clone)]
-pub struct IqrResult {
-    pub eigenvalues: Vec<Complex64>,
-    pub eigenvectors: Option<Array2<Complex64>>,
-    pub iterations: usize,
-    pub converged: bool,
-    pub residual: Option<f64>,
-}
-
-/// Infinite-dimensional QR algorithm for computing spectra
-pub fn iqr_algorithm(
-    operator: &InfiniteOperator,
-    n: usize,
-    max_iter: usize,
-    tol: f64,
-    shift: Option<Complex64>,
-    compute_eigenvectors: bool,
-) -> IqrResult {
-    // Get finite truncation
-    let mut a = operator.get_truncation(n);
-    
-    // Initialize eigenvector matrix if needed
-    let mut q_total = if compute_eigenvectors {
-        Some(Array2::<Complex64>::eye(n))
-    } else {
-        None
-    };
-    
-    // QR iteration
-    let mut iterations = 0;
-    let mut converged = false;
-    let mut max_off_diag = f64::INFINITY;
-    
-    for k in 0..max_iter {
-        // Compute shift (Wilkinson shift for better convergence)
-        let shift_val = if let Some(s) = shift {
-            s
-        } else {
-            // Wilkinson shift: use eigenvalue of bottom-right 2x2 block
-            if n >= 2 {
-                let a_val = a[(n-2, n-2)];
-                let b_val = a[(n-2, n-1)];
-                let c_val = a[(n-1, n-2)];
-                let d_val = a[(n-1, n-1)];
-                
-                // Eigenvalue of 2x2 matrix closest to d
-                let trace = a_val + d_val;
-                let det = a_val * d_val - b_val * c_val;
-                let discriminant = trace * trace - Complex64::new(4.0, 0.0) * det;
-                
-                if discriminant.re >= 0.0 {
-                    let sqrt_disc = Complex64::new(discriminant.re.sqrt(), 0.0);
-                    let lambda1 = (trace + sqrt_disc) / Complex64::new(2.0, 0.0);
-                    let lambda2 = (trace - sqrt_disc) / Complex64::new(2.0, 0.0);
-                    
-                    let dist1 = (lambda1 - d_val).norm();
-                    let dist2 = (lambda2 - d_val).norm();
-                    if dist2 < dist1 {
-                        lambda2
-                    } else {
-                        lambda1
-                    }
-                } else {
-                    trace / Complex64::new(2.0, 0.0)
-                }
-            } else {
-                a[(0, 0)]
-            }
-        };
-        
-        // Shift the matrix
-        let mut a_shifted = a.clone();
-        for i in 0..n {
-            a_shifted[(i, i)] = a_shifted[(i, i)] - shift_val;
-        }
-        
-        // QR decomposition (simplified - using ndarray's QR)
-        // Note: ndarray doesn't have built-in QR, so we'll use a simple implementation
-        let (q, r) = qr_decomposition(&a_shifted);
-        
-        // Reverse QR: A = R * Q + shift
-        a = r.dot(&q);
-        for i in 0..n {
-            a[(i, i)] = a[(i, i)] + shift_val;
-        }
-        
-        // Accumulate eigenvectors if needed
-        if let Some(q_tot) = &mut q_total {
-            *q_tot = q_tot.dot(&q);
-        }
-        
-        iterations = k + 1;
-        
-        // Check convergence: off-diagonal elements should be small
-        max_off_diag = 0.0;
-        for i in 0..n {
-            for j in 0..n {
-                if i != j {
-                    let val = a[(i, j)].norm();
-                    if val > max_off_diag {
-                        max_off_diag = val;
-                    }
-                }
-            }
-        }
-        
-        if max_off_diag < tol {
-            converged = true;
-            break;
-        }
-    }
-    
-    // Extract eigenvalues from diagonal
-    let mut eigenvalues: Vec<Complex64> = (0..n).map(|i| a[(i, i)]).collect();
-    
-    // Sort by magnitude
-    eigenvalues.sort_by(|a, b| b.norm().partial_cmp(&a.norm()).unwrap_or(std::cmp::Ordering::Equal));
-    
-    let mut result = IqrResult {
-        eigenvalues,
-        eigenvectors: None,
-        iterations,
-        converged,
-        residual: if converged { Some(max_off_diag) } else { None },
-    };
-    
-    if compute_eigenvectors {
-        if let Some(q_tot) = q_total {
-            // Reorder eigenvectors to match sorted eigenvalues
-            // This is simplified - full implementation would need to track eigenvalue order
-            result.eigenvectors = Some(q_tot);
-        }
-    }
-    
-    result
-}
-
-/// Simple QR decomposition using Gram-Schmidt
-fn qr_decomposition(a: &Array2<Complex64>) -> (Array2<Complex64>, Array2<Complex64>) {
-    let n = a.nrows();
-    let mut q = Array2::<Complex64>::zeros((n, n));
-    let mut r = Array2::<Complex64>::zeros((n, n));
-    
-        // Gram-Schmidt process
-    for j in 0..n {
-        let mut v = a.column(j).to_owned();
-        
-        for i in 0..j {
-            let r_ij = q.column(i).dot(&v);
-            r[(i, j)] = r_ij;
-            let q_col = q.column(i).to_owned();
-            let scaled = q_col.mapv(|x| x * r_ij);
-            v = &v - &scaled;
-        }
-        
-        let norm = v.mapv(|x| x.norm()).sum().sqrt();
-        if norm > 1e-10 {
-            r[(j, j)] = Complex64::new(norm, 0.0);
-            let q_col = &v / Complex64::new(norm, 0.0);
-            for i in 0..n {
-                q[(i, j)] = q_col[i];
-            }
-        } else {
-            r[(j, j)] = Complex64::new(0.0, 0.0);
-            for i in 0..n {
-                q[(i, j)] = if i == j { Complex64::new(1.0, 0.0) } else { Complex64::new(0.0, 0.0) };
-            }
-        }
-    }
-    
-    (q, r)
-}
-
-/// Compute spectrum using IQR algorithm with adaptive truncation
-pub fn iqr_spectrum(
-    operator: &InfiniteOperator,
-    n_range: &[usize],
-    max_iter: usize,
-    tol: f64,
-) -> IqrSpectrumResult {
-    let mut results_by_n = HashMap::new();
-    
-    for &n in n_range {
-        let result = iqr_algorithm(operator, n, max_iter, tol, None, false);
-        results_by_n.insert(n, result);
-    }
-    
-    // Use eigenvalues from largest truncation as estimate
-    let largest_n = *n_range.iter().max().unwrap();
-    let eigenvalues = results_by_n.get(&largest_n).unwrap().eigenvalues.clone();
-    
-    let converged = {
-        let results_ref = &results_by_n;
-        n_range.iter().all(|&n| results_ref.get(&n).unwrap().converged)
-    };
-    IqrSpectrumResult {
-        eigenvalues,
-        eigenvalues_by_n: results_by_n,
-        converged,
-        recommended_n: largest_n,
-    }
-}
-
-/// Result of spectrum computation
-#[derive(Debug, Clone)]
-pub struct IqrSpectrumResult {
-    pub eigenvalues: Vec<Complex64>,
-    pub eigenvalues_by_n: HashMap<usize, IqrResult>,
-    pub converged: bool,
-    pub recommended_n: usize,
-}
-
-/// Create an infinite diagonal operator
-pub fn create_diagonal_operator<F>(diagonal_values: F) -> InfiniteOperator
-where
-    F: Fn(usize) -> Complex64 + Send + Sync + 'static,
-{
-    let func = Arc::new(diagonal_values);
-    InfiniteOperator::new(move |i, j| {
-        if i == j {
-            (func)(i)
-        } else {
-            Complex64::new(0.0, 0.0)
-        }
-    })
-}
-
-/// Create an infinite tridiagonal operator
-pub fn create_tridiagonal_operator<F1, F2, F3>(
-    main_diag: F1,
-    upper_diag: Option<F2>,
-    lower_diag: Option<F3>,
-) -> InfiniteOperator
-where
-    F1: Fn(usize) -> Complex64 + Send + Sync + 'static,
-    F2: Fn(usize) -> Complex64 + Send + Sync + 'static,
-    F3: Fn(usize) -> Complex64 + Send + Sync + 'static,
-{
-    let main_func = Arc::new(main_diag);
-    let upper_func: Arc<dyn Fn(usize) -> Complex64 + Send + Sync> = if let Some(f) = upper_diag {
-        Arc::new(f)
-    } else {
-        Arc::new(|_| Complex64::new(0.0, 0.0))
-    };
-    let lower_func: Arc<dyn Fn(usize) -> Complex64 + Send + Sync> = if let Some(f) = lower_diag {
-        Arc::new(f)
-    } else {
-        Arc::new(|_| Complex64::new(0.0, 0.0))
-    };
-    
-    InfiniteOperator::new(move |i, j| {
-        if i == j {
-            (main_func)(i)
-        } else if j == i + 1 {
-            (upper_func)(i)
-        } else if i > 0 && j == i - 1 {
-            (lower_func)(j)
-        } else {
-            Complex64::new(0.0, 0.0)
-        }
-    })
-}
-
+//! Infinite-dimensional QR algorithm implementation.
+//!
+//! This module implements the infinite-dimensional QR (IQR) algorithm as described in:
+//! Colbrook, M.J. & Hansen, A.C. "On the infinite-dimensional QR algorithm"
+//! Numer. Math. 143, 17-83 (2019).
+
+use crate::arrays::{InfiniteArray, Shape};
+use crate::broadcasting::BroadcastArray;
+use crate::cache::CachedArray;
+use crate::dims::{Dims, Finite, Inf};
+use crate::display;
+use crate::error::InfiniteArraysError;
+#[cfg(feature = "gpu")]
+use crate::gpu;
+use crate::infinity::Size;
+use crate::linalg;
+use crate::operators::{adaptive_sum, diagonal_fn, BandedOperator, ComposedOperator, DenseOperator, DiagonalFn, DiagonalOperator, LinearOperator};
+use crate::pseudospectra::{spectral_error_bounds, Dispersion};
+use crate::spectra::{compare_spectra, distance_to_set, match_eigenvalues};
+use crate::sync::{Lock, LockValues};
+use ndarray::{s, Array2, Axis};
+use num_complex::Complex64;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::fs::File;
+use std::hash::{BuildHasherDefault, Hasher};
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::ops::{Add, Mul, Range};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Hasher for the (row, column) keys of the entry cache
+///
+/// The keys are indices chosen by the caller, so the flooding resistance of the
+/// default SipHash buys nothing here; a multiply-rotate mix is several times cheaper
+/// and dominates the cost of assembling truncations from cached entries.
+#[derive(Default)]
+struct IndexHasher(u64);
+
+impl Hasher for IndexHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+    
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.write_u64(u64::from(byte));
+        }
+    }
+    
+    fn write_usize(&mut self, i: usize) {
+        self.write_u64(i as u64);
+    }
+    
+    fn write_u64(&mut self, i: u64) {
+        self.0 = (self.0.rotate_left(5) ^ i).wrapping_mul(0x517c_c1b7_2722_0a95);
+    }
+}
+
+/// Cache of evaluated operator entries
+type EntryCache = HashMap<(usize, usize), Complex64, BuildHasherDefault<IndexHasher>>;
+
+/// Fail on the first entry of a truncation that is NaN or infinite
+fn check_finite(matrix: &Array2<Complex64>) -> Result<(), InfiniteArraysError> {
+    match matrix.indexed_iter().find(|(_, value)| !value.is_finite()) {
+        Some(((row, column), &value)) => Err(InfiniteArraysError::NonFiniteElement { row, column, value }),
+        None => Ok(()),
+    }
+}
+
+/// Represents an infinite-dimensional operator on l^2(N)
+///
+/// A cached handle around a [`LinearOperator`]: entries are evaluated once and the
+/// structure of the underlying operator (bandwidths, symmetry) decides which entries
+/// are evaluated at all. Clones share the operator and the entry cache, which only
+/// holds finished entries and stays usable after a panic on another thread.
+#[derive(Clone)]
+pub struct InfiniteOperator {
+    operator: Arc<dyn LinearOperator>,
+    cache: Arc<Lock<EntryCache>>,
+    bandwidths: Option<(usize, usize)>,
+    /// Whether `matvec` goes to the wrapped operator rather than the entry cache
+    structured: bool,
+}
+
+impl InfiniteOperator {
+    /// Operator without known structure; see [`InfiniteOperator::with_bandwidths`]
+    pub fn new<F>(matrix_func: F) -> Self
+    where
+        F: Fn(usize, usize) -> Complex64 + Send + Sync + 'static,
+    {
+        InfiniteOperator {
+            structured: false,
+            ..InfiniteOperator::from_operator(DenseOperator::new(matrix_func))
+        }
+    }
+    
+    /// Cached handle around a structured operator
+    ///
+    /// Products with vectors use the operator's own [`LinearOperator::matvec`], so
+    /// structure such as a diagonal or a Toeplitz symbol is exploited there too.
+    pub fn from_operator(operator: impl LinearOperator + 'static) -> Self {
+        let bandwidths = operator.bandwidths();
+        InfiniteOperator {
+            operator: Arc::new(operator),
+            cache: Arc::new(Lock::new(EntryCache::default())),
+            bandwidths,
+            structured: true,
+        }
+    }
+    
+    /// Banded operator given by its nonzero diagonals, see also [`crate::banded_operator!`]
+    ///
+    /// Offset 0 is the main diagonal, positive offsets lie above it and negative ones
+    /// below. Entry k of a diagonal sits at (k, k + offset) above the main diagonal
+    /// and at (k - offset, k) below it, i.e. it is indexed by the smaller of row and
+    /// column. The bandwidths are those of the outermost diagonals; panics if an
+    /// offset is given twice.
+    pub fn from_diagonals(diagonals: Vec<(isize, DiagonalFn)>) -> Self {
+        let lower = diagonals.iter().map(|&(offset, _)| offset.min(0).unsigned_abs()).max().unwrap_or(0);
+        let upper = diagonals.iter().map(|&(offset, _)| offset.max(0) as usize).max().unwrap_or(0);
+        let mut bands: Vec<Option<DiagonalFn>> = vec![None; lower + upper + 1];
+        for (offset, values) in diagonals {
+            let slot = &mut bands[(offset + lower as isize) as usize];
+            assert!(slot.is_none(), "diagonal {} is given twice", offset);
+            *slot = Some(values);
+        }
+        InfiniteOperator::from_operator(BandedOperator::new(lower, upper, move |i, j| match &bands[j + lower - i] {
+            Some(values) => values(i.min(j)),
+            None => Complex64::new(0.0, 0.0),
+        }))
+    }
+    
+    /// Whether the underlying operator is known to be Hermitian
+    pub fn is_hermitian(&self) -> bool {
+        self.operator.is_hermitian()
+    }
+    
+    /// Operator given explicitly on a finite upper-left block and by `tail` elsewhere
+    ///
+    /// Entry (i, j) is `finite[(i, j)]` when it lies inside the block and `tail(i, j)`
+    /// otherwise, as for discretizations with a boundary region followed by a
+    /// regular interior. The block need not be square.
+    pub fn from_finite_and_tail<F>(finite: Array2<Complex64>, tail: F) -> Self
+    where
+        F: Fn(usize, usize) -> Complex64 + Send + Sync + 'static,
+    {
+        let (rows, columns) = finite.dim();
+        InfiniteOperator::new(move |i, j| {
+            if i < rows && j < columns {
+                finite[(i, j)]
+            } else {
+                tail(i, j)
+            }
+        })
+    }
+    
+    /// Declare that the operator is banded with the given lower and upper bandwidths
+    ///
+    /// Entries outside the band are assumed to be zero and are never evaluated.
+    pub fn with_bandwidths(mut self, lower: usize, upper: usize) -> Self {
+        self.bandwidths = Some((lower, upper));
+        // The wrapped operator's own product does not know about the declared band
+        self.structured &= self.operator.bandwidths() == self.bandwidths;
+        self
+    }
+    
+    /// Lower and upper bandwidths, if the operator is known to be banded
+    pub fn bandwidths(&self) -> Option<(usize, usize)> {
+        self.bandwidths
+    }
+    
+    /// The operator with its ∞×∞ size in the type
+    pub fn typed(self) -> Dims<Self, Inf, Inf> {
+        Dims::new(self)
+    }
+    
+    /// The ∞×N operator formed by the first N columns, sharing the entry cache
+    ///
+    /// Routines taking it, such as [`crate::factorizations::tall_lsq_solve`], only
+    /// evaluate columns 0..N.
+    pub fn first_columns<const N: usize>(&self) -> Dims<Self, Inf, Finite<N>> {
+        Dims::new(self.clone())
+    }
+    
+    /// Columns of row i inside the n×n truncation that may hold nonzero entries
+    fn row_support(&self, i: usize, n: usize) -> Range<usize> {
+        match self.bandwidths {
+            Some((lower, upper)) => i.saturating_sub(lower).min(n)..(i + upper + 1).min(n),
+            None => 0..n,
+        }
+    }
+    
+    /// Rows of column j inside the n×n truncation that may hold nonzero entries
+    fn column_support(&self, j: usize, n: usize) -> Range<usize> {
+        match self.bandwidths {
+            Some((lower, upper)) => j.saturating_sub(upper).min(n)..(j + lower + 1).min(n),
+            None => 0..n,
+        }
+    }
+    
+    /// Whether (i, j) lies inside the declared band (always true without bandwidths)
+    fn in_band(&self, i: usize, j: usize) -> bool {
+        match self.bandwidths {
+            Some((lower, upper)) => j + lower >= i && j <= i + upper,
+            None => true,
+        }
+    }
+    
+    /// Entry (i, j) without adding it to the cache
+    ///
+    /// Used for the terms of adaptively truncated inner sums, which touch far more
+    /// entries than are ever read again.
+    pub(crate) fn get_uncached(&self, i: usize, j: usize) -> Complex64 {
+        if !self.in_band(i, j) {
+            return Complex64::new(0.0, 0.0);
+        }
+        if let Some(&value) = self.cache.lock_values().get(&(i, j)) {
+            return value;
+        }
+        self.operator.get(i, j)
+    }
+    
+    /// Get matrix element at position (i, j)
+    ///
+    /// Entries outside the declared band are zero and are neither evaluated nor cached.
+    pub fn get(&self, i: usize, j: usize) -> Complex64 {
+        if !self.in_band(i, j) {
+            return Complex64::new(0.0, 0.0);
+        }
+        
+        // Check cache
+        if let Some(&value) = self.cache.lock_values().get(&(i, j)) {
+            return value;
+        }
+        
+        // Compute and cache
+        let value = self.operator.get(i, j);
+        self.cache.lock_values().insert((i, j), value);
+        
+        value
+    }
+    
+    /// Get a finite n×n truncation of the operator
+    ///
+    /// For banded operators only the entries inside the band are evaluated.
+    pub fn get_truncation(&self, n: usize) -> Array2<Complex64> {
+        let mut matrix = Array2::<Complex64>::zeros((n, n));
+        self.fill_truncation(&mut matrix);
+        matrix
+    }
+    
+    /// [`InfiniteOperator::get_truncation`], reporting failures instead of panicking
+    ///
+    /// Fails for `n = 0` or an entry that is NaN or infinite.
+    pub fn try_get_truncation(&self, n: usize) -> Result<Array2<Complex64>, InfiniteArraysError> {
+        if n == 0 {
+            return Err(InfiniteArraysError::EmptyTruncation);
+        }
+        let mut matrix = Array2::<Complex64>::zeros((n, n));
+        self.fill_truncation(&mut matrix);
+        check_finite(&matrix)?;
+        Ok(matrix)
+    }
+    
+    /// Fill a zero square matrix with the truncation of its size
+    ///
+    /// Hermitian operators are evaluated on and above the diagonal only; the entries
+    /// below are the conjugates of their mirror images.
+    fn fill_truncation(&self, matrix: &mut Array2<Complex64>) {
+        let n = matrix.nrows();
+        let hermitian = self.is_hermitian();
+        let data = matrix.as_slice_mut().expect("new matrices are in standard layout");
+        for (i, row) in data.chunks_exact_mut(n.max(1)).enumerate() {
+            let support = self.row_support(i, n);
+            let columns = if hermitian { support.start.max(i)..support.end } else { support };
+            self.fill_row(i, columns.clone(), &mut row[columns]);
+        }
+        if hermitian {
+            for i in 0..n {
+                for j in self.row_support(i, n).start..i {
+                    matrix[(i, j)] = matrix[(j, i)].conj();
+                }
+            }
+        }
+    }
+    
+    /// Write the entries (i, j), j in `columns`, into `out`
+    ///
+    /// The cache lock is taken once for the lookups and once for the insertions,
+    /// rather than twice per entry as with [`InfiniteOperator::get`].
+    fn fill_row(&self, i: usize, columns: Range<usize>, out: &mut [Complex64]) {
+        self.fill_entries(columns.map(|j| (i, j)), out)
+    }
+    
+    /// Write the entries (i, j), i in `rows`, into `out`, locking as in `fill_row`
+    fn fill_column(&self, j: usize, rows: Range<usize>, out: &mut [Complex64]) {
+        self.fill_entries(rows.map(|i| (i, j)), out)
+    }
+    
+    fn fill_entries(&self, keys: impl Iterator<Item = (usize, usize)> + Clone, out: &mut [Complex64]) {
+        let mut missing = Vec::new();
+        {
+            let cache = self.cache.lock_values();
+            for (slot, (offset, key)) in out.iter_mut().zip(keys.enumerate()) {
+                match cache.get(&key) {
+                    Some(&value) => *slot = value,
+                    None => missing.push((offset, key)),
+                }
+            }
+        }
+        if missing.is_empty() {
+            return;
+        }
+        
+        // Evaluate outside the lock, as in `get`
+        let values: Vec<Complex64> = missing.iter().map(|&(_, (i, j))| self.operator.get(i, j)).collect();
+        let mut cache = self.cache.lock_values();
+        for (&(offset, key), value) in missing.iter().zip(values) {
+            cache.insert(key, value);
+            out[offset] = value;
+        }
+    }
+    
+    /// Entries 0..n of row i
+    ///
+    /// Only the entries inside the band are evaluated, with one lookup pass over the
+    /// entry cache instead of a lock per entry.
+    pub fn get_row(&self, i: usize, n: usize) -> Vec<Complex64> {
+        let mut row = vec![Complex64::new(0.0, 0.0); n];
+        let support = self.row_support(i, n);
+        self.fill_row(i, support.clone(), &mut row[support]);
+        row
+    }
+    
+    /// Entries 0..n of column j, evaluated like [`InfiniteOperator::get_row`]
+    ///
+    /// Suited to algorithms that consume the operator column by column, such as
+    /// Arnoldi and adaptive QR.
+    pub fn get_column(&self, j: usize, n: usize) -> Vec<Complex64> {
+        let mut column = vec![Complex64::new(0.0, 0.0); n];
+        let support = self.column_support(j, n);
+        self.fill_column(j, support.clone(), &mut column[support]);
+        column
+    }
+    
+    /// Get a finite n×n truncation of the operator, evaluating entries in parallel
+    ///
+    /// Worthwhile when the matrix function is expensive. The entry cache is locked
+    /// once to look up the band and once to insert the new entries, so the threads
+    /// evaluating them never contend for it. Hermitian operators are evaluated on and
+    /// above the diagonal only, as in [`InfiniteOperator::get_truncation`].
+    #[cfg(feature = "rayon")]
+    pub fn par_get_truncation(&self, n: usize) -> Array2<Complex64> {
+        let hermitian = self.is_hermitian();
+        let keys: Vec<(usize, usize)> = (0..n)
+            .flat_map(|i| {
+                let support = self.row_support(i, n);
+                let columns = if hermitian { support.start.max(i)..support.end } else { support };
+                columns.map(move |j| (i, j))
+            })
+            .collect();
+        
+        let mut matrix = Array2::<Complex64>::zeros((n, n));
+        let values = self.par_fill_entries(&keys);
+        for (&(i, j), value) in keys.iter().zip(values) {
+            matrix[(i, j)] = value;
+            if hermitian && j != i {
+                matrix[(j, i)] = value.conj();
+            }
+        }
+        matrix
+    }
+    
+    /// The entries at `keys`, evaluating the uncached ones in parallel
+    #[cfg(feature = "rayon")]
+    fn par_fill_entries(&self, keys: &[(usize, usize)]) -> Vec<Complex64> {
+        let mut values = vec![Complex64::new(0.0, 0.0); keys.len()];
+        let mut missing = Vec::new();
+        {
+            let cache = self.cache.lock_values();
+            for (offset, key) in keys.iter().enumerate() {
+                match cache.get(key) {
+                    Some(&value) => values[offset] = value,
+                    None => missing.push(offset),
+                }
+            }
+        }
+        if missing.is_empty() {
+            return values;
+        }
+        
+        let computed: Vec<Complex64> = missing
+            .par_iter()
+            .map(|&offset| {
+                let (i, j) = keys[offset];
+                self.operator.get(i, j)
+            })
+            .collect();
+        let mut cache = self.cache.lock_values();
+        cache.reserve(missing.len());
+        for (offset, value) in missing.into_iter().zip(computed) {
+            cache.insert(keys[offset], value);
+            values[offset] = value;
+        }
+        values
+    }
+    
+    /// Get the n×n truncation in compressed sparse row form
+    ///
+    /// Only entries inside the declared band are evaluated, and those that are exactly
+    /// zero are dropped, so diagonal and banded operators give O(n) storage for
+    /// sparse eigensolvers and direct solvers.
+    #[cfg(feature = "sprs")]
+    pub fn get_sparse_truncation(&self, n: usize) -> sprs::CsMat<Complex64> {
+        let mut indptr = Vec::with_capacity(n + 1);
+        let mut indices = Vec::new();
+        let mut data = Vec::new();
+        indptr.push(0);
+        let mut row = Vec::new();
+        for i in 0..n {
+            let support = self.row_support(i, n);
+            row.clear();
+            row.resize(support.len(), Complex64::new(0.0, 0.0));
+            self.fill_row(i, support.clone(), &mut row);
+            for (j, &value) in support.zip(&row) {
+                if value != Complex64::new(0.0, 0.0) {
+                    indices.push(j);
+                    data.push(value);
+                }
+            }
+            indptr.push(indices.len());
+        }
+        sprs::CsMat::new((n, n), indptr, indices, data)
+    }
+    
+    /// Grow an existing m×m truncation to the n×n truncation in place
+    ///
+    /// Only the new border (rows and columns m..n) is evaluated, so building a
+    /// sequence of nested truncations costs no more than building the largest one.
+    /// If n < m the matrix is cut down to its leading n×n block.
+    pub fn extend_truncation(&self, matrix: &mut Array2<Complex64>, n: usize) {
+        let m = matrix.nrows();
+        assert_eq!(m, matrix.ncols(), "truncation must be square");
+        if n <= m {
+            *matrix = matrix.slice(s![..n, ..n]).to_owned();
+            return;
+        }
+        
+        let mut grown = Array2::<Complex64>::zeros((n, n));
+        grown.slice_mut(s![..m, ..m]).assign(matrix);
+        let data = grown.as_slice_mut().expect("new matrices are in standard layout");
+        for (i, row) in data.chunks_exact_mut(n).enumerate() {
+            let support = self.row_support(i, n);
+            // Rows of the old block only need their new columns
+            let columns = if i < m { support.start.max(m)..support.end } else { support };
+            if !columns.is_empty() {
+                self.fill_row(i, columns.clone(), &mut row[columns]);
+            }
+        }
+        *matrix = grown;
+    }
+    
+    /// Multiply by a finitely supported vector
+    ///
+    /// For banded operators the product is exact and has `x.len() + lower` entries.
+    /// Otherwise the product is compressed to the first `x.len()` rows. Operators
+    /// built with [`InfiniteOperator::from_operator`] use their own
+    /// [`LinearOperator::matvec`]; closures go through the entry cache.
+    pub fn matvec(&self, x: &[Complex64]) -> Vec<Complex64> {
+        if self.structured {
+            return self.operator.matvec(x);
+        }
+        let rows = match self.bandwidths {
+            Some((lower, _)) => x.len() + lower,
+            None => x.len(),
+        };
+        (0..rows)
+            .map(|i| {
+                let support = self.row_support(i, x.len());
+                let mut row = vec![Complex64::new(0.0, 0.0); support.len()];
+                self.fill_row(i, support.clone(), &mut row);
+                row.iter().zip(&x[support]).map(|(a, b)| a * b).sum()
+            })
+            .collect()
+    }
+    
+    /// Lazily apply the operator to an infinite vector, keeping the real part
+    ///
+    /// Entry i of the result is the row sum Σⱼ A[i, j] x[j], computed when first read
+    /// and then cached. Banded rows are summed exactly; otherwise terms are added in
+    /// blocks of doubling length until a block past the diagonal contributes at most
+    /// `tol` relative to the row's absolute sum.
+    pub fn apply(&self, x: Arc<dyn InfiniteArray>, tol: f64) -> CachedArray {
+        CachedArray::new(Arc::new(AppliedOperator {
+            operator: self.clone(),
+            x,
+            tol,
+            imaginary: false,
+        }))
+    }
+    
+    /// Imaginary part of [`InfiniteOperator::apply`]
+    pub fn apply_imag(&self, x: Arc<dyn InfiniteArray>, tol: f64) -> CachedArray {
+        CachedArray::new(Arc::new(AppliedOperator {
+            operator: self.clone(),
+            x,
+            tol,
+            imaginary: true,
+        }))
+    }
+    
+    /// Lazily apply the operator to a complex infinite vector, as [`InfiniteOperator::apply`]
+    pub fn apply_complex(&self, x: Arc<dyn InfiniteArray<Complex64>>, tol: f64) -> CachedArray<Complex64> {
+        let operator = self.clone();
+        CachedArray::new(Arc::new(BroadcastArray::new(
+            move |i| operator.row_sum(i, x.as_ref(), tol),
+            Shape::OneD(Size::Infinite),
+        )))
+    }
+    
+    /// Row sum Σⱼ A[i, j] x[j], truncated adaptively for non-banded rows
+    fn row_sum<T: Into<Complex64>>(&self, i: usize, x: &dyn InfiniteArray<T>, tol: f64) -> Complex64 {
+        if let Some((lower, upper)) = self.bandwidths {
+            return (i.saturating_sub(lower)..=i + upper).map(|j| self.get(i, j) * x.get(j).into()).sum();
+        }
+        adaptive_sum(|j| self.get_uncached(i, j) * x.get(j).into(), i, tol)
+    }
+    
+    /// Lazy product AB, with inner sums truncated to `tol` unless both factors are banded
+    pub fn product(&self, other: &InfiniteOperator, tol: f64) -> InfiniteOperator {
+        InfiniteOperator::from_operator(ComposedOperator::Product(self.clone(), other.clone(), tol))
+    }
+    
+    /// Lazy multiple cA
+    pub fn scale(&self, c: Complex64) -> InfiniteOperator {
+        InfiniteOperator::from_operator(ComposedOperator::Scaled(self.clone(), c))
+    }
+}
+
+impl fmt::Debug for InfiniteOperator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let inf = display::infinity();
+        write!(f, "InfiniteOperator({}{}{}", inf, display::times(), inf)?;
+        if let Some((lower, upper)) = self.bandwidths {
+            write!(f, ", bandwidths ({}, {})", lower, upper)?;
+        }
+        write!(f, ")")
+    }
+}
+
+impl LinearOperator for InfiniteOperator {
+    fn get(&self, i: usize, j: usize) -> Complex64 {
+        InfiniteOperator::get(self, i, j)
+    }
+    
+    fn bandwidths(&self) -> Option<(usize, usize)> {
+        self.bandwidths
+    }
+    
+    fn is_hermitian(&self) -> bool {
+        InfiniteOperator::is_hermitian(self)
+    }
+    
+    fn matvec(&self, x: &[Complex64]) -> Vec<Complex64> {
+        InfiniteOperator::matvec(self, x)
+    }
+    
+    #[cfg(feature = "rayon")]
+    fn par_truncate(&self, n: usize) -> Array2<Complex64> {
+        self.par_get_truncation(n)
+    }
+}
+
+/// Tolerance of the inner sums of `A * B` for non-banded operators
+pub const PRODUCT_TOL: f64 = 1e-14;
+
+impl Add for &InfiniteOperator {
+    type Output = InfiniteOperator;
+    
+    /// Lazy sum A + B; banded if both operands are
+    fn add(self, other: &InfiniteOperator) -> InfiniteOperator {
+        InfiniteOperator::from_operator(ComposedOperator::Sum(self.clone(), other.clone()))
+    }
+}
+
+impl Add for InfiniteOperator {
+    type Output = InfiniteOperator;
+    
+    fn add(self, other: InfiniteOperator) -> InfiniteOperator {
+        &self + &other
+    }
+}
+
+impl Add<InfiniteOperator> for &InfiniteOperator {
+    type Output = InfiniteOperator;
+    
+    fn add(self, other: InfiniteOperator) -> InfiniteOperator {
+        self + &other
+    }
+}
+
+impl Add<&InfiniteOperator> for InfiniteOperator {
+    type Output = InfiniteOperator;
+    
+    fn add(self, other: &InfiniteOperator) -> InfiniteOperator {
+        &self + other
+    }
+}
+
+impl Mul for &InfiniteOperator {
+    type Output = InfiniteOperator;
+    
+    /// Lazy product AB (see [`InfiniteOperator::product`]) with tolerance [`PRODUCT_TOL`]
+    fn mul(self, other: &InfiniteOperator) -> InfiniteOperator {
+        self.product(other, PRODUCT_TOL)
+    }
+}
+
+impl Mul for InfiniteOperator {
+    type Output = InfiniteOperator;
+    
+    fn mul(self, other: InfiniteOperator) -> InfiniteOperator {
+        self.product(&other, PRODUCT_TOL)
+    }
+}
+
+impl Mul<InfiniteOperator> for &InfiniteOperator {
+    type Output = InfiniteOperator;
+    
+    fn mul(self, other: InfiniteOperator) -> InfiniteOperator {
+        self.product(&other, PRODUCT_TOL)
+    }
+}
+
+impl Mul<&InfiniteOperator> for InfiniteOperator {
+    type Output = InfiniteOperator;
+    
+    fn mul(self, other: &InfiniteOperator) -> InfiniteOperator {
+        self.product(other, PRODUCT_TOL)
+    }
+}
+
+impl Mul<Complex64> for &InfiniteOperator {
+    type Output = InfiniteOperator;
+    
+    fn mul(self, c: Complex64) -> InfiniteOperator {
+        self.scale(c)
+    }
+}
+
+impl Mul<Complex64> for InfiniteOperator {
+    type Output = InfiniteOperator;
+    
+    fn mul(self, c: Complex64) -> InfiniteOperator {
+        self.scale(c)
+    }
+}
+
+impl Mul<f64> for &InfiniteOperator {
+    type Output = InfiniteOperator;
+    
+    fn mul(self, c: f64) -> InfiniteOperator {
+        self.scale(Complex64::new(c, 0.0))
+    }
+}
+
+impl Mul<f64> for InfiniteOperator {
+    type Output = InfiniteOperator;
+    
+    fn mul(self, c: f64) -> InfiniteOperator {
+        self.scale(Complex64::new(c, 0.0))
+    }
+}
+
+/// Lazy product of an operator with an infinite vector (one part of it)
+struct AppliedOperator {
+    operator: InfiniteOperator,
+    x: Arc<dyn InfiniteArray>,
+    tol: f64,
+    imaginary: bool,
+}
+
+impl InfiniteArray for AppliedOperator {
+    fn get(&self, index: usize) -> f64 {
+        let value = self.operator.row_sum(index, self.x.as_ref(), self.tol);
+        if self.imaginary { value.im } else { value.re }
+    }
+    
+    fn shape(&self) -> Shape {
+        Shape::OneD(Size::Infinite)
+    }
+}
+
+/// A linear map acting on finitely supported vectors of l^2(N)
+///
+/// Implemented by [`InfiniteOperator`] and by spectral transformations such as
+/// [`crate::eigensolvers::ShiftInvert`], so the iterative methods accept either.
+pub trait MatVec {
+    /// Apply the map; the result may be longer than `x`
+    fn matvec(&self, x: &[Complex64]) -> Vec<Complex64>;
+}
+
+impl MatVec for InfiniteOperator {
+    fn matvec(&self, x: &[Complex64]) -> Vec<Complex64> {
+        InfiniteOperator::matvec(self, x)
+    }
+}
+
+/// Closures acting on finitely supported vectors, e.g. `|x| expm_multiply(&a, t, x, tol).coefficients`
+impl<F> MatVec for F
+where
+    F: Fn(&[Complex64]) -> Vec<Complex64>,
+{
+    fn matvec(&self, x: &[Complex64]) -> Vec<Complex64> {
+        self(x)
+    }
+}
+
+/// Result of IQR algorithm
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct IqrResult {
+    /// Ordered by [`IqrOptions::sort_by`], largest magnitude first by default
+    pub eigenvalues: Vec<Complex64>,
+    /// Accumulated Schur vectors; column k belongs to `eigenvalues[k]` and is an
+    /// eigenvector when the operator is normal
+    pub eigenvectors: Option<Array2<Complex64>>,
+    pub iterations: usize,
+    pub converged: bool,
+    pub residual: Option<f64>,
+    /// Set when [`IqrOptions::eigenvalue_conditioning`] finds eigenvalues too
+    /// ill-conditioned to be reliable
+    pub warning: Option<IqrWarning>,
+    /// The iteration was stopped by [`IqrOptions::cancel`] or [`IqrOptions::deadline`];
+    /// the eigenvalues are the diagonal of the last iterate
+    pub interrupted: bool,
+    /// Guaranteed ε per eigenvalue: λ is in the ε-pseudospectrum, and within ε of
+    /// the spectrum for normal operators. Set when [`IqrOptions::error_bounds`] is
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub error_bounds: Option<Vec<f64>>,
+}
+
+/// Reliability warnings attached to an [`IqrResult`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum IqrWarning {
+    /// The largest eigenvalue condition number of the final Schur form exceeds
+    /// [`ILL_CONDITIONED_THRESHOLD`]
+    IllConditioned { condition_number: f64 },
+}
+
+impl fmt::Display for IqrWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IqrWarning::IllConditioned { condition_number } => {
+                write!(f, "ill-conditioned eigenvalues ({}({}) = {:.3e})", display::kappa(), display::lambda(), condition_number)
+            }
+        }
+    }
+}
+
+/// Summary line followed by one eigenvalue per line; the precision (default 6)
+/// applies to the eigenvalues. Eigenvectors are not printed.
+impl fmt::Display for IqrResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let precision = f.precision().unwrap_or(6);
+        write!(f, "IqrResult: {} eigenvalues, ", self.eigenvalues.len())?;
+        write_convergence(f, self)?;
+        if let Some(residual) = self.residual {
+            write!(f, "\n  residual: {:.3e}", residual)?;
+        }
+        if let Some(warning) = &self.warning {
+            write!(f, "\n  warning: {}", warning)?;
+        }
+        for (i, lambda) in self.eigenvalues.iter().enumerate() {
+            write!(f, "\n  {}_{} = {:.*}", display::lambda(), i + 1, precision, lambda)?;
+            if let Some(bound) = self.error_bounds.as_ref().and_then(|bounds| bounds.get(i)) {
+                write!(f, " {} {:.3e}", display::plus_minus(), bound)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl IqrResult {
+    /// Reorder the eigenvalues, moving the eigenvector columns along with them
+    pub fn sort(&mut self, by: SortBy) {
+        let order = by.order(&self.eigenvalues);
+        self.eigenvalues = order.iter().map(|&k| self.eigenvalues[k]).collect();
+        if let Some(vectors) = &mut self.eigenvectors {
+            *vectors = vectors.select(Axis(1), &order);
+        }
+        if let Some(bounds) = &mut self.error_bounds {
+            *bounds = order.iter().map(|&k| bounds[k]).collect();
+        }
+    }
+    
+    /// Write the eigenvalues as CSV with columns `index,re,im,abs,error_bound`
+    ///
+    /// Numbers use the shortest representation that reads back exactly; the error
+    /// bound is empty when none was computed. Eigenvectors are not included;
+    /// [`IqrResult::write_json_to`] keeps them.
+    pub fn write_csv_to<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writeln!(writer, "index,re,im,abs,error_bound")?;
+        for (i, lambda) in self.eigenvalues.iter().enumerate() {
+            write!(writer, "{},{},{},{},", i, lambda.re, lambda.im, lambda.norm())?;
+            match self.error_bounds.as_ref().and_then(|bounds| bounds.get(i)) {
+                Some(bound) => writeln!(writer, "{}", bound)?,
+                None => writeln!(writer)?,
+            }
+        }
+        writer.flush()
+    }
+    
+    /// [`IqrResult::write_csv_to`] into a file, replacing it if it exists
+    pub fn write_csv(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        self.write_csv_to(BufWriter::new(File::create(path)?))
+    }
+    
+    /// Write the whole result, eigenvectors included, as pretty-printed JSON
+    ///
+    /// Complex numbers are `[re, im]` pairs and matrices use the ndarray layout
+    /// (`v`, `dim`, `data`). JSON has no infinities or NaN; such values are
+    /// written as `null`.
+    #[cfg(feature = "serde")]
+    pub fn write_json_to<W: Write>(&self, writer: W) -> io::Result<()> {
+        write_json_to(self, writer)
+    }
+    
+    /// [`IqrResult::write_json_to`] into a file, replacing it if it exists
+    #[cfg(feature = "serde")]
+    pub fn write_json(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        self.write_json_to(BufWriter::new(File::create(path)?))
+    }
+}
+
+/// Serialize a result as pretty-printed JSON
+#[cfg(feature = "serde")]
+fn write_json_to<T: Serialize, W: Write>(value: &T, mut writer: W) -> io::Result<()> {
+    serde_json::to_writer_pretty(&mut writer, value)?;
+    writer.flush()
+}
+
+/// Whether the run converged, ran out of iterations or was interrupted, and after how many steps
+fn write_convergence(f: &mut fmt::Formatter<'_>, result: &IqrResult) -> fmt::Result {
+    let state = if result.interrupted {
+        "interrupted"
+    } else if result.converged {
+        "converged"
+    } else {
+        "not converged"
+    };
+    write!(f, "{} after {} iterations", state, result.iterations)
+}
+
+/// Eigenvalue condition number above which [`IqrWarning::IllConditioned`] is reported
+pub const ILL_CONDITIONED_THRESHOLD: f64 = 1e12;
+
+/// 2-norm condition number κ₂ of the n×n truncation (infinite if singular)
+pub fn truncation_condition(operator: &InfiniteOperator, n: usize) -> f64 {
+    linalg::condition_number(&linalg::to_dmatrix(&operator.get_truncation(n)))
+}
+
+/// Largest eigenvalue condition number κ(λ) = ‖x‖‖y‖/|y*x| of an upper triangular T
+///
+/// For each diagonal entry λ_k the right eigenvector x (x_k = 1) is found by back
+/// substitution and the left one y (y_k = 1) by forward substitution, so y*x = 1.
+/// Differences T_jj − λ_k below ε‖T‖_F are replaced by ε‖T‖_F, which keeps the
+/// result finite, and large, for defective eigenvalues. Normal matrices give 1.
+fn eigenvalue_condition(t: &Array2<Complex64>) -> f64 {
+    let n = t.nrows();
+    let scale = t.iter().map(|z| z.norm_sqr()).sum::<f64>().sqrt();
+    let small = (f64::EPSILON * scale).max(f64::MIN_POSITIVE);
+    let pivot = |d: Complex64| if d.norm() < small { Complex64::new(small, 0.0) } else { d };
+    let mut worst = 1.0f64;
+    for k in 0..n {
+        let lambda = t[(k, k)];
+        let mut x = vec![Complex64::new(0.0, 0.0); k + 1];
+        x[k] = Complex64::new(1.0, 0.0);
+        for j in (0..k).rev() {
+            let sum: Complex64 = (j + 1..=k).map(|m| t[(j, m)] * x[m]).sum();
+            x[j] = -sum / pivot(t[(j, j)] - lambda);
+        }
+        let mut y = vec![Complex64::new(0.0, 0.0); n - k];
+        y[0] = Complex64::new(1.0, 0.0);
+        for j in k + 1..n {
+            let sum: Complex64 = (k..j).map(|m| y[m - k] * t[(m, j)]).sum();
+            y[j - k] = -sum / pivot(t[(j, j)] - lambda);
+        }
+        let norm = |v: &[Complex64]| v.iter().map(|z| z.norm_sqr()).sum::<f64>().sqrt();
+        worst = worst.max(norm(&x) * norm(&y));
+    }
+    if worst.is_nan() { f64::INFINITY } else { worst }
+}
+
+/// User convergence measure: the iterate and the diagonal of the previous iterate
+pub type ConvergenceMeasure = dyn Fn(&Array2<Complex64>, &[Complex64]) -> f64 + Send + Sync;
+
+/// When the QR iteration is considered converged
+///
+/// Each criterion reduces the current iterate to a convergence measure, and the
+/// iteration stops once that measure drops below `tol`. The measure is reported as
+/// [`IqrResult::residual`].
+#[derive(Clone, Default)]
+pub enum ConvergenceCriterion {
+    /// Largest entry off the diagonal, above or below. Only triggers for normal
+    /// operators, whose QR limit is diagonal.
+    MaxOffDiagonal,
+    /// Largest entry below the diagonal; for Hessenberg iterates this is the subdiagonal
+    #[default]
+    Subdiagonal,
+    /// Largest ratio |a_ij| / (|a_ii| + |a_jj|) below the diagonal, so small eigenvalues
+    /// are not held to the tolerance of large ones
+    RelativeSubdiagonal,
+    /// Largest relative change max |Δa_ii| / max(1, |a_ii|) of the diagonal between iterations
+    EigenvalueMovement,
+    /// User measure of the iterate and the diagonal of the previous iterate
+    Custom(Arc<ConvergenceMeasure>),
+}
+
+impl std::fmt::Debug for ConvergenceCriterion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConvergenceCriterion::MaxOffDiagonal => write!(f, "MaxOffDiagonal"),
+            ConvergenceCriterion::Subdiagonal => write!(f, "Subdiagonal"),
+            ConvergenceCriterion::RelativeSubdiagonal => write!(f, "RelativeSubdiagonal"),
+            ConvergenceCriterion::EigenvalueMovement => write!(f, "EigenvalueMovement"),
+            ConvergenceCriterion::Custom(_) => write!(f, "Custom(..)"),
+        }
+    }
+}
+
+impl ConvergenceCriterion {
+    /// Convergence measure of the iterate `a`, given the diagonal of the previous iterate
+    pub fn measure(&self, a: &Array2<Complex64>, previous_diagonal: &[Complex64]) -> f64 {
+        let n = a.nrows();
+        // Row slices of a standard-layout view, so the scans run over contiguous memory
+        let standard = a.as_standard_layout();
+        let rows = || standard.as_slice().expect("standard layout").chunks_exact(n.max(1)).enumerate();
+        match self {
+            ConvergenceCriterion::MaxOffDiagonal => rows()
+                .map(|(i, row)| linalg::max_norm_sqr(&row[..i]).max(linalg::max_norm_sqr(&row[i + 1..])))
+                .fold(0.0, f64::max)
+                .sqrt(),
+            ConvergenceCriterion::Subdiagonal => {
+                rows().map(|(i, row)| linalg::max_norm_sqr(&row[..i])).fold(0.0, f64::max).sqrt()
+            }
+            ConvergenceCriterion::RelativeSubdiagonal => {
+                let diagonal: Vec<f64> = a.diag().iter().map(|v| v.norm()).collect();
+                rows()
+                    .flat_map(|(i, row)| {
+                        let diagonal = &diagonal;
+                        row[..i].iter().enumerate().map(move |(j, v)| {
+                            v.norm() / (diagonal[i] + diagonal[j]).max(f64::MIN_POSITIVE)
+                        })
+                    })
+                    .fold(0.0, f64::max)
+            }
+            ConvergenceCriterion::EigenvalueMovement => previous_diagonal
+                .iter()
+                .enumerate()
+                .map(|(i, &mu)| (a[(i, i)] - mu).norm() / a[(i, i)].norm().max(1.0))
+                .fold(0.0, f64::max),
+            ConvergenceCriterion::Custom(measure) => measure(a, previous_diagonal),
+        }
+    }
+}
+
+/// Order in which eigenvalues are reported
+///
+/// Ties keep their previous relative order.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum SortBy {
+    /// Largest magnitude first
+    #[default]
+    Magnitude,
+    /// Largest real part first, so the rightmost eigenvalue leads
+    RealPart,
+    /// Largest imaginary part first
+    ImaginaryPart,
+    /// Closest to the target σ first
+    DistanceTo(Complex64),
+    /// Order of the diagonal of the final iterate
+    None,
+}
+
+impl SortBy {
+    /// Permutation listing the indices of `values` in this order
+    pub fn order(&self, values: &[Complex64]) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..values.len()).collect();
+        match *self {
+            SortBy::Magnitude => order.sort_by(|&a, &b| values[b].norm().total_cmp(&values[a].norm())),
+            SortBy::RealPart => order.sort_by(|&a, &b| values[b].re.total_cmp(&values[a].re)),
+            SortBy::ImaginaryPart => order.sort_by(|&a, &b| values[b].im.total_cmp(&values[a].im)),
+            SortBy::DistanceTo(target) => {
+                order.sort_by(|&a, &b| (values[a] - target).norm().total_cmp(&(values[b] - target).norm()))
+            }
+            SortBy::None => {}
+        }
+        order
+    }
+}
+
+/// Snapshot of a running QR iteration passed to [`IqrOptions::on_iteration`]
+#[derive(Debug, Clone, Copy)]
+pub struct IqrProgress<'a> {
+    /// Size of the truncation being iterated
+    pub n: usize,
+    /// Current convergence measure (see [`ConvergenceCriterion::measure`])
+    pub measure: f64,
+    /// Diagonal of the current iterate, the intermediate eigenvalue estimates
+    pub eigenvalues: &'a [Complex64],
+}
+
+/// Callback receiving the 1-based iteration count and the current state
+pub type IterationCallback = dyn Fn(usize, &IqrProgress) + Send + Sync;
+
+/// Callback receiving a truncation size and its finished result
+pub type TruncationCallback = dyn Fn(usize, &IqrResult) + Send + Sync;
+
+/// Where the QR steps of the IQR iteration run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Backend {
+    /// On the CPU, spread over threads from [`PARALLEL_QR_MIN_N`] on with the
+    /// `rayon` feature
+    #[default]
+    Cpu,
+    /// On a GPU for truncations of size `min_n` and above (`gpu` feature), e.g.
+    /// `Backend::Gpu { min_n: GPU_QR_MIN_N }`
+    ///
+    /// Needs an adapter with 64-bit float shaders, see [`gpu::is_available`];
+    /// without one, and for smaller truncations, the CPU kernel runs. The GPU sums
+    /// in a different order, so results agree with the CPU to rounding only.
+    #[cfg(feature = "gpu")]
+    Gpu { min_n: usize },
+}
+
+/// Optional settings of the IQR entry points
+///
+/// Callbacks are invoked synchronously on the thread doing the work; with the `rayon`
+/// feature the truncations of [`iqr_spectrum_with_options`] run in parallel, so the
+/// callbacks may be called concurrently and out of order.
+///
+/// Start from `IqrOptions::default()` and assign the fields to change; a deadline is
+/// set with [`IqrOptions::with_deadline`].
+#[derive(Clone, Default)]
+#[non_exhaustive]
+pub struct IqrOptions {
+    pub criterion: ConvergenceCriterion,
+    /// Order of the reported eigenvalues and eigenvectors
+    pub sort_by: SortBy,
+    /// Attach [`IqrResult::error_bounds`] using this dispersion information; costs
+    /// one SVD of an m×n section per eigenvalue
+    pub error_bounds: Option<Dispersion>,
+    /// Called after every QR step
+    pub on_iteration: Option<Arc<IterationCallback>>,
+    /// Called by [`iqr_spectrum_with_options`] as each truncation finishes
+    pub on_truncation: Option<Arc<TruncationCallback>>,
+    /// Stops the iteration once set to `true`, e.g. from another thread
+    pub cancel: Option<Arc<AtomicBool>>,
+    #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+    deadline: Option<Instant>,
+    /// Compute eigenvalue condition numbers from the final Schur form and set
+    /// [`IqrWarning::IllConditioned`] when one exceeds [`ILL_CONDITIONED_THRESHOLD`];
+    /// costs O(n³) per truncation
+    pub eigenvalue_conditioning: bool,
+    /// Where the QR steps run
+    pub backend: Backend,
+}
+
+impl IqrOptions {
+    /// Stop the iteration once `deadline` has passed
+    ///
+    /// `wasm32-unknown-unknown` has no clock, so there this does nothing and
+    /// [`IqrOptions::deadline`] stays `None`; use `cancel` from an `on_iteration`
+    /// callback instead.
+    pub fn with_deadline(self, deadline: Instant) -> Self {
+        #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+        return IqrOptions { deadline: Some(deadline), ..self };
+        #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+        {
+            let _ = deadline;
+            self
+        }
+    }
+
+    /// The deadline set with [`IqrOptions::with_deadline`], if any
+    pub fn deadline(&self) -> Option<Instant> {
+        #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+        return self.deadline;
+        #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+        None
+    }
+
+    /// Whether the computation has been cancelled or has run past its deadline
+    pub fn is_interrupted(&self) -> bool {
+        if self.cancel.as_ref().is_some_and(|flag| flag.load(Ordering::Relaxed)) {
+            return true;
+        }
+        self.deadline().is_some_and(|deadline| Instant::now() >= deadline)
+    }
+}
+
+impl std::fmt::Debug for IqrOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("IqrOptions")
+            .field("criterion", &self.criterion)
+            .field("sort_by", &self.sort_by)
+            .field("error_bounds", &self.error_bounds)
+            .field("on_iteration", &self.on_iteration.is_some())
+            .field("on_truncation", &self.on_truncation.is_some())
+            .field("cancel", &self.cancel)
+            .field("deadline", &self.deadline())
+            .field("eigenvalue_conditioning", &self.eigenvalue_conditioning)
+            .field("backend", &self.backend)
+            .finish()
+    }
+}
+
+/// Infinite-dimensional QR algorithm for computing spectra
+///
+/// Convergence is judged by the default [`ConvergenceCriterion`]; see
+/// [`iqr_algorithm_with_options`], which can also check the eigenvalues'
+/// conditioning with [`IqrOptions::eigenvalue_conditioning`].
+///
+/// Fails for `n = 0` or a non-finite entry in the truncation. Running out of
+/// iterations is not an error; the result then has `converged == false`.
+pub fn iqr_algorithm(
+    operator: &InfiniteOperator,
+    n: usize,
+    max_iter: usize,
+    tol: f64,
+    shift: Option<Complex64>,
+    compute_eigenvectors: bool,
+) -> Result<IqrResult, InfiniteArraysError> {
+    let options = IqrOptions::default();
+    iqr_algorithm_with_options(operator, n, max_iter, tol, shift, compute_eigenvectors, &options)
+}
+
+/// [`iqr_algorithm`] with an explicit convergence criterion
+pub fn iqr_algorithm_with_criterion(
+    operator: &InfiniteOperator,
+    n: usize,
+    max_iter: usize,
+    tol: f64,
+    shift: Option<Complex64>,
+    compute_eigenvectors: bool,
+    criterion: &ConvergenceCriterion,
+) -> Result<IqrResult, InfiniteArraysError> {
+    let options = IqrOptions {
+        criterion: criterion.clone(),
+        ..IqrOptions::default()
+    };
+    iqr_algorithm_with_options(operator, n, max_iter, tol, shift, compute_eigenvectors, &options)
+}
+
+/// [`iqr_algorithm`] with a convergence criterion, progress callback and cancellation
+pub fn iqr_algorithm_with_options(
+    operator: &InfiniteOperator,
+    n: usize,
+    max_iter: usize,
+    tol: f64,
+    shift: Option<Complex64>,
+    compute_eigenvectors: bool,
+    options: &IqrOptions,
+) -> Result<IqrResult, InfiniteArraysError> {
+    // Get finite truncation
+    let a = operator.try_get_truncation(n)?;
+    let mut result = iqr_iterate(a, max_iter, tol, shift, compute_eigenvectors, options);
+    attach_error_bounds(&mut result, operator, n, options);
+    Ok(result)
+}
+
+/// In-progress state of the QR iteration on one truncation
+///
+/// Advance it with [`iqr_resume`]. [`IqrState::save`] and [`IqrState::load`] store it
+/// bit-exactly on disk, so a long run can be continued after a process restart.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IqrState {
+    /// Current iterate
+    pub a: Array2<Complex64>,
+    /// Accumulated unitary factor, kept when eigenvectors are requested
+    pub q: Option<Array2<Complex64>>,
+    /// QR steps taken so far
+    pub iterations: usize,
+}
+
+/// Leading bytes of a saved [`IqrState`]
+const STATE_MAGIC: &[u8; 8] = b"IQRSTATE";
+/// Version of the saved [`IqrState`] layout
+const STATE_VERSION: u32 = 1;
+
+impl IqrState {
+    /// Initial state for the n×n truncation of an operator
+    pub fn new(operator: &InfiniteOperator, n: usize, compute_eigenvectors: bool) -> Self {
+        Self::from_matrix(operator.get_truncation(n), compute_eigenvectors)
+    }
+    
+    /// Initial state for an assembled truncation
+    pub fn from_matrix(a: Array2<Complex64>, compute_eigenvectors: bool) -> Self {
+        let q = compute_eigenvectors.then(|| Array2::eye(a.nrows()));
+        IqrState { a, q, iterations: 0 }
+    }
+    
+    /// Write the state in a little-endian binary layout
+    pub fn write_to<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(STATE_MAGIC)?;
+        writer.write_all(&STATE_VERSION.to_le_bytes())?;
+        writer.write_all(&(self.a.nrows() as u64).to_le_bytes())?;
+        writer.write_all(&(self.iterations as u64).to_le_bytes())?;
+        writer.write_all(&[u8::from(self.q.is_some())])?;
+        for matrix in std::iter::once(&self.a).chain(&self.q) {
+            for value in matrix.iter() {
+                writer.write_all(&value.re.to_le_bytes())?;
+                writer.write_all(&value.im.to_le_bytes())?;
+            }
+        }
+        writer.flush()
+    }
+    
+    /// Read a state written by [`IqrState::write_to`]
+    pub fn read_from<R: Read>(mut reader: R) -> io::Result<Self> {
+        let invalid = |message: &str| io::Error::new(io::ErrorKind::InvalidData, message.to_string());
+        let mut magic = [0u8; 8];
+        reader.read_exact(&mut magic)?;
+        if &magic != STATE_MAGIC {
+            return Err(invalid("not an IQR state"));
+        }
+        let mut word = [0u8; 4];
+        reader.read_exact(&mut word)?;
+        if u32::from_le_bytes(word) != STATE_VERSION {
+            return Err(invalid("unsupported IQR state version"));
+        }
+        let read_u64 = |reader: &mut R| -> io::Result<u64> {
+            let mut bytes = [0u8; 8];
+            reader.read_exact(&mut bytes)?;
+            Ok(u64::from_le_bytes(bytes))
+        };
+        let n = usize::try_from(read_u64(&mut reader)?).map_err(|_| invalid("truncation size too large"))?;
+        let iterations = usize::try_from(read_u64(&mut reader)?).map_err(|_| invalid("iteration count too large"))?;
+        let mut flag = [0u8; 1];
+        reader.read_exact(&mut flag)?;
+        let has_q = match flag[0] {
+            0 => false,
+            1 => true,
+            _ => return Err(invalid("corrupt eigenvector flag")),
+        };
+        let entries = n.checked_mul(n).ok_or_else(|| invalid("truncation size too large"))?;
+        let read_matrix = |reader: &mut R| -> io::Result<Array2<Complex64>> {
+            let mut values = Vec::with_capacity(entries.min(1 << 24));
+            for _ in 0..entries {
+                let re = f64::from_bits(read_u64(reader)?);
+                let im = f64::from_bits(read_u64(reader)?);
+                values.push(Complex64::new(re, im));
+            }
+            Ok(Array2::from_shape_vec((n, n), values).expect("n² values were read"))
+        };
+        let a = read_matrix(&mut reader)?;
+        let q = if has_q { Some(read_matrix(&mut reader)?) } else { None };
+        Ok(IqrState { a, q, iterations })
+    }
+    
+    /// Save the state to a file, replacing it if it exists
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        self.write_to(BufWriter::new(File::create(path)?))
+    }
+    
+    /// Load a state saved with [`IqrState::save`]
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::read_from(BufReader::new(File::open(path)?))
+    }
+}
+
+/// Run the shifted QR iteration on an already assembled truncation
+fn iqr_iterate(
+    a: Array2<Complex64>,
+    max_iter: usize,
+    tol: f64,
+    shift: Option<Complex64>,
+    compute_eigenvectors: bool,
+    options: &IqrOptions,
+) -> IqrResult {
+    let mut state = IqrState::from_matrix(a, compute_eigenvectors);
+    iqr_advance(&mut state, max_iter, tol, shift, options)
+}
+
+/// Continue the QR iteration from a saved state for up to `max_iter` further steps
+///
+/// The state is advanced in place, so it can be saved again afterwards; combined with
+/// [`IqrOptions::deadline`] this checkpoints a long run at regular intervals. The
+/// reported iteration count includes the steps taken before the state was saved.
+pub fn iqr_resume(
+    state: &mut IqrState,
+    max_iter: usize,
+    tol: f64,
+    shift: Option<Complex64>,
+    options: &IqrOptions,
+) -> Result<IqrResult, InfiniteArraysError> {
+    if state.a.is_empty() {
+        return Err(InfiniteArraysError::EmptyTruncation);
+    }
+    check_finite(&state.a)?;
+    Ok(iqr_advance(state, max_iter, tol, shift, options))
+}
+
+/// Take up to `max_iter` QR steps on `state`, reporting eigenvalues and Schur
+/// vectors in the order of [`IqrOptions::sort_by`]
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all, fields(n = state.a.nrows(), start = state.iterations)))]
+fn iqr_advance(
+    state: &mut IqrState,
+    max_iter: usize,
+    tol: f64,
+    shift: Option<Complex64>,
+    options: &IqrOptions,
+) -> IqrResult {
+    let IqrState { a, q: q_total, iterations } = state;
+    let n = a.nrows();
+    if !a.is_standard_layout() {
+        *a = a.as_standard_layout().into_owned();
+    }
+    if let Some(q) = q_total.as_mut().filter(|q| !q.is_standard_layout()) {
+        *q = q.as_standard_layout().into_owned();
+    }
+    let mut workspace = QrKernel::new(n, options.backend);
+    
+    // QR iteration
+    let mut converged = false;
+    let mut measure = f64::INFINITY;
+    let mut previous_diagonal: Vec<Complex64> = a.diag().to_vec();
+    
+    let mut interrupted = false;
+    
+    for _ in 0..max_iter {
+        if options.is_interrupted() {
+            interrupted = true;
+            break;
+        }
+        
+        // Compute shift (Wilkinson shift for better convergence)
+        let shift_val = if let Some(s) = shift {
+            s
+        } else {
+            // Wilkinson shift: use eigenvalue of bottom-right 2x2 block
+            if n >= 2 {
+                let a_val = a[(n-2, n-2)];
+                let b_val = a[(n-2, n-1)];
+                let c_val = a[(n-1, n-2)];
+                let d_val = a[(n-1, n-1)];
+                
+                // Eigenvalue of 2x2 matrix closest to d
+                let trace = a_val + d_val;
+                let det = a_val * d_val - b_val * c_val;
+                let discriminant = trace * trace - Complex64::new(4.0, 0.0) * det;
+                
+                if discriminant.re >= 0.0 {
+                    let sqrt_disc = Complex64::new(discriminant.re.sqrt(), 0.0);
+                    let lambda1 = (trace + sqrt_disc) / Complex64::new(2.0, 0.0);
+                    let lambda2 = (trace - sqrt_disc) / Complex64::new(2.0, 0.0);
+                    
+                    let dist1 = (lambda1 - d_val).norm();
+                    let dist2 = (lambda2 - d_val).norm();
+                    if dist2 < dist1 {
+                        lambda2
+                    } else {
+                        lambda1
+                    }
+                } else {
+                    trace / Complex64::new(2.0, 0.0)
+                }
+            } else {
+                a[(0, 0)]
+            }
+        };
+        
+        // Shifted QR step in place: A − σ = QR, A ← RQ + σ, accumulating Q for eigenvectors
+        for i in 0..n {
+            a[(i, i)] -= shift_val;
+        }
+        workspace.rq_step(a, q_total.as_mut());
+        for i in 0..n {
+            a[(i, i)] += shift_val;
+        }
+        
+        *iterations += 1;
+        
+        measure = options.criterion.measure(a, &previous_diagonal);
+        for (i, value) in previous_diagonal.iter_mut().enumerate() {
+            *value = a[(i, i)];
+        }
+        if let Some(on_iteration) = &options.on_iteration {
+            on_iteration(*iterations, &IqrProgress { n, measure, eigenvalues: &previous_diagonal });
+        }
+        trace_event!(iteration = *iterations, shift.re = shift_val.re, shift.im = shift_val.im, measure, "QR step");
+        
+        if measure < tol {
+            converged = true;
+            break;
+        }
+    }
+    if converged {
+        debug_event!(iterations = *iterations, measure, "QR iteration converged");
+    } else if interrupted {
+        debug_event!(iterations = *iterations, measure, "QR iteration interrupted");
+    } else {
+        debug_event!(iterations = *iterations, measure, "QR iteration reached max_iter");
+    }
+    
+    // Extract eigenvalues from diagonal, keeping each paired with its Schur vector
+    let diagonal: Vec<Complex64> = (0..n).map(|i| a[(i, i)]).collect();
+    let order = options.sort_by.order(&diagonal);
+    
+    let mut warning = None;
+    if options.eigenvalue_conditioning {
+        let condition_number = eigenvalue_condition(a);
+        if condition_number > ILL_CONDITIONED_THRESHOLD {
+            warn_event!(n, condition_number, "ill-conditioned eigenvalues");
+            warning = Some(IqrWarning::IllConditioned { condition_number });
+        }
+    }
+    
+    IqrResult {
+        eigenvalues: order.iter().map(|&k| diagonal[k]).collect(),
+        eigenvectors: q_total.as_ref().map(|q| q.select(Axis(1), &order)),
+        iterations: *iterations,
+        converged,
+        residual: if converged { Some(measure) } else { None },
+        warning,
+        interrupted,
+        error_bounds: None,
+    }
+}
+
+/// Fill in [`IqrResult::error_bounds`] if the options ask for them
+fn attach_error_bounds(result: &mut IqrResult, operator: &InfiniteOperator, n: usize, options: &IqrOptions) {
+    if let Some(dispersion) = &options.error_bounds {
+        result.error_bounds = Some(spectral_error_bounds(operator, &result.eigenvalues, n, dispersion));
+    }
+}
+
+/// Preallocated buffers for the in-place QR steps of [`iqr_advance`]
+struct RqWorkspace {
+    /// Householder vector of step k in row k, entries k..n
+    reflectors: Array2<Complex64>,
+    /// 2 / ‖v_k‖², zero when column k needed no reflection
+    betas: Vec<f64>,
+    /// Row combinations vᴴA of the current reflection
+    sums: Vec<Complex64>,
+}
+
+impl RqWorkspace {
+    fn new(n: usize) -> Self {
+        RqWorkspace {
+            reflectors: Array2::zeros((n, n)),
+            betas: vec![0.0; n],
+            sums: vec![Complex64::new(0.0, 0.0); n],
+        }
+    }
+    
+    /// Overwrite `a` with RQ, where QR = a, multiplying `q_total` by Q
+    ///
+    /// Q = H₀H₁⋯ is kept as Householder reflectors and applied from the right, so it is
+    /// never formed. Both matrices must be in standard (row-major) layout.
+    ///
+    /// From [`PARALLEL_QR_MIN_N`] on, with the `rayon` feature, the work of each step is
+    /// split over disjoint column blocks and rows. Every entry is still computed by the
+    /// same sequence of operations as in the serial loop, so the result is bit-for-bit
+    /// independent of the number of threads.
+    fn rq_step(&mut self, a: &mut Array2<Complex64>, q_total: Option<&mut Array2<Complex64>>) {
+        let n = a.nrows();
+        let data = a.as_slice_mut().expect("iterate is in standard layout");
+        let zero = Complex64::new(0.0, 0.0);
+        let parallel = n >= PARALLEL_QR_MIN_N;
+        
+        // a ← R, one reflection per column
+        for k in 0..n.saturating_sub(1) {
+            let tail: f64 = (k + 1..n).map(|i| data[i * n + k].norm_sqr()).sum();
+            if tail == 0.0 {
+                self.betas[k] = 0.0;
+                continue;
+            }
+            let x0 = data[k * n + k];
+            let phase = if x0.norm() == 0.0 { Complex64::new(1.0, 0.0) } else { x0 / x0.norm() };
+            let alpha = -phase * (x0.norm_sqr() + tail).sqrt();
+            let v = &mut self.reflectors.as_slice_mut().expect("workspace is in standard layout")[k * n..(k + 1) * n];
+            v[k] = x0 - alpha;
+            for i in k + 1..n {
+                v[i] = data[i * n + k];
+            }
+            let beta = 2.0 / (v[k].norm_sqr() + tail);
+            self.betas[k] = beta;
+            
+            // sums = vᴴA over columns k+1.., one block of columns at a time
+            let sums = &mut self.sums[k + 1..];
+            sums.fill(zero);
+            let v = &*v;
+            let rows = &*data;
+            for_each_chunk(sums, COLUMN_BLOCK, parallel, |block, chunk| {
+                let start = k + 1 + block * COLUMN_BLOCK;
+                for i in k..n {
+                    let weight = v[i].conj();
+                    let values = &rows[i * n + start..i * n + start + chunk.len()];
+                    for (sum, &value) in chunk.iter_mut().zip(values) {
+                        *sum += weight * value;
+                    }
+                }
+            });
+            // A ← A − β v sums, row by row
+            let sums = &*sums;
+            for_each_chunk(&mut data[k * n..], n, parallel, |offset, row| {
+                let weight = beta * v[k + offset];
+                for (value, &sum) in row[k + 1..].iter_mut().zip(sums) {
+                    *value -= weight * sum;
+                }
+            });
+            data[k * n + k] = alpha;
+            for i in k + 1..n {
+                data[i * n + k] = zero;
+            }
+        }
+        
+        // a ← RQ, then q_total ← q_total Q; each row passes through all reflectors in turn
+        let reflectors = self.reflectors.as_slice().expect("workspace is in standard layout");
+        let betas = &self.betas;
+        let reflect_row = |_: usize, row: &mut [Complex64]| {
+            for k in 0..n.saturating_sub(1) {
+                let beta = betas[k];
+                if beta == 0.0 {
+                    continue;
+                }
+                let v = &reflectors[k * n + k..(k + 1) * n];
+                let row = &mut row[k..];
+                let w: Complex64 = row.iter().zip(v).map(|(x, y)| x * y).sum::<Complex64>() * beta;
+                for (x, y) in row.iter_mut().zip(v) {
+                    *x -= w * y.conj();
+                }
+            }
+        };
+        for_each_chunk(data, n, parallel, reflect_row);
+        if let Some(q) = q_total {
+            let q = q.as_slice_mut().expect("eigenvector matrix is in standard layout");
+            for_each_chunk(q, n, parallel, reflect_row);
+        }
+    }
+}
+
+/// Truncation size from which a single QR step is spread over threads (`rayon` feature)
+pub const PARALLEL_QR_MIN_N: usize = 128;
+
+/// Suggested `min_n` of [`Backend::Gpu`]: below it, uploading and reading back the
+/// iterate around every step costs more than the GPU saves
+#[cfg(feature = "gpu")]
+pub const GPU_QR_MIN_N: usize = 512;
+
+/// QR step kernel of one truncation size, chosen by [`IqrOptions::backend`]
+enum QrKernel {
+    Cpu(RqWorkspace),
+    #[cfg(feature = "gpu")]
+    Gpu(Box<gpu::GpuQr>),
+}
+
+impl QrKernel {
+    fn new(n: usize, backend: Backend) -> Self {
+        match backend {
+            Backend::Cpu => {}
+            #[cfg(feature = "gpu")]
+            Backend::Gpu { min_n } => {
+                if n >= min_n {
+                    if let Some(kernel) = gpu::GpuQr::new(n) {
+                        return QrKernel::Gpu(Box::new(kernel));
+                    }
+                    debug_event!(n, "no GPU for this truncation; QR steps run on the CPU");
+                }
+            }
+        }
+        QrKernel::Cpu(RqWorkspace::new(n))
+    }
+
+    fn rq_step(&mut self, a: &mut Array2<Complex64>, q_total: Option<&mut Array2<Complex64>>) {
+        match self {
+            QrKernel::Cpu(workspace) => workspace.rq_step(a, q_total),
+            #[cfg(feature = "gpu")]
+            QrKernel::Gpu(kernel) => kernel.rq_step(a, q_total),
+        }
+    }
+}
+
+/// Columns per task when the column sums of a reflection are computed in parallel
+const COLUMN_BLOCK: usize = 64;
+
+/// Call `f(index, chunk)` on consecutive chunks of `data`
+///
+/// With the `rayon` feature and `parallel` set the chunks are processed concurrently;
+/// `f` only ever sees its own chunk, so the outcome does not depend on scheduling.
+fn for_each_chunk<F>(data: &mut [Complex64], size: usize, parallel: bool, f: F)
+where
+    F: Fn(usize, &mut [Complex64]) + Send + Sync,
+{
+    if size == 0 {
+        return;
+    }
+    #[cfg(feature = "rayon")]
+    if parallel && rayon::current_num_threads() > 1 {
+        data.par_chunks_mut(size).enumerate().for_each(|(index, chunk)| f(index, chunk));
+        return;
+    }
+    #[cfg(not(feature = "rayon"))]
+    let _ = parallel;
+    data.chunks_mut(size).enumerate().for_each(|(index, chunk)| f(index, chunk));
+}
+
+/// Compute spectrum using IQR algorithm with adaptive truncation
+///
+/// The truncations are nested, so they are assembled from smallest to largest with
+/// [`InfiniteOperator::extend_truncation`] and each operator element is evaluated
+/// once. The QR iterations on the truncations are independent; with the `rayon`
+/// feature enabled they run in parallel. Results are keyed by `n` so their order
+/// does not depend on scheduling.
+///
+/// Eigenvalues of consecutive sizes are paired by a minimum-distance assignment,
+/// giving each eigenvalue of the largest truncation a trajectory in n and an
+/// extrapolated limit.
+///
+/// Fails if `n_range` is empty or contains 0, or if a truncation has a
+/// non-finite entry.
+pub fn iqr_spectrum(
+    operator: &InfiniteOperator,
+    n_range: &[usize],
+    max_iter: usize,
+    tol: f64,
+) -> Result<IqrSpectrumResult, InfiniteArraysError> {
+    iqr_spectrum_with_options(operator, n_range, max_iter, tol, &IqrOptions::default())
+}
+
+/// [`iqr_spectrum`] with a convergence criterion, progress callbacks and cancellation
+///
+/// Once interrupted, the remaining truncations return their diagonal without
+/// iterating, and the result is marked as interrupted.
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all, fields(sizes = ?n_range)))]
+pub fn iqr_spectrum_with_options(
+    operator: &InfiniteOperator,
+    n_range: &[usize],
+    max_iter: usize,
+    tol: f64,
+    options: &IqrOptions,
+) -> Result<IqrSpectrumResult, InfiniteArraysError> {
+    let mut sizes = n_range.to_vec();
+    sizes.sort_unstable();
+    sizes.dedup();
+    if matches!(sizes.first(), None | Some(0)) {
+        return Err(InfiniteArraysError::EmptyTruncation);
+    }
+    
+    let mut truncation = Array2::<Complex64>::zeros((0, 0));
+    let truncations = sizes
+        .iter()
+        .map(|&n| {
+            operator.extend_truncation(&mut truncation, n);
+            check_finite(&truncation)?;
+            Ok((n, truncation.clone()))
+        })
+        .collect::<Result<Vec<(usize, Array2<Complex64>)>, InfiniteArraysError>>()?;
+    
+    let run = |(n, a): (usize, Array2<Complex64>)| {
+        let mut result = iqr_iterate(a, max_iter, tol, None, false, options);
+        attach_error_bounds(&mut result, operator, n, options);
+        debug_event!(n, iterations = result.iterations, converged = result.converged, "truncation finished");
+        if let Some(on_truncation) = &options.on_truncation {
+            on_truncation(n, &result);
+        }
+        (n, result)
+    };
+    #[cfg(feature = "rayon")]
+    let results: Vec<(usize, IqrResult)> = truncations.into_par_iter().map(run).collect();
+    #[cfg(not(feature = "rayon"))]
+    let results: Vec<(usize, IqrResult)> = truncations.into_iter().map(run).collect();
+    let results_by_n: BTreeMap<usize, IqrResult> = results.into_iter().collect();
+    
+    // Use eigenvalues from largest truncation as estimate
+    let (&largest_n, largest) = results_by_n.last_key_value().expect("at least one truncation");
+    let eigenvalues = largest.eigenvalues.clone();
+    let previous = results_by_n
+        .range(..largest_n)
+        .next_back()
+        .map(|(_, result)| result.eigenvalues.as_slice());
+    let classifications = classify_eigenvalues(&eigenvalues, previous, tol);
+    let trajectories = eigenvalue_trajectories(&results_by_n);
+    let interrupted = results_by_n.values().any(|result| result.interrupted);
+    
+    Ok(IqrSpectrumResult {
+        eigenvalues,
+        eigenvalues_by_n: results_by_n,
+        classifications,
+        trajectories,
+        recommended_n: largest_n,
+        interrupted,
+    })
+}
+
+/// Result of spectrum computation
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct IqrSpectrumResult {
+    pub eigenvalues: Vec<Complex64>,
+    pub eigenvalues_by_n: BTreeMap<usize, IqrResult>,
+    pub classifications: Vec<SpectralStatus>,
+    /// Convergence trajectory of each entry of `eigenvalues`, in the same order
+    pub trajectories: Vec<EigenvalueTrajectory>,
+    pub recommended_n: usize,
+    /// Some truncation was interrupted, so its eigenvalues are partial estimates
+    pub interrupted: bool,
+}
+
+/// Summary line, one line per truncation size, then each eigenvalue of the largest
+/// truncation with its classification and extrapolated limit
+impl fmt::Display for IqrSpectrumResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let precision = f.precision().unwrap_or(6);
+        let sizes: Vec<String> = self.eigenvalues_by_n.keys().map(|n| n.to_string()).collect();
+        write!(
+            f,
+            "IqrSpectrumResult: n = {}, recommended n = {}",
+            sizes.join(", "),
+            self.recommended_n
+        )?;
+        if self.interrupted {
+            write!(f, " (interrupted)")?;
+        }
+        for (n, result) in &self.eigenvalues_by_n {
+            write!(f, "\n  n = {}: ", n)?;
+            write_convergence(f, result)?;
+            if let Some(warning) = &result.warning {
+                write!(f, ", {}", warning)?;
+            }
+        }
+        for (i, (lambda, status)) in self.eigenvalues.iter().zip(&self.classifications).enumerate() {
+            write!(f, "\n  {}_{} = {:.*}  {}", display::lambda(), i + 1, precision, lambda, status)?;
+            if let Some(trajectory) = self.trajectories.get(i) {
+                write!(f, "  limit {:.*}", precision, trajectory.limit)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl IqrSpectrumResult {
+    /// Write the eigenvalues of every truncation as CSV
+    ///
+    /// Columns are `n,converged,index,re,im,error_bound,status,limit_re,limit_im`,
+    /// one row per eigenvalue and truncation size. The classification and
+    /// extrapolated limit are only known for the largest truncation and are left
+    /// empty elsewhere, as is the error bound when none was computed.
+    pub fn write_csv_to<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writeln!(writer, "n,converged,index,re,im,error_bound,status,limit_re,limit_im")?;
+        let largest_n = self.eigenvalues_by_n.keys().next_back().copied();
+        for (&n, result) in &self.eigenvalues_by_n {
+            for (i, lambda) in result.eigenvalues.iter().enumerate() {
+                write!(writer, "{},{},{},{},{},", n, result.converged, i, lambda.re, lambda.im)?;
+                if let Some(bound) = result.error_bounds.as_ref().and_then(|bounds| bounds.get(i)) {
+                    write!(writer, "{}", bound)?;
+                }
+                let annotation = if Some(n) == largest_n {
+                    self.classifications.get(i).zip(self.trajectories.get(i))
+                } else {
+                    None
+                };
+                match annotation {
+                    Some((status, trajectory)) => writeln!(
+                        writer,
+                        ",{},{},{}",
+                        status, trajectory.limit.re, trajectory.limit.im
+                    )?,
+                    None => writeln!(writer, ",,,")?,
+                }
+            }
+        }
+        writer.flush()
+    }
+    
+    /// [`IqrSpectrumResult::write_csv_to`] into a file, replacing it if it exists
+    pub fn write_csv(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        self.write_csv_to(BufWriter::new(File::create(path)?))
+    }
+    
+    /// Write the whole result as pretty-printed JSON, including the full
+    /// [`IqrResult`] of each truncation; see [`IqrResult::write_json_to`]
+    #[cfg(feature = "serde")]
+    pub fn write_json_to<W: Write>(&self, writer: W) -> io::Result<()> {
+        write_json_to(self, writer)
+    }
+    
+    /// [`IqrSpectrumResult::write_json_to`] into a file, replacing it if it exists
+    #[cfg(feature = "serde")]
+    pub fn write_json(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        self.write_json_to(BufWriter::new(File::create(path)?))
+    }
+}
+
+/// Path of one eigenvalue of the largest truncation back through the smaller ones
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct EigenvalueTrajectory {
+    /// (n, eigenvalue) pairs in increasing n, ending at the largest truncation
+    pub points: Vec<(usize, Complex64)>,
+    /// Aitken Δ² extrapolation of the last three points, or the last point when the
+    /// trajectory is shorter or the differences do not contract
+    pub limit: Complex64,
+}
+
+impl EigenvalueTrajectory {
+    /// Distance moved between the last two truncations (infinite for a single point)
+    pub fn last_step(&self) -> f64 {
+        match self.points.as_slice() {
+            [.., (_, a), (_, b)] => (b - a).norm(),
+            _ => f64::INFINITY,
+        }
+    }
+}
+
+/// Follow each eigenvalue of the largest truncation back through the smaller sizes
+fn eigenvalue_trajectories(results_by_n: &BTreeMap<usize, IqrResult>) -> Vec<EigenvalueTrajectory> {
+    let sizes: Vec<(usize, &[Complex64])> = results_by_n
+        .iter()
+        .map(|(&n, result)| (n, result.eigenvalues.as_slice()))
+        .collect();
+    let Some(&(largest_n, largest)) = sizes.last() else {
+        return Vec::new();
+    };
+    // matches[k][i]: index at size k of the partner of eigenvalue i at size k + 1
+    let matches: Vec<Vec<Option<usize>>> = sizes
+        .windows(2)
+        .map(|pair| match_eigenvalues(pair[0].1, pair[1].1))
+        .collect();
+    
+    (0..largest.len())
+        .map(|i| {
+            let mut points = vec![(largest_n, largest[i])];
+            let mut index = i;
+            for k in (0..matches.len()).rev() {
+                match matches[k][index] {
+                    Some(previous) => {
+                        index = previous;
+                        points.push((sizes[k].0, sizes[k].1[index]));
+                    }
+                    None => break,
+                }
+            }
+            points.reverse();
+            let limit = aitken_limit(&points);
+            EigenvalueTrajectory { points, limit }
+        })
+        .collect()
+}
+
+/// Aitken Δ² extrapolation of the last three values of a trajectory
+fn aitken_limit(points: &[(usize, Complex64)]) -> Complex64 {
+    let last = points.last().expect("a trajectory has at least one point").1;
+    let [(_, x0), (_, x1), (_, x2)] = points[points.len().saturating_sub(3)..] else {
+        return last;
+    };
+    let d1 = x1 - x0;
+    let d2 = x2 - x1;
+    let denominator = d2 - d1;
+    if d2.norm() >= d1.norm() || denominator.norm() <= f64::EPSILON * x2.norm().max(1.0) {
+        return last;
+    }
+    x2 - d2 * d2 / denominator
+}
+
+/// Reliability of an eigenvalue estimate judged by how it moves between truncations
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum SpectralStatus {
+    /// A matching eigenvalue was found at the previous truncation size
+    Stable,
+    /// Still moving, or newly appeared beyond the previous spectrum; needs a larger n
+    Suspect,
+    /// Isolated inside the range of the previous spectrum with no counterpart there,
+    /// the signature of spectral pollution
+    Polluted,
+}
+
+impl fmt::Display for SpectralStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            SpectralStatus::Stable => "stable",
+            SpectralStatus::Suspect => "suspect",
+            SpectralStatus::Polluted => "polluted",
+        };
+        f.pad(name)
+    }
+}
+
+/// Classify each eigenvalue of the largest truncation against the previous one
+///
+/// An eigenvalue is stable when the previous spectrum holds a point μ within
+/// `sqrt(tol) * max(1, |λ|)`. Genuine approximations of continuous spectrum interlace,
+/// so they move by at most the local spacing of the previous spectrum around μ. An
+/// eigenvalue inside the magnitude range of the previous spectrum that moved further
+/// than that is polluted; everything else is suspect.
+fn classify_eigenvalues(
+    eigenvalues: &[Complex64],
+    previous: Option<&[Complex64]>,
+    tol: f64,
+) -> Vec<SpectralStatus> {
+    let Some(previous) = previous.filter(|p| !p.is_empty()) else {
+        return vec![SpectralStatus::Suspect; eigenvalues.len()];
+    };
+    let previous_radius = previous.iter().map(|mu| mu.norm()).fold(0.0, f64::max);
+    
+    eigenvalues
+        .iter()
+        .map(|&lambda| {
+            let (nearest, moved) = previous
+                .iter()
+                .enumerate()
+                .map(|(idx, &mu)| (idx, (lambda - mu).norm()))
+                .min_by(|a, b| a.1.total_cmp(&b.1))
+                .unwrap();
+            if moved <= tol.sqrt() * lambda.norm().max(1.0) {
+                return SpectralStatus::Stable;
+            }
+            let spacing = distance_to_set(
+                previous[nearest],
+                previous.iter().enumerate().filter(|&(other, _)| other != nearest).map(|(_, mu)| mu),
+            );
+            if lambda.norm() <= previous_radius && moved > spacing {
+                SpectralStatus::Polluted
+            } else {
+                SpectralStatus::Suspect
+            }
+        })
+        .collect()
+}
+
+/// Compute the k largest-magnitude eigenvalues, growing the truncation until they settle
+///
+/// The truncation size starts at `max(2k, 8)` and doubles (capped at `max_n`). After
+/// each size the new eigenvalues are matched against the previous spectrum, and the
+/// distance to the matched partner is taken as the error estimate. The search stops
+/// once all k estimates are below `target_tol`.
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all, fields(k, target_tol, max_n)))]
+pub fn iqr_spectrum_adaptive(
+    operator: &InfiniteOperator,
+    k: usize,
+    target_tol: f64,
+    max_n: usize,
+    max_iter: usize,
+) -> Result<AdaptiveSpectrumResult, InfiniteArraysError> {
+    if max_n == 0 {
+        return Err(InfiniteArraysError::EmptyTruncation);
+    }
+    let mut n = (2 * k).max(8).min(max_n);
+    let mut truncation = Array2::<Complex64>::zeros((0, 0));
+    let mut previous: Option<Vec<Complex64>> = None;
+    let mut sizes = Vec::new();
+    
+    loop {
+        operator.extend_truncation(&mut truncation, n);
+        check_finite(&truncation)?;
+        let result = iqr_iterate(
+            truncation.clone(),
+            max_iter,
+            target_tol * 1e-2,
+            None,
+            false,
+            &IqrOptions::default(),
+        );
+        sizes.push(n);
+        
+        let current: Vec<Complex64> = result.eigenvalues.iter().take(k).copied().collect();
+        let comparison = previous.as_deref().map(|prev| compare_spectra(prev, &current));
+        let error_estimates = match &comparison {
+            Some(comparison) => comparison.differences(),
+            None => vec![f64::INFINITY; current.len()],
+        };
+        
+        let converged = current.len() == k && error_estimates.iter().all(|&e| e < target_tol);
+        debug_event!(
+            n,
+            max_error = error_estimates.iter().copied().fold(0.0, f64::max),
+            converged,
+            "adaptive truncation step"
+        );
+        if converged || n >= max_n {
+            return Ok(AdaptiveSpectrumResult {
+                eigenvalues: current,
+                error_estimates,
+                n,
+                converged,
+                sizes,
+            });
+        }
+        
+        previous = Some(result.eigenvalues);
+        n = (2 * n).min(max_n);
+    }
+}
+
+/// Result of adaptive spectrum computation
+#[derive(Debug, Clone)]
+pub struct AdaptiveSpectrumResult {
+    pub eigenvalues: Vec<Complex64>,
+    pub error_estimates: Vec<f64>,
+    pub n: usize,
+    pub converged: bool,
+    pub sizes: Vec<usize>,
+}
+
+/// Second-order relative spectrum of the n×n section
+///
+/// Solves the quadratic eigenproblem det(Pₙ A² Pₙ − 2z Pₙ A Pₙ + z² I) = 0 through its
+/// companion linearization. For self-adjoint operators this estimator does not
+/// pollute: every interval `[Re z − |Im z|, Re z + |Im z|]` meets the spectrum
+/// (Levitin & Shargorodsky, IMA J. Numer. Anal. 24, 2004), so points close to the
+/// real axis certify spectrum near their real part.
+///
+/// Pₙ A² Pₙ needs entries of A beyond the section. For banded operators the larger
+/// section is chosen so that the product is exact; otherwise a 2n section is used.
+pub fn second_order_spectrum(operator: &InfiniteOperator, n: usize) -> SecondOrderSpectrumResult {
+    let m = match operator.bandwidths() {
+        Some((lower, upper)) => n + lower.min(upper),
+        None => 2 * n,
+    };
+    let big = operator.get_truncation(m);
+    let section = big.slice(s![..n, ..n]);
+    let squared = big.slice(s![..n, ..]).dot(&big.slice(s![.., ..n]));
+    
+    // Companion form [[0, I], [-Pₙ A² Pₙ, 2 Pₙ A Pₙ]]
+    let mut companion = Array2::<Complex64>::zeros((2 * n, 2 * n));
+    for i in 0..n {
+        companion[(i, n + i)] = Complex64::new(1.0, 0.0);
+        for j in 0..n {
+            companion[(n + i, j)] = -squared[(i, j)];
+            companion[(n + i, n + j)] = section[(i, j)] * 2.0;
+        }
+    }
+    
+    let mut points = linalg::eigenvalues(&companion);
+    linalg::sort_by_magnitude(&mut points);
+    let enclosures = points
+        .iter()
+        .map(|z| (z.re - z.im.abs(), z.re + z.im.abs()))
+        .collect();
+    
+    SecondOrderSpectrumResult { points, enclosures, n }
+}
+
+/// Result of second-order relative spectrum computation
+#[derive(Debug, Clone)]
+pub struct SecondOrderSpectrumResult {
+    pub points: Vec<Complex64>,
+    /// Intervals `[Re z − |Im z|, Re z + |Im z|]`, each of which meets the spectrum of a
+    /// self-adjoint operator
+    pub enclosures: Vec<(f64, f64)>,
+    pub n: usize,
+}
+
+/// Condition number of Bₙ above which the pencil is not reduced to Bₙ⁻¹Aₙ
+const PENCIL_REDUCTION_CONDITION: f64 = 1e8;
+
+/// Generalized eigenvalues of the n×n sections of the pencil A − λB
+///
+/// When Bₙ is well-conditioned the pencil is reduced to the standard problem
+/// Bₙ⁻¹Aₙ. Otherwise (e.g. singular Bₙ from boundary rows of a spectral
+/// discretization) the eigenvalues μ of (Aₙ − σBₙ)⁻¹Bₙ are computed for a shift σ
+/// that makes Aₙ − σBₙ well-conditioned, and mapped back as λ = σ + 1/μ; μ ≈ 0
+/// corresponds to an infinite eigenvalue.
+///
+/// Fails with [`InfiniteArraysError::SingularPencil`] when Aₙ − σBₙ is numerically
+/// singular for every shift tried, i.e. det(Aₙ − λBₙ) ≡ 0 (for instance when Aₙ and
+/// Bₙ share a zero row), and with [`InfiniteArraysError::EmptyTruncation`] for n = 0.
+pub fn generalized_eigenvalues(
+    a: &InfiniteOperator,
+    b: &InfiniteOperator,
+    n: usize,
+) -> Result<GeneralizedEigenResult, InfiniteArraysError> {
+    if n == 0 {
+        return Err(InfiniteArraysError::EmptyTruncation);
+    }
+    let a_n = linalg::to_dmatrix(&a.try_get_truncation(n)?);
+    let b_n = linalg::to_dmatrix(&b.try_get_truncation(n)?);
+    let condition_b = linalg::condition_number(&b_n);
+    
+    let reduced = if condition_b < PENCIL_REDUCTION_CONDITION { b_n.clone().lu().solve(&a_n) } else { None };
+    let (mut eigenvalues, infinite_eigenvalues) = if let Some(reduced) = reduced {
+        (linalg::eigenvalues(&linalg::from_dmatrix(&reduced)), 0)
+    } else {
+        let scale = a_n.norm() / b_n.norm().max(f64::MIN_POSITIVE);
+        let (shift, condition) = (0..8)
+            .map(|k| Complex64::from_polar(scale.max(1.0), 0.7 + 2.1 * k as f64))
+            .map(|s| (s, linalg::condition_number(&(&a_n - &b_n * s))))
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .ok_or(InfiniteArraysError::SingularPencil)?;
+        if condition.is_nan() || condition >= 1.0 / f64::EPSILON {
+            return Err(InfiniteArraysError::SingularPencil);
+        }
+        let transformed = (&a_n - &b_n * shift)
+            .lu()
+            .solve(&b_n)
+            .ok_or(InfiniteArraysError::SingularPencil)?;
+        let mu = linalg::eigenvalues(&linalg::from_dmatrix(&transformed));
+        let threshold = 1e3 * f64::EPSILON * transformed.norm();
+        let finite: Vec<Complex64> = mu
+            .iter()
+            .filter(|m| m.norm() > threshold)
+            .map(|&m| shift + Complex64::new(1.0, 0.0) / m)
+            .collect();
+        let infinite = mu.len() - finite.len();
+        (finite, infinite)
+    };
+    linalg::sort_by_magnitude(&mut eigenvalues);
+    
+    Ok(GeneralizedEigenResult {
+        eigenvalues,
+        infinite_eigenvalues,
+        condition_b,
+        n,
+    })
+}
+
+/// Result of a generalized eigenvalue computation
+#[derive(Debug, Clone)]
+pub struct GeneralizedEigenResult {
+    /// Finite eigenvalues, sorted by magnitude
+    pub eigenvalues: Vec<Complex64>,
+    /// Number of eigenvalues at infinity
+    pub infinite_eigenvalues: usize,
+    /// 2-norm condition number of Bₙ
+    pub condition_b: f64,
+    pub n: usize,
+}
+
+/// Block companion pencil C₀ − λC₁ of the operator polynomial P(λ) = Σₖ λᵏ Aₖ
+///
+/// `coefficients` holds A₀, …, A_d (d ≥ 1). With the blocks of the vector
+/// (x, λx, …, λ^{d−1}x), C₀ has identity blocks on its superdiagonal and
+/// −A₀, …, −A_{d−1} in its last block row, and C₁ = diag(I, …, I, A_d). The blocks
+/// are interleaved (entry i belongs to block i mod d, row i / d), so the pencil is
+/// banded whenever the coefficients are, and its (d·n)-sections are the companion
+/// pencils of the n-sections of the coefficients.
+pub fn companion_pencil(coefficients: Vec<InfiniteOperator>) -> (InfiniteOperator, InfiniteOperator) {
+    assert!(coefficients.len() >= 2, "a polynomial eigenproblem needs degree at least 1");
+    let d = coefficients.len() - 1;
+    let bandwidths = coefficients
+        .iter()
+        .map(|c| c.bandwidths())
+        .try_fold((0, 0), |(l, u), b| b.map(|(bl, bu)| (l.max(bl), u.max(bu))));
+    let coefficients = Arc::new(coefficients);
+    let one = Complex64::new(1.0, 0.0);
+    let zero = Complex64::new(0.0, 0.0);
+    
+    let c0_coefficients = Arc::clone(&coefficients);
+    let mut c0 = InfiniteOperator::new(move |i, j| {
+        let (bi, ri) = (i % d, i / d);
+        let (bj, rj) = (j % d, j / d);
+        if bi + 1 < d {
+            if bj == bi + 1 && ri == rj { one } else { zero }
+        } else {
+            -c0_coefficients[bj].get(ri, rj)
+        }
+    });
+    let c1_coefficients = Arc::clone(&coefficients);
+    let mut c1 = InfiniteOperator::new(move |i, j| {
+        let (bi, ri) = (i % d, i / d);
+        let (bj, rj) = (j % d, j / d);
+        if bi + 1 < d || bj + 1 < d {
+            if i == j { one } else { zero }
+        } else {
+            c1_coefficients[d].get(ri, rj)
+        }
+    });
+    if let Some((lower, upper)) = bandwidths {
+        c0 = c0.with_bandwidths(lower * d + d - 1, (upper * d).max(1));
+        c1 = c1.with_bandwidths(lower * d, upper * d);
+    }
+    (c0, c1)
+}
+
+/// Eigenvalues of the operator polynomial P(λ) = Σₖ λᵏ Aₖ from n×n coefficient sections
+///
+/// Solves the companion pencil of [`companion_pencil`] with
+/// [`generalized_eigenvalues`]; its eigenvalues are those of P(λ) directly. A
+/// singular leading coefficient shows up as infinite eigenvalues; a singular
+/// polynomial (det P(λ) ≡ 0) is an error as for [`generalized_eigenvalues`].
+pub fn polynomial_eigenvalues(
+    coefficients: Vec<InfiniteOperator>,
+    n: usize,
+) -> Result<GeneralizedEigenResult, InfiniteArraysError> {
+    let d = coefficients.len() - 1;
+    let (c0, c1) = companion_pencil(coefficients);
+    let mut result = generalized_eigenvalues(&c0, &c1, d * n)?;
+    result.n = n;
+    Ok(result)
+}
+
+/// Create an infinite diagonal operator
+pub fn create_diagonal_operator<F>(diagonal_values: F) -> InfiniteOperator
+where
+    F: Fn(usize) -> Complex64 + Send + Sync + 'static,
+{
+    InfiniteOperator::from_operator(DiagonalOperator::new(diagonal_values))
+}
+
+/// Create an infinite tridiagonal operator
+pub fn create_tridiagonal_operator<F1, F2, F3>(
+    main_diag: F1,
+    upper_diag: Option<F2>,
+    lower_diag: Option<F3>,
+) -> InfiniteOperator
+where
+    F1: Fn(usize) -> Complex64 + Send + Sync + 'static,
+    F2: Fn(usize) -> Complex64 + Send + Sync + 'static,
+    F3: Fn(usize) -> Complex64 + Send + Sync + 'static,
+{
+    // Missing off-diagonals are zero but keep the bandwidths at (1, 1)
+    let zero = || diagonal_fn(|_| Complex64::new(0.0, 0.0));
+    InfiniteOperator::from_diagonals(vec![
+        (-1, lower_diag.map_or_else(zero, diagonal_fn)),
+        (0, diagonal_fn(main_diag)),
+        (1, upper_diag.map_or_else(zero, diagonal_fn)),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::{laplacian, real};
+    use approx::assert_abs_diff_eq;
+
+    #[test]
+    fn iqr_spectrum_tracks_the_eigenvalues_across_truncations() {
+        let operator = create_diagonal_operator(|i| real(1.0 / (i + 1) as f64));
+        let spectrum = iqr_spectrum(&operator, &[8, 4], 100, 1e-12).unwrap();
+        assert_eq!(spectrum.eigenvalues_by_n.keys().copied().collect::<Vec<_>>(), [4, 8]);
+        for (&n, result) in &spectrum.eigenvalues_by_n {
+            assert_eq!(result.eigenvalues.len(), n);
+            for (k, lambda) in result.eigenvalues.iter().enumerate() {
+                assert_abs_diff_eq!(lambda.re, 1.0 / (k + 1) as f64, epsilon = 1e-12);
+            }
+        }
+        assert_abs_diff_eq!(spectrum.eigenvalues[0].re, 1.0, epsilon = 1e-12);
+        assert_eq!(iqr_spectrum(&operator, &[], 100, 1e-12).unwrap_err(), InfiniteArraysError::EmptyTruncation);
+    }
+
+    /// Eigenvalues sorted by real part
+    fn ascending(mut values: Vec<Complex64>) -> Vec<Complex64> {
+        values.sort_by(|a, b| a.re.total_cmp(&b.re));
+        values
+    }
+
+    #[test]
+    fn iqr_finds_the_spectrum_of_a_diagonal_operator() {
+        let operator = create_diagonal_operator(|i| real((i + 1) as f64));
+        let result = iqr_algorithm(&operator, 6, 100, 1e-12, None, true).unwrap();
+        assert!(result.converged);
+        // Largest magnitude first
+        for (k, lambda) in result.eigenvalues.iter().enumerate() {
+            assert_abs_diff_eq!(lambda.re, (6 - k) as f64, epsilon = 1e-12);
+            assert_abs_diff_eq!(lambda.im, 0.0, epsilon = 1e-12);
+        }
+        let vectors = result.eigenvectors.unwrap();
+        assert_abs_diff_eq!(vectors[(5, 0)].norm(), 1.0, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn iqr_finds_the_laplacian_eigenvalues() {
+        // The n×n section of tridiag(1, −2, 1) has eigenvalues −2 + 2 cos(kπ/(n + 1))
+        let laplacian = laplacian();
+        let n = 8;
+        let result = iqr_algorithm(&laplacian, n, 500, 1e-12, None, false).unwrap();
+        assert!(result.converged);
+        let expected = ascending(
+            (1..=n).map(|k| real(-2.0 + 2.0 * (k as f64 * std::f64::consts::PI / (n + 1) as f64).cos())).collect(),
+        );
+        for (lambda, exact) in ascending(result.eigenvalues).iter().zip(&expected) {
+            assert_abs_diff_eq!(lambda.re, exact.re, epsilon = 1e-10);
+            assert_abs_diff_eq!(lambda.im, 0.0, epsilon = 1e-10);
+        }
+    }
+
+    #[test]
+    fn operator_entries_are_cached() {
+        let calls = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let counter = Arc::clone(&calls);
+        let operator = InfiniteOperator::new(move |i, j| {
+            counter.fetch_add(1, Ordering::Relaxed);
+            real((i + j) as f64)
+        });
+        assert_eq!(operator.get(2, 5), real(7.0));
+        assert_eq!(operator.get(2, 5), real(7.0));
+        assert_eq!(operator.get_truncation(2)[(1, 1)], real(2.0));
+        assert_eq!(calls.load(Ordering::Relaxed), 5);
+    }
+
+    #[test]
+    fn apply_sums_past_a_gap_without_caching_the_row() {
+        // Row 0 has entries at columns 0 and 100 only; the blocks between are zero
+        let operator = InfiniteOperator::new(|i, j| if i == 0 && (j == 0 || j == 100) { real(1.0) } else { real(0.0) });
+        let x: Arc<dyn InfiniteArray> = Arc::new(crate::arrays::Ones::new(None));
+        let y = operator.apply(x, 1e-12);
+        assert_abs_diff_eq!(y.get(0), 2.0);
+        assert!(operator.cache.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn generalized_eigenvalues_of_a_regular_pencil() {
+        let a = create_diagonal_operator(|i| real((i + 1) as f64));
+        let b = create_diagonal_operator(|_| real(2.0));
+        let result = generalized_eigenvalues(&a, &b, 4).unwrap();
+        assert_eq!(result.infinite_eigenvalues, 0);
+        // sorted by decreasing magnitude
+        for (k, lambda) in result.eigenvalues.iter().enumerate() {
+            assert_abs_diff_eq!(lambda.re, (4 - k) as f64 / 2.0, epsilon = 1e-10);
+            assert_abs_diff_eq!(lambda.im, 0.0, epsilon = 1e-10);
+        }
+    }
+
+    #[test]
+    fn generalized_eigenvalues_count_infinite_eigenvalues() {
+        let a = create_diagonal_operator(|i| real((i + 1) as f64));
+        let b = create_diagonal_operator(|i| real(if i == 0 { 0.0 } else { 1.0 }));
+        let result = generalized_eigenvalues(&a, &b, 4).unwrap();
+        assert_eq!(result.infinite_eigenvalues, 1);
+        assert_eq!(result.eigenvalues.len(), 3);
+    }
+
+    #[test]
+    fn generalized_eigenvalues_reject_a_singular_pencil() {
+        // A and B share a zero first row, so det(A − λB) = 0 for every λ
+        let a = create_diagonal_operator(|i| real(i as f64));
+        let b = create_diagonal_operator(|i| real(if i == 0 { 0.0 } else { 1.0 }));
+        assert_eq!(generalized_eigenvalues(&a, &b, 4).unwrap_err(), InfiniteArraysError::SingularPencil);
+        assert_eq!(generalized_eigenvalues(&a, &b, 0).unwrap_err(), InfiniteArraysError::EmptyTruncation);
+    }
+
+    #[test]
+    fn badly_scaled_normal_truncation_does_not_warn() {
+        // κ₂ = 10¹⁵, but the eigenvalues of a diagonal operator are perfectly conditioned
+        let operator = create_diagonal_operator(|i| real(0.1f64.powi(i as i32)));
+        assert!(truncation_condition(&operator, 16) > ILL_CONDITIONED_THRESHOLD);
+        let options = IqrOptions { eigenvalue_conditioning: true, ..IqrOptions::default() };
+        let result = iqr_algorithm_with_options(&operator, 16, 10, 1e-12, None, false, &options).unwrap();
+        assert_eq!(result.warning, None);
+    }
+
+    #[test]
+    fn non_normal_truncation_warns_when_asked() {
+        // Upper bidiagonal with diagonal 0, 1, 2, ... and 100 above it: κ(λ) ~ 100ⁿ/n!
+        let operator = crate::banded_operator!{ 0 => |i| i as f64, 1 => |_| 100.0 };
+        let result = iqr_algorithm(&operator, 12, 5, 1e-12, None, false).unwrap();
+        assert_eq!(result.warning, None);
+
+        let options = IqrOptions { eigenvalue_conditioning: true, ..IqrOptions::default() };
+        let result = iqr_algorithm_with_options(&operator, 12, 5, 1e-12, None, false, &options).unwrap();
+        assert!(matches!(
+            result.warning,
+            Some(IqrWarning::IllConditioned { condition_number }) if condition_number > ILL_CONDITIONED_THRESHOLD
+        ));
+
+        let spectrum = iqr_spectrum_with_options(&operator, &[4, 12], 5, 1e-12, &options).unwrap();
+        assert_eq!(spectrum.eigenvalues_by_n[&4].warning, None);
+        assert!(spectrum.eigenvalues_by_n[&12].warning.is_some());
+    }
+
+    #[test]
+    fn result_display_is_plain_ascii_under_ascii() {
+        let result = IqrResult {
+            eigenvalues: vec![real(2.0)],
+            eigenvectors: None,
+            iterations: 3,
+            converged: true,
+            residual: None,
+            warning: Some(IqrWarning::IllConditioned { condition_number: 1e9 }),
+            interrupted: false,
+            error_bounds: Some(vec![1e-10]),
+        };
+        let unicode = result.to_string();
+        assert!(unicode.contains("κ(λ)") && unicode.contains("λ_1") && unicode.contains("±"));
+        let ascii = display::Ascii(&result).to_string();
+        assert!(ascii.is_ascii());
+        assert!(ascii.contains("kappa(lambda)") && ascii.contains("lambda_1") && ascii.contains("+/-"));
+    }
+
+    #[test]
+    fn iqr_state_round_trips_through_its_binary_layout() {
+        let laplacian = laplacian();
+        let mut state = IqrState::new(&laplacian, 5, true);
+        iqr_resume(&mut state, 3, 1e-12, None, &IqrOptions::default()).unwrap();
+        let mut bytes = Vec::new();
+        state.write_to(&mut bytes).unwrap();
+        assert!(bytes.starts_with(STATE_MAGIC));
+        let read = IqrState::read_from(&bytes[..]).unwrap();
+        assert_eq!(read.iterations, 3);
+        assert_eq!(read.a, state.a);
+        assert_eq!(read.q, state.q);
+
+        let without_q = IqrState::new(&laplacian, 3, false);
+        let mut bytes = Vec::new();
+        without_q.write_to(&mut bytes).unwrap();
+        assert_eq!(IqrState::read_from(&bytes[..]).unwrap(), without_q);
+    }
+
+    #[test]
+    fn iqr_state_rejects_corrupt_input() {
+        let state = IqrState::new(&create_diagonal_operator(|i| real(i as f64)), 3, true);
+        let mut bytes = Vec::new();
+        state.write_to(&mut bytes).unwrap();
+
+        let mut bad_magic = bytes.clone();
+        bad_magic[0] = b'X';
+        assert_eq!(IqrState::read_from(&bad_magic[..]).unwrap_err().kind(), io::ErrorKind::InvalidData);
+        let mut bad_version = bytes.clone();
+        bad_version[8] = 2;
+        assert_eq!(IqrState::read_from(&bad_version[..]).unwrap_err().kind(), io::ErrorKind::InvalidData);
+        let mut bad_flag = bytes.clone();
+        bad_flag[28] = 7;
+        assert_eq!(IqrState::read_from(&bad_flag[..]).unwrap_err().kind(), io::ErrorKind::InvalidData);
+        let truncated = &bytes[..bytes.len() - 1];
+        assert_eq!(IqrState::read_from(truncated).unwrap_err().kind(), io::ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn resuming_a_saved_state_matches_an_uninterrupted_run() {
+        let laplacian = laplacian();
+        let uninterrupted = iqr_algorithm(&laplacian, 8, 500, 1e-12, None, false).unwrap();
+        assert!(uninterrupted.converged);
+
+        let mut state = IqrState::new(&laplacian, 8, false);
+        let first = iqr_resume(&mut state, 2, 1e-12, None, &IqrOptions::default()).unwrap();
+        assert!(!first.converged);
+        let mut bytes = Vec::new();
+        state.write_to(&mut bytes).unwrap();
+        let mut restored = IqrState::read_from(&bytes[..]).unwrap();
+        let resumed = iqr_resume(&mut restored, 498, 1e-12, None, &IqrOptions::default()).unwrap();
+        assert!(resumed.converged);
+        assert!(resumed.iterations > 2);
+        for (lambda, expected) in ascending(resumed.eigenvalues).iter().zip(&ascending(uninterrupted.eigenvalues)) {
+            assert_abs_diff_eq!(lambda.re, expected.re, epsilon = 1e-10);
+            assert_abs_diff_eq!(lambda.im, expected.im, epsilon = 1e-10);
+        }
+    }
+
+    #[test]
+    fn second_order_spectrum_does_not_pollute_a_spectral_gap() {
+        // Hoppings 2, 1, 2, 1, ... give the spectrum [−3, −1] ∪ [1, 3]; odd sections
+        // end on a dangling site whose eigenvalue 0 lies in the gap
+        let dimer = crate::banded_operator! { -1 => |i| if i % 2 == 0 { 2.0 } else { 1.0 }, 1 => |i| if i % 2 == 0 { 2.0 } else { 1.0 } };
+        let in_spectrum = |x: f64| (1.0..=3.0).contains(&x.abs());
+        let n = 15;
+        let galerkin = iqr_algorithm(&dimer, n, 1000, 1e-12, None, false).unwrap();
+        assert!(galerkin.eigenvalues.iter().any(|lambda| lambda.norm() < 1e-8));
+
+        let result = second_order_spectrum(&dimer, n);
+        assert_eq!((result.n, result.points.len(), result.enclosures.len()), (n, 2 * n, 2 * n));
+        for (&(lo, hi), z) in result.enclosures.iter().zip(&result.points) {
+            assert!(hi >= lo);
+            // Every enclosure meets the spectrum
+            assert!(hi >= -3.0 && lo <= 3.0 && !(lo > -1.0 && hi < 1.0), "{} misses the spectrum", z);
+            if z.im.abs() < 1e-6 {
+                assert!(in_spectrum(z.re), "spurious point {}", z);
+            }
+        }
+
+        // For a diagonal operator the quadratic (D − z)² has double roots at the eigenvalues
+        let diagonal = create_diagonal_operator(|i| real((i + 1) as f64));
+        let result = second_order_spectrum(&diagonal, 4);
+        for (k, z) in result.points.iter().enumerate() {
+            assert_abs_diff_eq!(z.re, (4 - k / 2) as f64, epsilon = 1e-6);
+            assert_abs_diff_eq!(z.im, 0.0, epsilon = 1e-6);
+        }
+    }
+
+    #[test]
+    fn polynomial_eigenvalues_of_a_diagonal_quadratic() {
+        // Row k of λ²M + λC + K is (λ − a)(λ − b) with a = k + 1, b = −(k + 3/2)
+        let roots = |k: usize| ((k + 1) as f64, -(k as f64 + 1.5));
+        let k = create_diagonal_operator(move |i| real(roots(i).0 * roots(i).1));
+        let c = create_diagonal_operator(move |i| real(-(roots(i).0 + roots(i).1)));
+        let m = create_diagonal_operator(|_| real(1.0));
+        let result = polynomial_eigenvalues(vec![k, c, m], 3).unwrap();
+        assert_eq!((result.n, result.infinite_eigenvalues), (3, 0));
+        let mut expected: Vec<f64> = (0..3).flat_map(|i| [roots(i).0, roots(i).1]).collect();
+        expected.sort_by(|a, b| a.total_cmp(b));
+        for (lambda, exact) in ascending(result.eigenvalues).iter().zip(&expected) {
+            assert_abs_diff_eq!(lambda.re, exact, epsilon = 1e-10);
+            assert_abs_diff_eq!(lambda.im, 0.0, epsilon = 1e-10);
+        }
+
+        // λ² + (k + 1)² has the roots ±i(k + 1); a zero leading entry leaves the
+        // linear factor λ + 2 and one infinite eigenvalue
+        let k = create_diagonal_operator(|i| real(((i + 1) * (i + 1)) as f64));
+        let c = create_diagonal_operator(|i| real(if i == 0 { 1.0 } else { 0.0 }));
+        let m = create_diagonal_operator(|i| real(if i == 0 { 0.0 } else { 1.0 }));
+        let result = polynomial_eigenvalues(vec![k, c, m], 2).unwrap();
+        assert_eq!(result.infinite_eigenvalues, 1);
+        let mut eigenvalues = result.eigenvalues;
+        eigenvalues.sort_by(|a, b| a.re.total_cmp(&b.re).then(a.im.total_cmp(&b.im)));
+        let expected = [real(-1.0), Complex64::new(0.0, -2.0), Complex64::new(0.0, 2.0)];
+        assert_eq!(eigenvalues.len(), expected.len());
+        for (lambda, exact) in eigenvalues.iter().zip(&expected) {
+            assert_abs_diff_eq!((lambda - exact).norm(), 0.0, epsilon = 1e-8);
+        }
+    }
+
+    #[test]
+    fn companion_pencil_interleaves_the_blocks() {
+        let k = create_diagonal_operator(|i| real(i as f64 + 1.0));
+        let c = create_diagonal_operator(|_| real(2.0));
+        let m = create_diagonal_operator(|_| real(3.0));
+        let (c0, c1) = companion_pencil(vec![k, c, m]);
+        // Rows 2k and 2k + 1 belong to (x, λx) at index k
+        assert_eq!(c0.get(0, 1), real(1.0));
+        assert_eq!((c0.get(1, 0), c0.get(1, 1)), (real(-1.0), real(-2.0)));
+        assert_eq!((c0.get(3, 2), c0.get(3, 3)), (real(-2.0), real(-2.0)));
+        assert_eq!((c1.get(0, 0), c1.get(1, 1), c1.get(2, 2), c1.get(3, 3)), (real(1.0), real(3.0), real(1.0), real(3.0)));
+        assert_eq!(c0.bandwidths(), Some((1, 1)));
+        assert_eq!(c1.bandwidths(), Some((0, 0)));
+    }
+
+    #[test]
+    fn adaptive_spectrum_stops_once_the_leading_eigenvalues_settle() {
+        // The three largest eigenvalues 1, 1/2, 1/3 are already exact at n = 8
+        let decaying = create_diagonal_operator(|i| real(1.0 / (i + 1) as f64));
+        let result = iqr_spectrum_adaptive(&decaying, 3, 1e-8, 1024, 100).unwrap();
+        assert!(result.converged);
+        assert_eq!(result.sizes, [8, 16]);
+        assert_eq!(result.n, 16);
+        for (k, lambda) in result.eigenvalues.iter().enumerate() {
+            assert_abs_diff_eq!(lambda.re, 1.0 / (k + 1) as f64, epsilon = 1e-10);
+        }
+        assert!(result.error_estimates.iter().all(|&e| e < 1e-8));
+
+        // The largest eigenvalues of diag(1, 2, 3, ...) are those of the last rows,
+        // so they move with every size until max_n
+        let growing = create_diagonal_operator(|i| real((i + 1) as f64));
+        let result = iqr_spectrum_adaptive(&growing, 2, 1e-8, 32, 100).unwrap();
+        assert!(!result.converged);
+        assert_eq!(result.sizes, [8, 16, 32]);
+        assert_eq!(result.n, 32);
+        assert_abs_diff_eq!(result.eigenvalues[0].re, 32.0, epsilon = 1e-10);
+        assert!(result.error_estimates.iter().all(|&e| e >= 1e-8));
+
+        assert_eq!(iqr_spectrum_adaptive(&growing, 2, 1e-8, 0, 100).unwrap_err(), InfiniteArraysError::EmptyTruncation);
+    }
+
+    fn written(write: impl FnOnce(&mut Vec<u8>) -> io::Result<()>) -> String {
+        let mut bytes = Vec::new();
+        write(&mut bytes).unwrap();
+        String::from_utf8(bytes).unwrap()
+    }
+
+    #[test]
+    fn result_csv_has_one_row_per_eigenvalue() {
+        let mut result = IqrResult {
+            eigenvalues: vec![real(2.0), Complex64::new(3.0, 4.0)],
+            eigenvectors: None,
+            iterations: 3,
+            converged: true,
+            residual: None,
+            warning: None,
+            interrupted: false,
+            error_bounds: None,
+        };
+        let csv = written(|w| result.write_csv_to(w));
+        assert_eq!(csv, "index,re,im,abs,error_bound\n0,2,0,2,\n1,3,4,5,\n");
+        result.error_bounds = Some(vec![0.25, 0.5]);
+        let csv = written(|w| result.write_csv_to(w));
+        assert_eq!(csv, "index,re,im,abs,error_bound\n0,2,0,2,0.25\n1,3,4,5,0.5\n");
+    }
+
+    #[test]
+    fn spectrum_csv_annotates_the_largest_truncation() {
+        let operator = create_diagonal_operator(|i| real(1.0 / (i + 1) as f64));
+        let spectrum = iqr_spectrum(&operator, &[2, 4], 100, 1e-12).unwrap();
+        let csv = written(|w| spectrum.write_csv_to(w));
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], "n,converged,index,re,im,error_bound,status,limit_re,limit_im");
+        assert_eq!(lines.len(), 1 + 2 + 4);
+        assert_eq!(lines[1], "2,true,0,1,0,,,,");
+        assert_eq!(lines[2], "2,true,1,0.5,0,,,,");
+        for (i, line) in lines[3..].iter().enumerate() {
+            let fields: Vec<&str> = line.split(',').collect();
+            assert_eq!(fields.len(), 9);
+            assert_eq!(&fields[..3], ["4", "true", &i.to_string()]);
+            assert_eq!(fields[6], spectrum.classifications[i].to_string());
+            assert_eq!(fields[7].parse::<f64>().unwrap(), spectrum.trajectories[i].limit.re);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn json_output_parses_back() {
+        let operator = create_diagonal_operator(|i| real((i + 1) as f64));
+        let result = iqr_algorithm(&operator, 3, 100, 1e-12, None, true).unwrap();
+        let json: serde_json::Value = serde_json::from_str(&written(|w| result.write_json_to(w))).unwrap();
+        assert_eq!(json["converged"], serde_json::json!(true));
+        assert_eq!(json["eigenvalues"][0], serde_json::json!([3.0, 0.0]));
+        assert_eq!(json["eigenvectors"]["dim"], serde_json::json!([3, 3]));
+
+        let spectrum = iqr_spectrum(&operator, &[2, 3], 100, 1e-12).unwrap();
+        let json: serde_json::Value = serde_json::from_str(&written(|w| spectrum.write_json_to(w))).unwrap();
+        assert!(json.is_object());
+        assert_eq!(json["eigenvalues"].as_array().unwrap().len(), spectrum.eigenvalues.len());
+    }
+
+    #[test]
+    fn classification_tells_stable_from_drifting_eigenvalues() {
+        // 1/2 is an eigenvalue at every size; the largest eigenvalue n moves with n
+        let operator = create_diagonal_operator(|i| real(if i == 0 { 0.5 } else { i as f64 }));
+        let spectrum = iqr_spectrum(&operator, &[4, 8], 100, 1e-12).unwrap();
+        let status_of = |value: f64| {
+            let k = spectrum.eigenvalues.iter().position(|lambda| (lambda - value).norm() < 1e-10).unwrap();
+            spectrum.classifications[k]
+        };
+        assert_eq!(status_of(0.5), SpectralStatus::Stable);
+        assert_eq!(status_of(7.0), SpectralStatus::Suspect);
+
+        // Without a previous spectrum nothing can be confirmed
+        assert_eq!(classify_eigenvalues(&[real(1.0)], None, 1e-12), [SpectralStatus::Suspect]);
+        // 5 sits inside [0, 10] far from any previous eigenvalue: pollution
+        let previous = [real(0.0), real(1.0), real(10.0)];
+        assert_eq!(
+            classify_eigenvalues(&[real(1.0), real(1.5), real(5.0), real(20.0)], Some(&previous), 1e-12),
+            [SpectralStatus::Stable, SpectralStatus::Suspect, SpectralStatus::Polluted, SpectralStatus::Suspect]
+        );
+    }
+
+    #[test]
+    fn aitken_limit_is_exact_on_geometric_sequences() {
+        // x_k = 1 + i + 2⁻ᵏ
+        let points: Vec<(usize, Complex64)> = (1..=4).map(|k| (8 * k, Complex64::new(1.0 + 0.5f64.powi(k as i32), 1.0))).collect();
+        let limit = aitken_limit(&points);
+        assert_abs_diff_eq!(limit.re, 1.0, epsilon = 1e-15);
+        assert_abs_diff_eq!(limit.im, 1.0, epsilon = 1e-15);
+        // Too short or not contracting: the last point
+        assert_eq!(aitken_limit(&points[..2]), points[1].1);
+        let growing = [(1, real(0.0)), (2, real(1.0)), (3, real(3.0))];
+        assert_eq!(aitken_limit(&growing), real(3.0));
+    }
+
+    #[test]
+    fn trajectories_follow_matched_eigenvalues_back() {
+        let result = |eigenvalues: Vec<Complex64>| IqrResult {
+            eigenvalues,
+            eigenvectors: None,
+            iterations: 1,
+            converged: true,
+            residual: None,
+            warning: None,
+            interrupted: false,
+            error_bounds: None,
+        };
+        let mut results = BTreeMap::new();
+        results.insert(2, result(vec![real(2.1), real(1.2)]));
+        results.insert(3, result(vec![real(5.0), real(2.05), real(1.1)]));
+        let trajectories = eigenvalue_trajectories(&results);
+        assert_eq!(trajectories.len(), 3);
+        // 5 is new at n = 3; the others are matched to their neighbours at n = 2
+        assert_eq!(trajectories[0].points, [(3, real(5.0))]);
+        assert_eq!(trajectories[1].points, [(2, real(2.1)), (3, real(2.05))]);
+        assert_eq!(trajectories[2].points, [(2, real(1.2)), (3, real(1.1))]);
+        assert_eq!(trajectories[1].limit, real(2.05));
+        assert_abs_diff_eq!(trajectories[1].last_step(), 0.05, epsilon = 1e-12);
+        assert_eq!(trajectories[0].last_step(), f64::INFINITY);
+        assert!(eigenvalue_trajectories(&BTreeMap::new()).is_empty());
+    }
+
+    #[test]
+    fn convergence_criteria_measure_different_entries() {
+        let a = ndarray::array![[real(1.0), real(2.0)], [real(3.0), real(4.0)]];
+        let previous = [real(1.0), real(3.0)];
+        assert_eq!(ConvergenceCriterion::MaxOffDiagonal.measure(&a, &previous), 3.0);
+        assert_eq!(ConvergenceCriterion::Subdiagonal.measure(&a, &previous), 3.0);
+        assert_eq!(ConvergenceCriterion::RelativeSubdiagonal.measure(&a, &previous), 0.6);
+        assert_eq!(ConvergenceCriterion::EigenvalueMovement.measure(&a, &previous), 0.25);
+        let custom = ConvergenceCriterion::Custom(Arc::new(|a: &Array2<Complex64>, _: &[Complex64]| a[(0, 1)].re));
+        assert_eq!(custom.measure(&a, &previous), 2.0);
+    }
+
+    #[test]
+    fn convergence_criterion_decides_when_the_iteration_stops() {
+        // An upper triangular iterate stays triangular: the subdiagonal vanishes after
+        // one step, while the entries above the diagonal never do
+        let operator = crate::banded_operator! { 0 => |i| (i + 1) as f64, 1 => |_| 100.0 };
+        let run = |criterion: ConvergenceCriterion| {
+            iqr_algorithm_with_criterion(&operator, 6, 20, 1e-12, None, false, &criterion).unwrap()
+        };
+        let subdiagonal = run(ConvergenceCriterion::Subdiagonal);
+        assert!(subdiagonal.converged);
+        assert_eq!(subdiagonal.iterations, 1);
+        let off_diagonal = run(ConvergenceCriterion::MaxOffDiagonal);
+        assert!(!off_diagonal.converged);
+        assert_eq!(off_diagonal.iterations, 20);
+        let never = run(ConvergenceCriterion::Custom(Arc::new(|_: &Array2<Complex64>, _: &[Complex64]| f64::INFINITY)));
+        assert!(!never.converged);
+        assert_eq!(never.iterations, 20);
+    }
+
+    #[test]
+    fn callbacks_report_every_step_and_truncation() {
+        let laplacian = laplacian();
+        let steps = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let truncations = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let (step_log, truncation_log) = (Arc::clone(&steps), Arc::clone(&truncations));
+        let options = IqrOptions {
+            on_iteration: Some(Arc::new(move |iteration, progress: &IqrProgress| {
+                step_log.lock().unwrap().push((progress.n, iteration, progress.eigenvalues.len()));
+            })),
+            on_truncation: Some(Arc::new(move |n, result: &IqrResult| {
+                truncation_log.lock().unwrap().push((n, result.iterations));
+            })),
+            ..IqrOptions::default()
+        };
+
+        let result = iqr_algorithm_with_options(&laplacian, 6, 500, 1e-12, None, false, &options).unwrap();
+        let recorded = std::mem::take(&mut *steps.lock().unwrap());
+        assert_eq!(recorded.len(), result.iterations);
+        for (k, &(n, iteration, estimates)) in recorded.iter().enumerate() {
+            assert_eq!((n, iteration, estimates), (6, k + 1, 6));
+        }
+        // on_truncation only fires for spectra
+        assert!(truncations.lock().unwrap().is_empty());
+
+        let spectrum = iqr_spectrum_with_options(&laplacian, &[8, 4], 500, 1e-12, &options).unwrap();
+        let mut finished = truncations.lock().unwrap().clone();
+        finished.sort_unstable();
+        let expected: Vec<(usize, usize)> = spectrum.eigenvalues_by_n.iter().map(|(&n, r)| (n, r.iterations)).collect();
+        assert_eq!(finished, expected);
+        let recorded = steps.lock().unwrap();
+        for n in [4, 8] {
+            let iterations: Vec<usize> = recorded.iter().filter(|step| step.0 == n).map(|step| step.1).collect();
+            assert_eq!(iterations, (1..=spectrum.eigenvalues_by_n[&n].iterations).collect::<Vec<_>>());
+        }
+    }
+
+    #[test]
+    fn a_set_cancel_flag_interrupts() {
+        let laplacian = laplacian();
+        let cancel = Arc::new(AtomicBool::new(true));
+        let options = IqrOptions { cancel: Some(Arc::clone(&cancel)), ..IqrOptions::default() };
+        let result = iqr_algorithm_with_options(&laplacian, 6, 500, 1e-12, None, false, &options).unwrap();
+        assert!(result.interrupted);
+        assert!(!result.converged);
+        assert_eq!(result.iterations, 0);
+        // The diagonal of the untouched truncation
+        assert!(result.eigenvalues.iter().all(|&lambda| lambda == real(-2.0)));
+        let spectrum = iqr_spectrum_with_options(&laplacian, &[4, 6], 500, 1e-12, &options).unwrap();
+        assert!(spectrum.interrupted);
+
+        // Cancelled from a callback after the third step
+        let flag = Arc::new(AtomicBool::new(false));
+        let setter = Arc::clone(&flag);
+        let options = IqrOptions {
+            cancel: Some(flag),
+            on_iteration: Some(Arc::new(move |iteration, _: &IqrProgress| {
+                if iteration == 3 {
+                    setter.store(true, Ordering::Relaxed);
+                }
+            })),
+            ..IqrOptions::default()
+        };
+        let result = iqr_algorithm_with_options(&laplacian, 6, 500, 1e-12, None, false, &options).unwrap();
+        assert!(result.interrupted);
+        assert_eq!(result.iterations, 3);
+    }
+
+    #[test]
+    fn sort_by_orders_the_eigenvalues() {
+        let values = [real(1.0), real(-3.0), Complex64::new(0.0, 2.0), Complex64::new(0.5, -1.0)];
+        assert_eq!(SortBy::Magnitude.order(&values), [1, 2, 3, 0]);
+        assert_eq!(SortBy::RealPart.order(&values), [0, 3, 2, 1]);
+        // Ties keep their order
+        assert_eq!(SortBy::ImaginaryPart.order(&values), [2, 0, 1, 3]);
+        assert_eq!(SortBy::DistanceTo(Complex64::new(0.0, 2.0)).order(&values), [2, 0, 3, 1]);
+        assert_eq!(SortBy::None.order(&values), [0, 1, 2, 3]);
+
+        // Eigenvectors are permuted along with the eigenvalues
+        let operator = create_diagonal_operator(|i| real([-3.0, 1.0, 2.0][i.min(2)]));
+        let options = IqrOptions { sort_by: SortBy::RealPart, ..IqrOptions::default() };
+        let result = iqr_algorithm_with_options(&operator, 3, 10, 1e-12, None, true, &options).unwrap();
+        assert_eq!(result.eigenvalues, [real(2.0), real(1.0), real(-3.0)]);
+        let vectors = result.eigenvectors.unwrap();
+        for (k, row) in [2, 1, 0].into_iter().enumerate() {
+            assert_abs_diff_eq!(vectors[(row, k)].norm(), 1.0, epsilon = 1e-12);
+        }
+    }
+
+    #[test]
+    fn finite_block_overrides_the_tail() {
+        // A 2×3 boundary block over the tail (i, j) ↦ i − j
+        let block = Array2::from_shape_fn((2, 3), |(i, j)| real(100.0 + (10 * i + j) as f64));
+        let operator = InfiniteOperator::from_finite_and_tail(block.clone(), |i, j| real(i as f64 - j as f64));
+        for i in 0..2 {
+            for j in 0..3 {
+                assert_eq!(operator.get(i, j), block[(i, j)]);
+            }
+        }
+        assert_eq!(operator.get(2, 0), real(2.0));
+        assert_eq!(operator.get(0, 3), real(-3.0));
+        assert_eq!(operator.get(1, 5), real(-4.0));
+        assert_eq!(operator.get(6, 6), real(0.0));
+        let truncation = operator.get_truncation(4);
+        assert_eq!(truncation[(1, 2)], real(112.0));
+        assert_eq!(truncation[(3, 1)], real(2.0));
+    }
+
+    #[test]
+    fn columns_match_the_truncation() {
+        let dense = InfiniteOperator::new(|i, j| Complex64::new(i as f64, j as f64 + 1.0));
+        let banded = crate::banded_operator! { -2 => |i| i as f64, 0 => |_| 1.0, 1 => |i| -(i as f64) };
+        for operator in [&dense, &banded] {
+            let truncation = operator.get_truncation(6);
+            for j in 0..6 {
+                assert_eq!(operator.get_column(j, 6), truncation.column(j).to_vec());
+            }
+        }
+        // Columns past the truncation are still evaluated down to row n − 1
+        assert_eq!(banded.get_column(8, 4), vec![real(0.0); 4]);
+        assert_eq!(dense.get_column(8, 2), [Complex64::new(0.0, 9.0), Complex64::new(1.0, 9.0)]);
+    }
+
+    #[test]
+    #[cfg(feature = "rayon")]
+    fn parallel_truncation_matches_the_serial_one() {
+        /// Dense Hermitian operator, so only its upper triangle is evaluated
+        struct Hermitian;
+        impl LinearOperator for Hermitian {
+            fn get(&self, i: usize, j: usize) -> Complex64 {
+                if i <= j {
+                    Complex64::new((i + j) as f64, j as f64 - i as f64)
+                } else {
+                    self.get(j, i).conj()
+                }
+            }
+            fn is_hermitian(&self) -> bool {
+                true
+            }
+        }
+
+        // Built twice each, so the parallel path starts from an empty cache
+        let operators: [fn() -> InfiniteOperator; 3] = [
+            || InfiniteOperator::new(|i, j| Complex64::new(i as f64, 1.0 / (j + 1) as f64)),
+            || crate::banded_operator! { -2 => |i| i as f64, 0 => |_| 1.0, 1 => |i| -(i as f64) },
+            || InfiniteOperator::from_operator(Hermitian),
+        ];
+        for make in operators {
+            let (serial, parallel) = (make(), make());
+            assert_eq!(parallel.par_get_truncation(12), serial.get_truncation(12));
+            // The second call finds the leading block in the cache
+            assert_eq!(parallel.par_get_truncation(16), serial.get_truncation(16));
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "rayon")]
+    fn parallel_rq_step_is_bit_identical_to_the_serial_one() {
+        let n = 160;
+        assert!(n >= PARALLEL_QR_MIN_N);
+        let mut state = 0x9e37_79b9_7f4a_7c15u64;
+        let mut next = move || {
+            // xorshift64, mapped to [−1, 1)
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            (state >> 11) as f64 / (1u64 << 52) as f64 - 1.0
+        };
+        let a = Array2::from_shape_simple_fn((n, n), || Complex64::new(next(), next()));
+        let step = |threads: usize| {
+            let pool = rayon::ThreadPoolBuilder::new().num_threads(threads).build().unwrap();
+            let (mut a, mut q) = (a.clone(), Array2::eye(n));
+            pool.install(|| RqWorkspace::new(n).rq_step(&mut a, Some(&mut q)));
+            (a, q)
+        };
+        // With a single thread the serial loops run
+        assert_eq!(step(4), step(1));
+    }
+
+    #[test]
+    fn extended_truncation_equals_a_fresh_one() {
+        let dense = InfiniteOperator::new(|i, j| Complex64::new(i as f64, 1.0 / (j + 1) as f64));
+        let banded = crate::banded_operator! { -2 => |i| i as f64, 0 => |_| 1.0, 1 => |i| -(i as f64) };
+        for operator in [&dense, &banded] {
+            let mut matrix = operator.get_truncation(3);
+            operator.extend_truncation(&mut matrix, 7);
+            assert_eq!(matrix, operator.get_truncation(7));
+            operator.extend_truncation(&mut matrix, 7);
+            assert_eq!(matrix, operator.get_truncation(7));
+            // Shrinking keeps the leading block
+            operator.extend_truncation(&mut matrix, 4);
+            assert_eq!(matrix, operator.get_truncation(4));
+            let mut empty = Array2::zeros((0, 0));
+            operator.extend_truncation(&mut empty, 5);
+            assert_eq!(empty, operator.get_truncation(5));
+        }
+    }
+
+    #[test]
+    #[cfg(not(target_arch = "wasm32"))]
+    fn a_poisoned_entry_cache_still_serves_entries() {
+        let operator = InfiniteOperator::new(|i, j| Complex64::new(i as f64, j as f64));
+        assert_eq!(operator.get(1, 2), Complex64::new(1.0, 2.0));
+        let cache = operator.cache.clone();
+        let _ = std::thread::spawn(move || {
+            let _guard = cache.lock().unwrap();
+            panic!("poison the entry cache");
+        })
+        .join();
+        assert!(operator.cache.lock().is_err());
+
+        assert_eq!(operator.get(1, 2), Complex64::new(1.0, 2.0));
+        assert_eq!(operator.get(3, 0), Complex64::new(3.0, 0.0));
+        assert_eq!(operator.get_row(2, 3), [Complex64::new(2.0, 0.0), Complex64::new(2.0, 1.0), Complex64::new(2.0, 2.0)]);
+        assert_eq!(operator.try_get_truncation(4).unwrap(), operator.get_truncation(4));
+        let mut truncation = operator.get_truncation(2);
+        operator.extend_truncation(&mut truncation, 3);
+        assert_eq!(truncation, operator.get_truncation(3));
+    }
+
+    /// Identity entries with a doubled product, so only a delegated `matvec` gives `2x`
+    struct DoublingProduct;
+
+    impl LinearOperator for DoublingProduct {
+        fn get(&self, i: usize, j: usize) -> Complex64 {
+            if i == j { real(1.0) } else { Complex64::ZERO }
+        }
+
+        fn bandwidths(&self) -> Option<(usize, usize)> {
+            Some((0, 0))
+        }
+
+        fn matvec(&self, x: &[Complex64]) -> Vec<Complex64> {
+            x.iter().map(|&xi| 2.0 * xi).collect()
+        }
+    }
+
+    #[test]
+    fn matvec_delegates_to_the_wrapped_operator() {
+        let x = [real(1.0), Complex64::new(0.5, -2.0), real(3.0)];
+        let doubled: Vec<Complex64> = x.iter().map(|&xi| 2.0 * xi).collect();
+        let operator = InfiniteOperator::from_operator(DoublingProduct);
+        assert_eq!(operator.get(1, 1), real(1.0));
+        assert_eq!(operator.matvec(&x), doubled);
+        assert_eq!(LinearOperator::matvec(&operator, &x), doubled);
+        assert_eq!(MatVec::matvec(&operator, &x), doubled);
+
+        // Closures and handles with overridden bandwidths go through the entries
+        assert_eq!(operator.clone().with_bandwidths(1, 1).matvec(&x)[..3], x);
+        let closure = InfiniteOperator::new(|i, j| if i == j { real(1.0) } else { Complex64::ZERO });
+        assert_eq!(closure.matvec(&x), x);
+    }
+
+    #[test]
+    #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+    fn a_passed_deadline_interrupts() {
+        let now = Instant::now();
+        let options = IqrOptions::default().with_deadline(now);
+        assert_eq!(options.deadline(), Some(now));
+        assert!(options.is_interrupted());
+        assert!(!IqrOptions::default().is_interrupted());
+    }
+
+    #[test]
+    #[cfg(feature = "gpu")]
+    fn gpu_backend_agrees_with_the_cpu() {
+        if !gpu::is_available() {
+            eprintln!("skipping gpu_backend_agrees_with_the_cpu: no adapter with 64-bit float shaders");
+            return;
+        }
+        let laplacian = laplacian();
+        let cpu = iqr_algorithm(&laplacian, 8, 500, 1e-12, None, true).unwrap();
+        let options = IqrOptions { backend: Backend::Gpu { min_n: 2 }, ..IqrOptions::default() };
+        let steps = gpu::STEPS.load(std::sync::atomic::Ordering::Relaxed);
+        let gpu = iqr_algorithm_with_options(&laplacian, 8, 500, 1e-12, None, true, &options).unwrap();
+        assert!(gpu::STEPS.load(std::sync::atomic::Ordering::Relaxed) > steps, "the QR steps ran on the CPU");
+        assert!(gpu.converged);
+        for (lambda, expected) in ascending(gpu.eigenvalues).iter().zip(&ascending(cpu.eigenvalues)) {
+            assert_abs_diff_eq!(lambda.re, expected.re, epsilon = 1e-10);
+            assert_abs_diff_eq!(lambda.im, expected.im, epsilon = 1e-10);
+        }
+    }
+}
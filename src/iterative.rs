@@ -0,0 +1,523 @@
+//! Iterative linear solvers for infinite operators.
+//!
+//! Like the eigensolvers, these only need products with finitely supported vectors
+//! (see [`MatVec`]): the iterates grow their support as the operator spreads it, so
+//! no truncation size has to be chosen in advance.
+
+use crate::error::InfiniteArraysError;
+use crate::infinity::Size;
+use crate::iqr::{InfiniteOperator, MatVec};
+use crate::linalg;
+use crate::sync::{Lock, LockValues};
+use num_complex::Complex64;
+
+/// Approximate inverse M⁻¹ used to precondition the iterative solvers
+pub trait Preconditioner {
+    /// Apply M⁻¹ to a finitely supported vector
+    fn apply(&self, r: &[Complex64]) -> Vec<Complex64>;
+}
+
+/// Apply an optional preconditioner, the identity if none is given
+fn precondition(preconditioner: Option<&dyn Preconditioner>, r: &[Complex64]) -> Vec<Complex64> {
+    match preconditioner {
+        Some(m) => m.apply(r),
+        None => r.to_vec(),
+    }
+}
+
+/// Jacobi (diagonal) preconditioner M = diag(A)
+pub struct Jacobi<'a> {
+    operator: &'a InfiniteOperator,
+}
+
+impl<'a> Jacobi<'a> {
+    pub fn new(operator: &'a InfiniteOperator) -> Self {
+        Jacobi { operator }
+    }
+}
+
+impl Preconditioner for Jacobi<'_> {
+    fn apply(&self, r: &[Complex64]) -> Vec<Complex64> {
+        r.iter()
+            .enumerate()
+            .map(|(i, &v)| {
+                let d = self.operator.get(i, i);
+                if d.norm() == 0.0 { v } else { v / d }
+            })
+            .collect()
+    }
+}
+
+/// Size of the shift replacing a zero pivot of [`BandedIlu`], relative to its row
+const ZERO_PIVOT_SHIFT: f64 = 1e-8;
+
+/// Incomplete LU preconditioner on a band of the operator
+///
+/// Factors (without pivoting) the part of A within the given bandwidths, which are
+/// capped at the operator's own; an infinite bandwidth (`Size::Infinite` or `None`)
+/// keeps the operator's. A banded LU has no fill outside its band, so this
+/// is the ILU(0) factorization of that band; with the operator's full bandwidths it
+/// is the exact LU of its sections. Rows are factored on demand. A zero pivot is
+/// replaced by a small shift relative to its row (1 for a zero row), as with no
+/// pivoting the factorization would otherwise break down and feed NaNs to the solver.
+pub struct BandedIlu<'a> {
+    operator: &'a InfiniteOperator,
+    lower: usize,
+    upper: usize,
+    /// Row i holds L[i, i − l .. i] followed by U[i, i ..= i + u]
+    rows: Lock<Vec<Vec<Complex64>>>,
+}
+
+impl<'a> BandedIlu<'a> {
+    /// ILU on the band with the given bandwidths; fails with
+    /// [`InfiniteArraysError::NotBanded`] if the operator has no bandwidths
+    pub fn new(
+        operator: &'a InfiniteOperator,
+        lower: impl Into<Size>,
+        upper: impl Into<Size>,
+    ) -> Result<Self, InfiniteArraysError> {
+        let (op_lower, op_upper) = operator.bandwidths().ok_or(InfiniteArraysError::NotBanded)?;
+        Ok(BandedIlu {
+            operator,
+            lower: lower.into().min_count(op_lower),
+            upper: upper.into().min_count(op_upper),
+            rows: Lock::new(Vec::new()),
+        })
+    }
+    
+    /// ILU on the operator's full band
+    pub fn full_band(operator: &'a InfiniteOperator) -> Result<Self, InfiniteArraysError> {
+        Self::new(operator, Size::Infinite, Size::Infinite)
+    }
+    
+    /// Entry of a factored row at column j, L to the left of the diagonal and U from it
+    fn entry(&self, rows: &[Vec<Complex64>], i: usize, j: usize) -> Complex64 {
+        if j + self.lower < i || j > i + self.upper {
+            return Complex64::new(0.0, 0.0);
+        }
+        rows[i][j + self.lower - i]
+    }
+    
+    fn factor_to(&self, rows: &mut Vec<Vec<Complex64>>, n: usize) {
+        while rows.len() < n {
+            let i = rows.len();
+            let mut row = vec![Complex64::new(0.0, 0.0); self.lower + self.upper + 1];
+            for j in i.saturating_sub(self.lower)..=i + self.upper {
+                let mut value = self.operator.get(i, j);
+                for k in i.saturating_sub(self.lower).max(j.saturating_sub(self.upper))..j.min(i) {
+                    value -= row[k + self.lower - i] * self.entry(rows, k, j);
+                }
+                if j < i {
+                    value /= self.entry(rows, j, j);
+                }
+                row[j + self.lower - i] = value;
+            }
+            let pivot = &mut row[self.lower];
+            if pivot.norm() == 0.0 || !pivot.is_finite() {
+                let scale = (i.saturating_sub(self.lower)..=i + self.upper)
+                    .map(|j| self.operator.get(i, j).norm())
+                    .fold(0.0, f64::max);
+                *pivot = Complex64::new(if scale > 0.0 { ZERO_PIVOT_SHIFT * scale } else { 1.0 }, 0.0);
+            }
+            rows.push(row);
+        }
+    }
+}
+
+impl Preconditioner for BandedIlu<'_> {
+    fn apply(&self, r: &[Complex64]) -> Vec<Complex64> {
+        let n = r.len();
+        let mut rows = self.rows.lock_values();
+        self.factor_to(&mut rows, n);
+        let mut z = r.to_vec();
+        for i in 0..n {
+            let head: Complex64 = (i.saturating_sub(self.lower)..i).map(|k| self.entry(&rows, i, k) * z[k]).sum();
+            z[i] -= head;
+        }
+        for i in (0..n).rev() {
+            let tail: Complex64 = (i + 1..(i + self.upper + 1).min(n)).map(|k| self.entry(&rows, i, k) * z[k]).sum();
+            z[i] = (z[i] - tail) / self.entry(&rows, i, i);
+        }
+        z
+    }
+}
+
+/// Result of an iterative solve
+#[derive(Debug, Clone)]
+pub struct IterativeSolveResult {
+    /// Finitely supported approximate solution
+    pub solution: Vec<Complex64>,
+    /// Relative residual ‖b − Ax‖ / ‖b‖ after every iteration
+    pub residual_history: Vec<f64>,
+    pub iterations: usize,
+    pub restarts: usize,
+    pub converged: bool,
+}
+
+/// Restarted GMRES(m) for A x = b
+///
+/// Each cycle builds a Krylov basis of at most `restart` vectors with modified
+/// Gram–Schmidt and minimizes the residual over it with Givens rotations. Stops when
+/// the relative residual drops to `tol` or after `max_restarts` cycles; the true
+/// residual is recomputed at each restart. A preconditioner is applied from the
+/// right, so the reported residuals are those of the original system.
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all, fields(restart, tol)))]
+pub fn gmres<M: MatVec + ?Sized>(
+    op: &M,
+    b: &[Complex64],
+    restart: usize,
+    tol: f64,
+    max_restarts: usize,
+    preconditioner: Option<&dyn Preconditioner>,
+) -> IterativeSolveResult {
+    let b_norm = linalg::norm(b);
+    let mut x: Vec<Complex64> = Vec::new();
+    let mut residual_history = Vec::new();
+    let mut iterations = 0;
+    let mut restarts = 0;
+    if b_norm == 0.0 {
+        return IterativeSolveResult {
+            solution: x,
+            residual_history,
+            iterations,
+            restarts,
+            converged: true,
+        };
+    }
+    
+    let m = restart.max(1);
+    let mut r = b.to_vec();
+    loop {
+        let beta = linalg::norm(&r);
+        debug_event!(restarts, iterations, residual = beta / b_norm, "GMRES cycle start");
+        if beta <= tol * b_norm || restarts >= max_restarts {
+            return IterativeSolveResult {
+                solution: x,
+                residual_history,
+                iterations,
+                restarts,
+                converged: beta <= tol * b_norm,
+            };
+        }
+        
+        let mut v0 = r;
+        linalg::normalize(&mut v0);
+        let mut basis = vec![v0];
+        let mut h: Vec<Vec<Complex64>> = Vec::with_capacity(m);
+        let mut rotations: Vec<(f64, Complex64)> = Vec::with_capacity(m);
+        let mut g = vec![Complex64::new(beta, 0.0)];
+        for j in 0..m {
+            let mut w = op.matvec(&precondition(preconditioner, &basis[j]));
+            let mut column = vec![Complex64::new(0.0, 0.0); j + 2];
+            for _ in 0..2 {
+                for (i, q) in basis.iter().enumerate() {
+                    let hij = linalg::dot(q, &w);
+                    column[i] += hij;
+                    linalg::axpy(&mut w, -hij, q);
+                }
+            }
+            let h_next = linalg::normalize(&mut w);
+            column[j + 1] = Complex64::new(h_next, 0.0);
+            
+            for (i, &(c, s)) in rotations.iter().enumerate() {
+                let (p, q) = (column[i], column[i + 1]);
+                column[i] = p * c + s * q;
+                column[i + 1] = -s.conj() * p + q * c;
+            }
+            let (c, s) = linalg::givens(column[j], column[j + 1]);
+            column[j] = column[j] * c + s * column[j + 1];
+            column[j + 1] = Complex64::new(0.0, 0.0);
+            rotations.push((c, s));
+            g.push(-s.conj() * g[j]);
+            g[j] *= c;
+            h.push(column);
+            iterations += 1;
+            
+            let estimate = g[j + 1].norm();
+            residual_history.push(estimate / b_norm);
+            trace_event!(iteration = iterations, residual = estimate / b_norm, "GMRES step");
+            let scale = h[j].iter().map(|v| v.norm()).fold(0.0, f64::max).max(1.0);
+            if estimate <= tol * b_norm || h_next <= 1e-14 * scale {
+                break;
+            }
+            basis.push(w);
+        }
+        
+        // Back substitution with the triangularized Hessenberg matrix
+        let k = h.len();
+        let mut y = vec![Complex64::new(0.0, 0.0); k];
+        for i in (0..k).rev() {
+            let tail: Complex64 = (i + 1..k).map(|j| h[j][i] * y[j]).sum();
+            y[i] = (g[i] - tail) / h[i][i];
+        }
+        let mut update = Vec::new();
+        for (yi, q) in y.iter().zip(&basis) {
+            linalg::axpy(&mut update, *yi, q);
+        }
+        linalg::axpy(&mut x, Complex64::new(1.0, 0.0), &precondition(preconditioner, &update));
+        
+        r = b.to_vec();
+        linalg::axpy(&mut r, Complex64::new(-1.0, 0.0), &op.matvec(&x));
+        restarts += 1;
+    }
+}
+
+/// Stopping rule of the conjugate gradient method
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CgStopping {
+    /// Stop when ‖b − Ax‖ / ‖b‖ ≤ tol
+    Residual,
+    /// Stop when the estimated A-norm error ‖x − xₖ‖_A / ‖x‖_A ≤ tol
+    ///
+    /// The error of xₖ is estimated from the `delay` iterations that follow it
+    /// (Hestenes–Stiefel), which makes it a lower bound that tightens as `delay`
+    /// grows; a delay of 0 is treated as 1.
+    EnergyNorm { delay: usize },
+}
+
+/// Result of the conjugate gradient method
+#[derive(Debug, Clone)]
+pub struct CgResult {
+    pub solution: Vec<Complex64>,
+    /// Relative residual after every iteration
+    pub residual_history: Vec<f64>,
+    /// (k, estimated relative A-norm error of the k-th iterate); filled in with a
+    /// lag of `delay` iterations under [`CgStopping::EnergyNorm`]
+    pub energy_error_history: Vec<(usize, f64)>,
+    pub iterations: usize,
+    pub converged: bool,
+}
+
+/// Conjugate gradients for A x = b with A self-adjoint positive definite
+///
+/// With a preconditioner (which must itself be self-adjoint positive definite) this
+/// is preconditioned CG; the residual history is always that of the original system.
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all, fields(tol, max_iter)))]
+pub fn cg<M: MatVec + ?Sized>(
+    op: &M,
+    b: &[Complex64],
+    tol: f64,
+    max_iter: usize,
+    stopping: CgStopping,
+    preconditioner: Option<&dyn Preconditioner>,
+) -> CgResult {
+    let b_norm = linalg::norm(b);
+    let mut result = CgResult {
+        solution: Vec::new(),
+        residual_history: Vec::new(),
+        energy_error_history: Vec::new(),
+        iterations: 0,
+        converged: b_norm == 0.0,
+    };
+    if result.converged {
+        return result;
+    }
+    
+    let mut r = b.to_vec();
+    let mut p = precondition(preconditioner, &r);
+    let mut rho = linalg::dot(&r, &p).re;
+    // α_i r_iᴴz_i per iteration; their tail sums are the squared A-norm errors
+    let mut energy_terms: Vec<f64> = Vec::new();
+    while result.iterations < max_iter {
+        let q = op.matvec(&p);
+        let alpha = rho / linalg::dot(&p, &q).re;
+        linalg::axpy(&mut result.solution, Complex64::new(alpha, 0.0), &p);
+        linalg::axpy(&mut r, Complex64::new(-alpha, 0.0), &q);
+        energy_terms.push(alpha * rho);
+        result.iterations += 1;
+        let z = precondition(preconditioner, &r);
+        let rho_next = linalg::dot(&r, &z).re;
+        let relative_residual = linalg::norm(&r) / b_norm;
+        result.residual_history.push(relative_residual);
+        trace_event!(iteration = result.iterations, residual = relative_residual, "CG step");
+        
+        let done = match stopping {
+            CgStopping::Residual => relative_residual <= tol,
+            CgStopping::EnergyNorm { delay } => {
+                let k = result.iterations;
+                let delay = delay.max(1);
+                if k > delay {
+                    // ‖x‖_A² ≈ Re bᴴxₖ, and ‖x − x_{k−d}‖_A² ≈ Σ_{i=k−d}^{k−1} αᵢ rᵢᴴzᵢ
+                    let solution_energy = linalg::dot(b, &result.solution).re.max(f64::MIN_POSITIVE);
+                    let error = energy_terms[k - delay..].iter().sum::<f64>();
+                    let estimate = (error / solution_energy).sqrt();
+                    result.energy_error_history.push((k - delay, estimate));
+                    estimate <= tol
+                } else {
+                    false
+                }
+            }
+        };
+        if done || rho_next == 0.0 {
+            result.converged = true;
+            break;
+        }
+        let beta = rho_next / rho;
+        rho = rho_next;
+        let mut next = z;
+        linalg::axpy(&mut next, Complex64::new(beta, 0.0), &p);
+        p = next;
+    }
+    debug_event!(iterations = result.iterations, converged = result.converged, "CG finished");
+    result
+}
+
+/// MINRES for A x = b with A self-adjoint, possibly indefinite
+///
+/// Minimizes ‖b − Ax‖ over the Krylov space built by the Lanczos recurrence, with
+/// Givens rotations on the tridiagonal matrix (Paige & Saunders, 1975).
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all, fields(tol, max_iter)))]
+pub fn minres<M: MatVec + ?Sized>(op: &M, b: &[Complex64], tol: f64, max_iter: usize) -> IterativeSolveResult {
+    let zero = Complex64::new(0.0, 0.0);
+    let b_norm = linalg::norm(b);
+    let mut result = IterativeSolveResult {
+        solution: Vec::new(),
+        residual_history: Vec::new(),
+        iterations: 0,
+        restarts: 0,
+        converged: b_norm == 0.0,
+    };
+    if result.converged {
+        return result;
+    }
+    
+    let mut v_prev: Vec<Complex64> = Vec::new();
+    let mut v = b.to_vec();
+    linalg::normalize(&mut v);
+    let mut beta = 0.0;
+    let mut rotations: [(f64, Complex64); 2] = [(1.0, zero); 2];
+    let mut w_prev: Vec<Complex64> = Vec::new();
+    let mut w_prev2: Vec<Complex64> = Vec::new();
+    let mut g = Complex64::new(b_norm, 0.0);
+    while result.iterations < max_iter {
+        // Lanczos step: A v = β v_prev + α v + β_next v_next
+        let mut p = op.matvec(&v);
+        let alpha = linalg::dot(&v, &p).re;
+        linalg::axpy(&mut p, Complex64::new(-alpha, 0.0), &v);
+        linalg::axpy(&mut p, Complex64::new(-beta, 0.0), &v_prev);
+        let beta_next = linalg::normalize(&mut p);
+        
+        // Column k of the tridiagonal matrix, rotated by the two previous rotations
+        let mut column = [zero, Complex64::new(beta, 0.0), Complex64::new(alpha, 0.0), Complex64::new(beta_next, 0.0)];
+        for (offset, &(c, s)) in rotations.iter().enumerate() {
+            let (x, y) = (column[offset], column[offset + 1]);
+            column[offset] = x * c + s * y;
+            column[offset + 1] = -s.conj() * x + y * c;
+        }
+        let (c, s) = linalg::givens(column[2], column[3]);
+        column[2] = column[2] * c + s * column[3];
+        rotations = [rotations[1], (c, s)];
+        
+        // New search direction and solution update
+        let mut w = v.clone();
+        linalg::axpy(&mut w, -column[1], &w_prev);
+        linalg::axpy(&mut w, -column[0], &w_prev2);
+        for value in w.iter_mut() {
+            *value /= column[2];
+        }
+        linalg::axpy(&mut result.solution, g * c, &w);
+        g = -s.conj() * g;
+        result.iterations += 1;
+        
+        let relative_residual = g.norm() / b_norm;
+        result.residual_history.push(relative_residual);
+        trace_event!(iteration = result.iterations, residual = relative_residual, "MINRES step");
+        if relative_residual <= tol || beta_next == 0.0 {
+            result.converged = true;
+            break;
+        }
+        w_prev2 = std::mem::replace(&mut w_prev, w);
+        v_prev = std::mem::replace(&mut v, p);
+        beta = beta_next;
+    }
+    debug_event!(iterations = result.iterations, converged = result.converged, "MINRES finished");
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::iqr::create_diagonal_operator;
+    use crate::test_fixtures::{real, tridiagonal, tridiagonal_solution};
+    use approx::assert_abs_diff_eq;
+
+    #[test]
+    fn gmres_converges_for_a_tridiagonal_system() {
+        let a = tridiagonal();
+        let result = gmres(&a, &[real(1.0)], 20, 1e-12, 10, None);
+        assert!(result.converged);
+        assert!(*result.residual_history.last().unwrap() <= 1e-12);
+        for k in 0..8 {
+            assert_abs_diff_eq!(result.solution[k].re, tridiagonal_solution(k), epsilon = 1e-10);
+        }
+    }
+
+    #[test]
+    fn cg_converges_for_a_positive_definite_operator() {
+        let a = tridiagonal();
+        let result = cg(&a, &[real(1.0)], 1e-12, 100, CgStopping::Residual, None);
+        assert!(result.converged);
+        // κ ≤ 5, so the residual falls at least like ((√5 − 1)/(√5 + 1))ᵏ
+        assert!(result.iterations <= 40);
+        for k in 0..8 {
+            assert_abs_diff_eq!(result.solution[k].re, tridiagonal_solution(k), epsilon = 1e-10);
+        }
+    }
+
+    #[test]
+    fn minres_converges_for_an_indefinite_operator() {
+        let a = create_diagonal_operator(|i| real(if i % 2 == 0 { 2.0 } else { -3.0 }));
+        let b: Vec<Complex64> = (1..=6).map(|k| real(k as f64)).collect();
+        let result = minres(&a, &b, 1e-12, 50);
+        assert!(result.converged);
+        for (k, value) in result.solution.iter().enumerate() {
+            let d = if k % 2 == 0 { 2.0 } else { -3.0 };
+            assert_abs_diff_eq!(value.re, (k + 1) as f64 / d, epsilon = 1e-10);
+        }
+    }
+
+    #[test]
+    fn preconditioned_gmres_converges() {
+        let a = tridiagonal();
+        let jacobi = Jacobi::new(&a);
+        let ilu = BandedIlu::full_band(&a).unwrap();
+        let preconditioners: [&dyn Preconditioner; 2] = [&jacobi, &ilu];
+        for preconditioner in preconditioners {
+            let result = gmres(&a, &[real(1.0)], 20, 1e-12, 10, Some(preconditioner));
+            assert!(result.converged);
+            for k in 0..8 {
+                assert_abs_diff_eq!(result.solution[k].re, tridiagonal_solution(k), epsilon = 1e-10);
+            }
+        }
+    }
+
+    #[test]
+    fn ilu_shifts_a_zero_leading_pivot() {
+        // tridiag(1, 3, 1) with A[0, 0] = 0 has no LU without pivoting
+        let a = crate::banded_operator! { -1 => |_| 1.0, 0 => |i| if i == 0 { 0.0 } else { 3.0 }, 1 => |_| 1.0 };
+        let ilu = BandedIlu::full_band(&a).unwrap();
+        let z = ilu.apply(&[real(1.0), real(2.0), real(3.0)]);
+        assert!(z.iter().all(|v| v.is_finite()));
+        let result = gmres(&a, &[real(1.0)], 20, 1e-10, 10, Some(&ilu));
+        assert!(result.converged);
+        assert!(result.solution.iter().all(|v| v.is_finite()));
+        let mut residual = a.matvec(&result.solution);
+        residual[0] -= real(1.0);
+        assert!(linalg::norm(&residual) <= 1e-9);
+    }
+
+    #[test]
+    fn cg_energy_estimate_is_labelled_with_its_iterate() {
+        // A = diag(1, 2, 3, ...) and b = (1, 1, 1): CG is exact after three steps, so
+        // with delay 2 the estimate for x₁ = (1/2, 1/2, 1/2) is its exact error
+        let a = create_diagonal_operator(|i| real((i + 1) as f64));
+        let b = [real(1.0); 3];
+        let result = cg(&a, &b, 0.0, 3, CgStopping::EnergyNorm { delay: 2 }, None);
+        let error_energy: f64 = 0.25 + 3.0 / 36.0;
+        let solution_energy = 1.0 + 2.0 / 4.0 + 3.0 / 9.0;
+        assert_eq!(result.energy_error_history.len(), 1);
+        let (k, estimate) = result.energy_error_history[0];
+        assert_eq!(k, 1);
+        assert_abs_diff_eq!(estimate, (error_energy / solution_energy).sqrt(), epsilon = 1e-12);
+    }
+}
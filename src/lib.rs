@@ -2,23 +2,113 @@
 //!
 //! This library provides lazy infinite arrays designed to work with numerical computing.
 //! It is inspired by and converted from InfiniteArrays.jl.
+//!
+//! Without the default `std` feature the crate is `no_std` + `alloc`: infinities,
+//! ranges, the lazy arrays, broadcasting and caches remain, while the spectral and
+//! linear-algebra modules (which need `ndarray` and `nalgebra`) are left out.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+#[cfg(feature = "std")]
+#[macro_use]
+mod instrument;
+mod macros;
 
 pub mod infinity;
 pub mod ranges;
 pub mod arrays;
+#[cfg(feature = "approx")]
+pub mod approx_eq;
+#[cfg(feature = "async")]
+pub mod async_arrays;
 pub mod broadcasting;
 pub mod cache;
+#[cfg(feature = "std")]
+pub mod contour;
+#[cfg(feature = "serde")]
+pub mod descriptor;
 pub mod diagonal;
+pub mod display;
+pub mod dims;
+#[cfg(feature = "std")]
+pub mod eigensolvers;
+pub mod error;
+#[cfg(feature = "export")]
+pub mod export;
+#[cfg(feature = "std")]
+pub mod factorizations;
+#[cfg(feature = "gpu")]
+pub mod gpu;
+#[cfg(feature = "std")]
 pub mod iqr;
+#[cfg(feature = "std")]
+pub mod iterative;
+#[cfg(feature = "std")]
+pub mod matfun;
+#[cfg(feature = "std")]
+pub mod operators;
+#[cfg(feature = "plotters")]
+pub mod plotting;
+#[cfg(feature = "std")]
+pub mod pseudospectra;
+#[cfg(feature = "std")]
+pub mod spectra;
+#[cfg(feature = "stream")]
+pub mod stream;
+#[cfg(feature = "std")]
+pub mod toeplitz;
+#[cfg(feature = "proptest")]
+pub mod testing;
+#[cfg(feature = "std")]
+pub mod trace;
+#[cfg(feature = "std")]
+pub mod transfer;
 pub mod utils;
 
+#[cfg(feature = "std")]
+mod linalg;
+mod sync;
+#[cfg(all(test, feature = "std"))]
+mod test_fixtures;
+
+#[cfg(all(feature = "rayon", target_arch = "wasm32", not(target_feature = "atomics")))]
+compile_error!("the `rayon` feature needs threads, which single-threaded wasm32 does not have");
+
+#[cfg(all(feature = "gpu", target_arch = "wasm32"))]
+compile_error!("the `gpu` feature needs 64-bit float shaders, which WebGPU does not have");
+
 // Re-export main types and functions
-pub use infinity::Infinity;
-pub use ranges::{OneToInf, InfUnitRange, InfStepRange};
-pub use arrays::{InfiniteArray, Ones, Zeros, Fill};
+pub use infinity::{Infinity, MaybeInfinite, SignedInfinity, Size};
+pub use ranges::{OneToInf, InfUnitRange, InfStepRange, InfRangeIter, SignedRangeIter, NegInfRange, FullZRange, RationalStepRange};
+pub use arrays::{InfiniteArray, ArrayAxis, Ones, Zeros, Fill, PaddedArray, IndexedArray, Vcat, Cyclic};
 pub use broadcasting::BroadcastArray;
 pub use cache::{cache, CachedArray};
 pub use diagonal::InfiniteDiagonal;
+pub use dims::{Dims, Finite, Inf};
+pub use error::{InfiniteArraysError, InfiniteSizeError, RangeError};
+#[cfg(feature = "serde")]
+pub use error::DescriptorError;
+#[cfg(feature = "std")]
+pub use operators::LinearOperator;
+#[cfg(feature = "std")]
+pub use toeplitz::InfiniteToeplitz;
+
+// Paths used by the exported macros, which must also work in `no_std` crates
+#[doc(hidden)]
+pub mod __private {
+    pub use alloc::sync::Arc;
+    pub use alloc::vec;
+}
+
+/// The vector reductions measured by `benches/kernels.rs`, exported only with the
+/// `bench` feature and not covered by semver
+#[cfg(feature = "bench")]
+#[doc(hidden)]
+pub mod kernels {
+    pub use crate::linalg::{dot, max_norm_sqr, norm_sqr};
+}
 
 /// Infinity constant for specifying infinite dimensions
 pub const INFINITY: Infinity = Infinity;
@@ -0,0 +1,384 @@
+//! Dense linear algebra helpers shared by the spectral routines.
+//!
+//! Truncations are assembled as `ndarray` matrices; the factorizations come from
+//! `nalgebra`, so this module converts between the two.
+
+use nalgebra::DMatrix;
+use ndarray::Array2;
+use num_complex::Complex64;
+
+/// Convert an `ndarray` matrix to an `nalgebra` matrix
+pub(crate) fn to_dmatrix(a: &Array2<Complex64>) -> DMatrix<Complex64> {
+    DMatrix::from_fn(a.nrows(), a.ncols(), |i, j| a[(i, j)])
+}
+
+/// Convert an `nalgebra` matrix to an `ndarray` matrix
+pub(crate) fn from_dmatrix(m: &DMatrix<Complex64>) -> Array2<Complex64> {
+    Array2::from_shape_fn((m.nrows(), m.ncols()), |(i, j)| m[(i, j)])
+}
+
+/// 2-norm condition number of a matrix (infinite if singular)
+pub(crate) fn condition_number(m: &DMatrix<Complex64>) -> f64 {
+    if m.is_empty() {
+        return 1.0;
+    }
+    let singular_values = m.singular_values();
+    let smallest = singular_values.min();
+    if smallest == 0.0 {
+        f64::INFINITY
+    } else {
+        singular_values.max() / smallest
+    }
+}
+
+/// QR sweeps per eigenvalue after which a Schur decomposition is restarted, as LAPACK
+const SCHUR_SWEEPS_PER_EIGENVALUE: usize = 30;
+
+/// Directions of the shifts tried when the Schur iteration stalls
+const SCHUR_RESTART_SHIFTS: [(f64, f64); 3] = [(0.37, 0.21), (-0.29, 0.43), (0.18, -0.52)];
+
+/// Eigenvalues of a general square matrix, read off the complex Schur form
+///
+/// nalgebra's QR iteration has no exceptional shifts and can cycle forever, e.g. on
+/// matrices whose spectrum is symmetric about 0. A stalled decomposition is restarted
+/// on A + cI for a shift c of the order of ‖A‖, which breaks the symmetry, and c is
+/// subtracted from the eigenvalues found.
+pub(crate) fn eigenvalues(a: &Array2<Complex64>) -> Vec<Complex64> {
+    let n = a.nrows();
+    if n == 0 {
+        return Vec::new();
+    }
+    let max_sweeps = SCHUR_SWEEPS_PER_EIGENVALUE * n.max(4);
+    let matrix = to_dmatrix(a);
+    let scale = a.iter().map(|v| v.norm()).fold(0.0, f64::max);
+    let shifts = std::iter::once(Complex64::new(0.0, 0.0))
+        .chain(SCHUR_RESTART_SHIFTS.iter().map(|&(re, im)| Complex64::new(re, im) * scale));
+    for shift in shifts {
+        let mut shifted = matrix.clone();
+        for i in 0..n {
+            shifted[(i, i)] += shift;
+        }
+        if let Some(schur) = nalgebra::Schur::try_new(shifted, f64::EPSILON, max_sweeps) {
+            let (_, t) = schur.unpack();
+            return (0..n).map(|i| t[(i, i)] - shift).collect();
+        }
+    }
+    let (_, t) = nalgebra::Schur::new(matrix).unpack();
+    (0..n).map(|i| t[(i, i)]).collect()
+}
+
+/// Minimum-cost assignment of rows to columns (Kuhn–Munkres, O(n²m))
+///
+/// Returns for every row the column assigned to it; when there are more rows than
+/// columns the surplus rows are left unassigned.
+pub(crate) fn hungarian(cost: &Array2<f64>) -> Vec<Option<usize>> {
+    let (rows, cols) = cost.dim();
+    if rows > cols {
+        let transposed = hungarian(&cost.t().to_owned());
+        let mut assignment = vec![None; rows];
+        for (col, row) in transposed.into_iter().enumerate() {
+            if let Some(row) = row {
+                assignment[row] = Some(col);
+            }
+        }
+        return assignment;
+    }
+    
+    // Potentials u (rows) and v (columns), 1-based with a virtual column 0
+    let mut u = vec![0.0; rows + 1];
+    let mut v = vec![0.0; cols + 1];
+    let mut owner = vec![0usize; cols + 1];
+    let mut way = vec![0usize; cols + 1];
+    for row in 1..=rows {
+        owner[0] = row;
+        let mut col0 = 0;
+        let mut min_slack = vec![f64::INFINITY; cols + 1];
+        let mut used = vec![false; cols + 1];
+        loop {
+            used[col0] = true;
+            let row0 = owner[col0];
+            let mut delta = f64::INFINITY;
+            let mut col1 = 0;
+            for col in 1..=cols {
+                if used[col] {
+                    continue;
+                }
+                let slack = cost[(row0 - 1, col - 1)] - u[row0] - v[col];
+                if slack < min_slack[col] {
+                    min_slack[col] = slack;
+                    way[col] = col0;
+                }
+                if min_slack[col] < delta {
+                    delta = min_slack[col];
+                    col1 = col;
+                }
+            }
+            for col in 0..=cols {
+                if used[col] {
+                    u[owner[col]] += delta;
+                    v[col] -= delta;
+                } else {
+                    min_slack[col] -= delta;
+                }
+            }
+            col0 = col1;
+            if owner[col0] == 0 {
+                break;
+            }
+        }
+        while col0 != 0 {
+            let col1 = way[col0];
+            owner[col0] = owner[col1];
+            col0 = col1;
+        }
+    }
+    
+    let mut assignment = vec![None; rows];
+    for col in 1..=cols {
+        if owner[col] != 0 {
+            assignment[owner[col] - 1] = Some(col - 1);
+        }
+    }
+    assignment
+}
+
+/// Sort eigenvalues by decreasing magnitude, the order used throughout the crate
+pub(crate) fn sort_by_magnitude(values: &mut [Complex64]) {
+    values.sort_by(|a, b| b.norm().total_cmp(&a.norm()));
+}
+
+/// Complex Givens rotation (c, s) mapping (a, b) to (ρ, 0)
+///
+/// The rotation acts on a pair of rows (x, y) as (c·x + s·y, −s̄·x + c·y).
+pub(crate) fn givens(a: Complex64, b: Complex64) -> (f64, Complex64) {
+    if b.norm() == 0.0 {
+        return (1.0, Complex64::new(0.0, 0.0));
+    }
+    if a.norm() == 0.0 {
+        return (0.0, Complex64::new(1.0, 0.0));
+    }
+    let rho = (a.norm_sqr() + b.norm_sqr()).sqrt();
+    (a.norm() / rho, (a / a.norm()) * b.conj() / rho)
+}
+
+/// R factor of the QR factorization of the upper Hessenberg matrix `H − zI`
+///
+/// One Givens rotation per column, so O(n²) work instead of O(n³).
+pub(crate) fn shifted_hessenberg_r(h: &DMatrix<Complex64>, z: Complex64) -> DMatrix<Complex64> {
+    let n = h.nrows();
+    let mut r = h.clone();
+    for i in 0..n {
+        r[(i, i)] -= z;
+    }
+    for k in 0..n.saturating_sub(1) {
+        let b = r[(k + 1, k)];
+        if b.norm() == 0.0 {
+            continue;
+        }
+        let (c, s) = givens(r[(k, k)], b);
+        for j in k..n {
+            let x = r[(k, j)];
+            let y = r[(k + 1, j)];
+            r[(k, j)] = x * c + s * y;
+            r[(k + 1, j)] = -s.conj() * x + y * c;
+        }
+    }
+    r
+}
+
+/// Smallest singular value of `R` (square, upper triangular) by inverse iteration on `R*R`
+///
+/// Each step costs two triangular solves. Returns 0 when `R` is exactly singular.
+pub(crate) fn triangular_sigma_min(r: &DMatrix<Complex64>, tol: f64, max_iter: usize) -> f64 {
+    let n = r.nrows();
+    if n == 0 {
+        return 0.0;
+    }
+    if (0..n).any(|i| r[(i, i)].norm() == 0.0) {
+        return 0.0;
+    }
+    let r_adjoint = r.adjoint();
+    let mut x = nalgebra::DVector::from_element(n, Complex64::new(1.0 / (n as f64).sqrt(), 0.0));
+    let mut sigma = f64::INFINITY;
+    for _ in 0..max_iter.max(1) {
+        let y = r_adjoint.solve_lower_triangular(&x).unwrap_or_else(|| x.clone());
+        let w = r.solve_upper_triangular(&y).unwrap_or_else(|| y.clone());
+        let growth = w.norm();
+        if !growth.is_finite() || growth == 0.0 {
+            return 0.0;
+        }
+        let next = 1.0 / growth.sqrt();
+        x = w / Complex64::new(growth, 0.0);
+        let converged = (next - sigma).abs() <= tol * next;
+        sigma = next;
+        if converged {
+            break;
+        }
+    }
+    sigma
+}
+
+/// Solve the banded system `a x = b` by Gaussian elimination with partial pivoting
+///
+/// Row interchanges widen the upper band to `lower + upper`, so the elimination only
+/// touches that band and costs O(n · lower · (lower + upper)). Pass `n` for either
+/// bandwidth to solve a dense system. Returns `None` if `a` is singular.
+pub(crate) fn banded_solve(
+    mut a: Array2<Complex64>,
+    mut b: Vec<Complex64>,
+    lower: usize,
+    upper: usize,
+) -> Option<Vec<Complex64>> {
+    let n = a.nrows();
+    let width = lower + upper;
+    for k in 0..n {
+        let last_row = (k + lower + 1).min(n);
+        let last_col = (k + width + 1).min(n);
+        let pivot = (k..last_row).max_by(|&p, &q| a[(p, k)].norm().total_cmp(&a[(q, k)].norm()))?;
+        if a[(pivot, k)].norm() == 0.0 {
+            return None;
+        }
+        if pivot != k {
+            for j in k..last_col {
+                a.swap((k, j), (pivot, j));
+            }
+            b.swap(k, pivot);
+        }
+        for i in k + 1..last_row {
+            let factor = a[(i, k)] / a[(k, k)];
+            if factor.norm() == 0.0 {
+                continue;
+            }
+            for j in k..last_col {
+                let update = factor * a[(k, j)];
+                a[(i, j)] -= update;
+            }
+            let update = factor * b[k];
+            b[i] -= update;
+        }
+    }
+    for k in (0..n).rev() {
+        let last_col = (k + width + 1).min(n);
+        let tail: Complex64 = (k + 1..last_col).map(|j| a[(k, j)] * b[j]).sum();
+        b[k] = (b[k] - tail) / a[(k, k)];
+    }
+    Some(b)
+}
+
+/// Width of the unrolled reductions below
+///
+/// Floating-point sums cannot be reordered by the compiler, so a plain fold is a
+/// serial dependency chain. Keeping `LANES` independent accumulators lets the loop
+/// body run in vector registers.
+const LANES: usize = 4;
+
+/// Squared Euclidean norm of a vector
+pub fn norm_sqr(x: &[Complex64]) -> f64 {
+    let mut acc = [0.0; LANES];
+    let chunks = x.chunks_exact(LANES);
+    let tail: f64 = chunks.remainder().iter().map(|v| v.norm_sqr()).sum();
+    for chunk in chunks {
+        for (a, v) in acc.iter_mut().zip(chunk) {
+            *a += v.re * v.re + v.im * v.im;
+        }
+    }
+    acc.iter().sum::<f64>() + tail
+}
+
+/// Largest squared modulus of the entries of a vector (0 for an empty vector)
+pub fn max_norm_sqr(x: &[Complex64]) -> f64 {
+    let mut acc = [0.0f64; LANES];
+    let chunks = x.chunks_exact(LANES);
+    let tail = chunks.remainder().iter().map(|v| v.norm_sqr()).fold(0.0, f64::max);
+    for chunk in chunks {
+        for (a, v) in acc.iter_mut().zip(chunk) {
+            let value = v.re * v.re + v.im * v.im;
+            *a = if value > *a { value } else { *a };
+        }
+    }
+    acc.iter().copied().fold(tail, f64::max)
+}
+
+/// Euclidean norm of a vector
+pub(crate) fn norm(x: &[Complex64]) -> f64 {
+    norm_sqr(x).sqrt()
+}
+
+/// Euclidean distance between two vectors, the shorter one padded with zeros
+pub(crate) fn padded_distance(x: &[Complex64], y: &[Complex64]) -> f64 {
+    let zero = Complex64::new(0.0, 0.0);
+    (0..x.len().max(y.len()))
+        .map(|i| (x.get(i).copied().unwrap_or(zero) - y.get(i).copied().unwrap_or(zero)).norm_sqr())
+        .sum::<f64>()
+        .sqrt()
+}
+
+/// Inner product xᴴy of two vectors, the shorter one padded with zeros
+pub fn dot(x: &[Complex64], y: &[Complex64]) -> Complex64 {
+    let len = x.len().min(y.len());
+    let (mut re, mut im) = ([0.0; LANES], [0.0; LANES]);
+    let (x_chunks, y_chunks) = (x[..len].chunks_exact(LANES), y[..len].chunks_exact(LANES));
+    let tail: Complex64 = x_chunks.remainder().iter().zip(y_chunks.remainder()).map(|(a, b)| a.conj() * b).sum();
+    for (xc, yc) in x_chunks.zip(y_chunks) {
+        for lane in 0..LANES {
+            let (a, b) = (xc[lane], yc[lane]);
+            re[lane] += a.re * b.re + a.im * b.im;
+            im[lane] += a.re * b.im - a.im * b.re;
+        }
+    }
+    Complex64::new(re.iter().sum(), im.iter().sum()) + tail
+}
+
+/// y ← y + αx, growing y if x is longer
+pub(crate) fn axpy(y: &mut Vec<Complex64>, alpha: Complex64, x: &[Complex64]) {
+    if y.len() < x.len() {
+        y.resize(x.len(), Complex64::new(0.0, 0.0));
+    }
+    for (yi, xi) in y.iter_mut().zip(x) {
+        *yi += alpha * xi;
+    }
+}
+
+/// Scale a vector to unit norm, returning the original norm
+pub(crate) fn normalize(x: &mut [Complex64]) -> f64 {
+    let nrm = norm(x);
+    if nrm > 0.0 {
+        for v in x.iter_mut() {
+            *v /= nrm;
+        }
+    }
+    nrm
+}
+
+/// Eigenvalues and unit eigenvectors (as columns) of a general square matrix
+///
+/// The eigenvectors come from back substitution on the complex Schur form.
+pub(crate) fn eigenpairs(a: &Array2<Complex64>) -> (Vec<Complex64>, Array2<Complex64>) {
+    let n = a.nrows();
+    if n == 0 {
+        return (Vec::new(), Array2::zeros((0, 0)));
+    }
+    let (q, t) = nalgebra::Schur::new(to_dmatrix(a)).unpack();
+    let values: Vec<Complex64> = (0..n).map(|i| t[(i, i)]).collect();
+    let scale = values.iter().map(|v| v.norm()).fold(f64::MIN_POSITIVE, f64::max);
+    
+    let mut vectors = Array2::<Complex64>::zeros((n, n));
+    for k in 0..n {
+        let mut v = vec![Complex64::new(0.0, 0.0); n];
+        v[k] = Complex64::new(1.0, 0.0);
+        for j in (0..k).rev() {
+            let sum: Complex64 = (j + 1..=k).map(|l| t[(j, l)] * v[l]).sum();
+            let mut denom = t[(j, j)] - values[k];
+            if denom.norm() < f64::EPSILON * scale {
+                denom = Complex64::new(f64::EPSILON * scale, 0.0);
+            }
+            v[j] = -sum / denom;
+        }
+        let mut x: Vec<Complex64> = (0..n).map(|i| (0..=k).map(|l| q[(i, l)] * v[l]).sum()).collect();
+        normalize(&mut x);
+        for i in 0..n {
+            vectors[(i, k)] = x[i];
+        }
+    }
+    (values, vectors)
+}
@@ -0,0 +1,74 @@
+//! Construction macros.
+
+/// Infinite array literal
+///
+/// - `inf_array![1.0, 2.0, 3.0; tail = 0.5]`: the listed entries followed by 0.5
+///   forever, a [`Vcat`](crate::Vcat) with a [`Fill`](crate::Fill) tail
+/// - `inf_array![1.0, 2.0, 3.0; cycle]`: the listed entries repeated forever, a
+///   [`Cyclic`](crate::Cyclic)
+/// - `inf_array![1.0, 2.0, 3.0]`: the listed entries followed by zeros, a
+///   [`PaddedArray`](crate::PaddedArray)
+/// - `inf_array![|i| (i as f64).sin()]`: entry i computed by the closure, a
+///   [`BroadcastArray`](crate::BroadcastArray); the closure must be written out
+///   (`move` and a parameter type are allowed), as a closure in a variable would be
+///   taken for a single listed entry
+#[macro_export]
+macro_rules! inf_array {
+    ($(move)? |$i:pat_param| $body:expr) => {
+        $crate::inf_array!(@broadcast move |$i: usize| $body)
+    };
+    ($(move)? |$i:ident: $t:ty| $body:expr) => {
+        $crate::inf_array!(@broadcast move |$i: $t| $body)
+    };
+    (@broadcast $f:expr) => {
+        $crate::BroadcastArray::new($f, $crate::arrays::Shape::OneD($crate::Size::Infinite))
+    };
+    ($($x:expr),+ $(,)?; tail = $tail:expr) => {
+        $crate::Vcat::new(
+            $crate::__private::vec![$($x),+],
+            $crate::__private::Arc::new($crate::Fill::new($tail, None)),
+        )
+    };
+    ($($x:expr),+ $(,)?; cycle) => {
+        $crate::Cyclic::new($crate::__private::vec![$($x),+])
+    };
+    ($($x:expr),+ $(,)?) => {
+        $crate::PaddedArray::new($crate::__private::vec![$($x),+])
+    };
+}
+
+/// Banded operator written as a stencil of diagonals
+///
+/// `banded_operator!{ -1 => |_| 1.0, 0 => |_| -2.0, 1 => |_| 1.0 }` is the
+/// second-difference operator. Each offset maps to a closure giving the entries
+/// along that diagonal (real or complex), indexed by the smaller of row and column
+/// as described at [`InfiniteOperator::from_diagonals`]; the bandwidths follow from
+/// the outermost offsets.
+///
+/// [`InfiniteOperator::from_diagonals`]: crate::iqr::InfiniteOperator::from_diagonals
+#[cfg(feature = "std")]
+#[macro_export]
+macro_rules! banded_operator {
+    ($($offset:expr => $values:expr),+ $(,)?) => {
+        $crate::iqr::InfiniteOperator::from_diagonals(::std::vec![
+            $(($offset, $crate::operators::diagonal_fn($values))),+
+        ])
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::InfiniteArray;
+
+    #[test]
+    fn inf_array_builds_each_structured_array() {
+        let tail = inf_array![1.0, 2.0; tail = 0.5];
+        assert_eq!([tail.get(0), tail.get(1), tail.get(2), tail.get(1000)], [1.0, 2.0, 0.5, 0.5]);
+        let cyclic = inf_array![1.0, -1.0, 3.0; cycle];
+        assert_eq!(cyclic.get(7), -1.0);
+        let padded = inf_array![4.0, 5.0];
+        assert_eq!([padded.get(1), padded.get(2)], [5.0, 0.0]);
+        let squares = inf_array![|i| (i * i) as f64];
+        assert_eq!(squares.get(12), 144.0);
+    }
+}
@@ -0,0 +1,260 @@
+//! Functions of infinite operators.
+//!
+//! Polynomials are built lazily from operator products, so banded operators stay
+//! banded (with bandwidths growing linearly in the degree). The action f(A)v on a
+//! finitely supported v is computed matrix-free, so its support grows only as far
+//! as the operator spreads it.
+
+use crate::arrays::PaddedArray;
+use crate::cache::CachedArray;
+use crate::contour::{Contour, QUADRATURE_NODES};
+use crate::error::InfiniteArraysError;
+use crate::factorizations::adaptive_solve;
+use crate::iqr::{create_diagonal_operator, InfiniteOperator, MatVec};
+use crate::linalg;
+use nalgebra::DMatrix;
+use num_complex::Complex64;
+use std::sync::Arc;
+
+/// Largest Krylov space used per time step of [`expm_multiply`]
+const MAX_KRYLOV_DIM: usize = 40;
+
+/// Largest number of quadrature nodes used by [`matfun_apply`]
+const MAX_QUADRATURE_NODES: usize = 1024;
+
+/// Lazy operator polynomial p(A) = Σₖ coeffs[k] Aᵏ
+///
+/// Evaluated with Horner's rule, p(A) = c₀ + A(c₁ + A(c₂ + ⋯)); each intermediate
+/// operator caches its entries.
+pub fn polynomial(operator: &InfiniteOperator, coeffs: &[Complex64]) -> InfiniteOperator {
+    let identity = |c: Complex64| create_diagonal_operator(move |_| c);
+    let Some((&leading, rest)) = coeffs.split_last() else {
+        return identity(Complex64::new(0.0, 0.0));
+    };
+    rest.iter()
+        .rev()
+        .fold(identity(leading), |acc, &c| operator * acc + identity(c))
+}
+
+/// Action f(A)v of an operator function on a finitely supported vector
+#[derive(Debug, Clone)]
+pub struct OperatorAction {
+    /// Leading coefficients of f(A)v; later coefficients are below the tolerance
+    pub coefficients: Vec<Complex64>,
+    /// Estimate of the error in ‖·‖
+    pub error_estimate: f64,
+    /// Number of time steps or quadrature nodes used
+    pub steps: usize,
+}
+
+impl OperatorAction {
+    /// Real part of f(A)v as a cached infinite array
+    pub fn real_part(&self) -> CachedArray {
+        CachedArray::new(Arc::new(PaddedArray::new(self.coefficients.iter().map(|c| c.re).collect())))
+    }
+    
+    /// Imaginary part of f(A)v as a cached infinite array
+    pub fn imag_part(&self) -> CachedArray {
+        CachedArray::new(Arc::new(PaddedArray::new(self.coefficients.iter().map(|c| c.im).collect())))
+    }
+}
+
+/// Compute exp(tA)v with a Krylov method
+///
+/// Each step builds an Arnoldi basis of dimension at most 40 from the current vector
+/// and evaluates exp(τH) on the small Hessenberg matrix; τ is halved (reusing the
+/// basis) until the a posteriori estimate β·h_{m+1,m}·|eₘᵀexp(τH)e₁| is at most
+/// `tol · ‖v‖ · |τ/t|` (Saad, SIAM J. Numer. Anal. 29, 1992). Trailing coefficients
+/// below `tol · ‖v‖` are dropped from the result.
+pub fn expm_multiply<M: MatVec + ?Sized>(op: &M, t: Complex64, v: &[Complex64], tol: f64) -> OperatorAction {
+    let v_norm = linalg::norm(v);
+    let mut w = v.to_vec();
+    let mut remaining = 1.0;
+    let mut error_estimate = 0.0;
+    let mut steps = 0;
+    while remaining > 0.0 && v_norm > 0.0 {
+        let beta = linalg::norm(&w);
+        let (basis, h, h_next) = krylov_basis(op, &w, MAX_KRYLOV_DIM);
+        let m = basis.len();
+        let mut tau = remaining;
+        let (y, error) = loop {
+            let y = (&h * (t * tau)).exp().column(0).into_owned();
+            let error = beta * h_next * y[m - 1].norm();
+            if error <= tol * v_norm * tau || tau < 1e-12 {
+                break (y, error);
+            }
+            tau /= 2.0;
+        };
+        let mut next = Vec::new();
+        for (q, &yi) in basis.iter().zip(y.iter()) {
+            linalg::axpy(&mut next, yi * beta, q);
+        }
+        w = next;
+        error_estimate += error;
+        remaining = if tau >= remaining { 0.0 } else { remaining - tau };
+        steps += 1;
+    }
+    
+    let cutoff = tol * v_norm;
+    while w.last().is_some_and(|c| c.norm() <= cutoff) {
+        w.pop();
+    }
+    OperatorAction {
+        coefficients: w,
+        error_estimate,
+        steps,
+    }
+}
+
+/// Orthonormal Krylov basis of at most `m` vectors with its Hessenberg matrix
+///
+/// Returns the basis, the square Hessenberg matrix and the next subdiagonal entry
+/// h_{m+1,m} (zero after a breakdown, when the Krylov space is invariant).
+fn krylov_basis<M: MatVec + ?Sized>(op: &M, start: &[Complex64], m: usize) -> (Vec<Vec<Complex64>>, DMatrix<Complex64>, f64) {
+    let mut q0 = start.to_vec();
+    linalg::normalize(&mut q0);
+    let mut basis = vec![q0];
+    let mut h = DMatrix::<Complex64>::zeros(m, m);
+    for j in 0..m {
+        let mut w = op.matvec(&basis[j]);
+        for _ in 0..2 {
+            for (i, q) in basis.iter().enumerate() {
+                let hij = linalg::dot(q, &w);
+                h[(i, j)] += hij;
+                linalg::axpy(&mut w, -hij, q);
+            }
+        }
+        let beta = linalg::normalize(&mut w);
+        let scale = (0..=j).map(|i| h[(i, j)].norm()).fold(0.0, f64::max).max(1.0);
+        if beta <= 1e-14 * scale {
+            let size = j + 1;
+            return (basis, h.view((0, 0), (size, size)).into_owned(), 0.0);
+        }
+        if j + 1 == m {
+            return (basis, h, beta);
+        }
+        h[(j + 1, j)] = Complex64::new(beta, 0.0);
+        basis.push(w);
+    }
+    unreachable!("the loop returns at j + 1 == m")
+}
+
+/// Compute f(A)v = (2πi)⁻¹ ∮ f(z)(z − A)⁻¹v dz over a contour
+///
+/// f must be analytic inside and on the contour, which must enclose the part of the
+/// spectrum f is applied to (for fractional powers or the sign function the branch
+/// cut has to stay outside). Each node costs one adaptive QR solve of (A − z)x = v.
+/// The trapezoidal rule is doubled, reusing earlier solves, until it agrees with the
+/// rule on every other node to `tol` (relative). Fails with
+/// [`InfiniteArraysError::NotBanded`] unless A is banded.
+pub fn matfun_apply<F>(
+    operator: &InfiniteOperator,
+    f: F,
+    v: &[Complex64],
+    contour: &Contour,
+    tol: f64,
+) -> Result<OperatorAction, InfiniteArraysError>
+where
+    F: Fn(Complex64) -> Complex64,
+{
+    // term(z) = −f(z)(A − z)⁻¹v, to be weighted by the quadrature
+    let term = |z: Complex64| {
+        let shifted = operator + create_diagonal_operator(move |_| -z);
+        let mut x = adaptive_solve(&shifted, v, tol)?.coefficients;
+        let scale = -f(z);
+        x.iter_mut().for_each(|c| *c *= scale);
+        Ok(x)
+    };
+    
+    let mut nodes = QUADRATURE_NODES;
+    let mut terms: Vec<Vec<Complex64>> = contour
+        .quadrature(nodes)
+        .iter()
+        .map(|&(z, _)| term(z))
+        .collect::<Result<_, InfiniteArraysError>>()?;
+    loop {
+        let rule = contour.quadrature(nodes);
+        let mut full = Vec::new();
+        let mut half = Vec::new();
+        for (k, ((_, weight), x)) in rule.iter().zip(&terms).enumerate() {
+            linalg::axpy(&mut full, *weight, x);
+            if k % 2 == 0 {
+                linalg::axpy(&mut half, *weight * 2.0, x);
+            }
+        }
+        let error_estimate = linalg::padded_distance(&full, &half);
+        if error_estimate <= tol * linalg::norm(&full) || nodes >= MAX_QUADRATURE_NODES {
+            return Ok(OperatorAction {
+                coefficients: full,
+                error_estimate,
+                steps: nodes,
+            });
+        }
+        
+        nodes *= 2;
+        let refined = contour.quadrature(nodes);
+        let mut previous = std::mem::take(&mut terms).into_iter();
+        terms = refined
+            .iter()
+            .enumerate()
+            .map(|(k, &(z, _))| if k % 2 == 0 { Ok(previous.next().unwrap()) } else { term(z) })
+            .collect::<Result<_, _>>()?;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::{real, tridiagonal};
+    use ndarray::Array2;
+
+    #[test]
+    fn polynomial_matches_the_dense_polynomial() {
+        // p(A) = 1 + 2A + A²; (A²)ₙ differs from (Aₙ)² only in the last row and column
+        let a = tridiagonal();
+        let p = polynomial(&a, &[real(1.0), real(2.0), real(1.0)]);
+        assert_eq!(p.bandwidths(), Some((2, 2)));
+        let n = 16;
+        let section = a.get_truncation(n);
+        let dense = Array2::eye(n).mapv(real) + section.mapv(|x| x * 2.0) + section.dot(&section);
+        for i in 0..n - 1 {
+            for j in 0..n - 1 {
+                assert!((p.get(i, j) - dense[(i, j)]).norm() < 1e-12);
+            }
+        }
+        assert_eq!(polynomial(&a, &[]).get(0, 0), real(0.0));
+    }
+
+    #[test]
+    fn expm_multiply_matches_the_diagonal_exponential() {
+        // A = diag(0, −1/2, −1, ...), so exp(tA)v = (v_k e^{−tk/2})
+        let a = create_diagonal_operator(|k| real(-0.5 * k as f64));
+        let v: Vec<Complex64> = (1..=6).map(|k| real(k as f64)).collect();
+        for t in [real(0.7), Complex64::new(0.0, 2.0), real(5.0)] {
+            let action = expm_multiply(&a, t, &v, 1e-12);
+            for (k, &vk) in v.iter().enumerate() {
+                let expected = vk * (t * -0.5 * k as f64).exp();
+                assert!((action.coefficients[k] - expected).norm() < 1e-10 * linalg::norm(&v));
+            }
+            assert!(action.coefficients.len() <= v.len());
+        }
+    }
+
+    #[test]
+    fn matfun_apply_matches_the_eigendecomposition_of_a_section() {
+        // √A e₀ for the tridiagonal A; entries far from the corner of a large section
+        // agree with the infinite operator to rounding
+        let a = tridiagonal();
+        let contour = Contour::Circle { center: real(3.0), radius: 2.5 };
+        let action = matfun_apply(&a, |z| z.sqrt(), &[real(1.0)], &contour, 1e-12).unwrap();
+
+        let eigen = linalg::to_dmatrix(&a.get_truncation(80)).symmetric_eigen();
+        for i in 0..10 {
+            let expected: Complex64 = (0..80)
+                .map(|k| eigen.eigenvectors[(i, k)] * eigen.eigenvalues[k].sqrt() * eigen.eigenvectors[(0, k)].conj())
+                .sum();
+            assert!((action.coefficients[i] - expected).norm() < 1e-9);
+        }
+        assert!(matfun_apply(&InfiniteOperator::new(|_, _| real(1.0)), |z| z, &[real(1.0)], &contour, 1e-12).is_err());
+    }
+}
@@ -0,0 +1,412 @@
+//! Structured operators behind [`InfiniteOperator`].
+//!
+//! An [`InfiniteOperator`] is a cached handle around a [`LinearOperator`], so the
+//! spectral algorithms see the structure of the operator (bandwidths, symmetry)
+//! through dynamic dispatch rather than treating every operator as a dense closure.
+//! The implementors here cover dense closures, banded stencils, diagonals and lazy
+//! sums, products and multiples; [`crate::InfiniteToeplitz`] implements the trait too.
+
+use crate::iqr::InfiniteOperator;
+use num_complex::Complex64;
+use std::sync::Arc;
+
+#[cfg(feature = "rayon")]
+use ndarray::Array2;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+/// Largest number of terms in an adaptively truncated sum
+const MAX_SUM_TERMS: usize = 1 << 20;
+
+/// Consecutive negligible blocks after which an adaptive sum stops
+pub(crate) const SMALL_BLOCKS: usize = 3;
+
+/// A linear operator on l^2(N) given entry by entry
+pub trait LinearOperator: Send + Sync {
+    /// Matrix element at position (i, j)
+    fn get(&self, i: usize, j: usize) -> Complex64;
+
+    /// Lower and upper bandwidths, if the operator is known to be banded
+    fn bandwidths(&self) -> Option<(usize, usize)> {
+        None
+    }
+
+    /// Whether the operator is known to equal its adjoint
+    ///
+    /// Truncations of Hermitian operators are assembled from their upper triangle.
+    fn is_hermitian(&self) -> bool {
+        false
+    }
+
+    /// Multiply by a finitely supported vector
+    ///
+    /// For banded operators the product is exact and has `x.len() + lower` entries.
+    /// Otherwise the product is compressed to the first `x.len()` rows.
+    fn matvec(&self, x: &[Complex64]) -> Vec<Complex64> {
+        match self.bandwidths() {
+            Some((lower, upper)) => (0..x.len() + lower)
+                .map(|i| {
+                    (i.saturating_sub(lower)..(i + upper + 1).min(x.len()))
+                        .map(|j| self.get(i, j) * x[j])
+                        .sum()
+                })
+                .collect(),
+            None => (0..x.len())
+                .map(|i| x.iter().enumerate().map(|(j, &xj)| self.get(i, j) * xj).sum())
+                .collect(),
+        }
+    }
+
+    /// The n×n truncation, with the rows evaluated in parallel
+    ///
+    /// Entries outside the declared band are left zero without being evaluated.
+    #[cfg(feature = "rayon")]
+    fn par_truncate(&self, n: usize) -> Array2<Complex64> {
+        let rows: Vec<Vec<Complex64>> = (0..n)
+            .into_par_iter()
+            .map(|i| {
+                let columns = match self.bandwidths() {
+                    Some((lower, upper)) => i.saturating_sub(lower).min(n)..(i + upper + 1).min(n),
+                    None => 0..n,
+                };
+                let mut row = vec![Complex64::new(0.0, 0.0); n];
+                for j in columns {
+                    row[j] = self.get(i, j);
+                }
+                row
+            })
+            .collect();
+        Array2::from_shape_vec((n, n), rows.concat()).expect("n rows of length n")
+    }
+}
+
+/// Operator without known structure, given by a closure (i, j) ↦ A[i, j]
+pub struct DenseOperator<F> {
+    func: F,
+}
+
+impl<F> DenseOperator<F>
+where
+    F: Fn(usize, usize) -> Complex64 + Send + Sync,
+{
+    pub fn new(func: F) -> Self {
+        DenseOperator { func }
+    }
+}
+
+impl<F> LinearOperator for DenseOperator<F>
+where
+    F: Fn(usize, usize) -> Complex64 + Send + Sync,
+{
+    fn get(&self, i: usize, j: usize) -> Complex64 {
+        (self.func)(i, j)
+    }
+}
+
+/// Banded operator given by a closure that is only evaluated inside the band
+pub struct BandedOperator<F> {
+    lower: usize,
+    upper: usize,
+    func: F,
+}
+
+impl<F> BandedOperator<F>
+where
+    F: Fn(usize, usize) -> Complex64 + Send + Sync,
+{
+    pub fn new(lower: usize, upper: usize, func: F) -> Self {
+        BandedOperator { lower, upper, func }
+    }
+}
+
+impl<F> LinearOperator for BandedOperator<F>
+where
+    F: Fn(usize, usize) -> Complex64 + Send + Sync,
+{
+    fn get(&self, i: usize, j: usize) -> Complex64 {
+        if j + self.lower >= i && j <= i + self.upper {
+            (self.func)(i, j)
+        } else {
+            Complex64::new(0.0, 0.0)
+        }
+    }
+
+    fn bandwidths(&self) -> Option<(usize, usize)> {
+        Some((self.lower, self.upper))
+    }
+}
+
+/// Entries k ↦ a(k) along one diagonal of a banded operator, see
+/// [`InfiniteOperator::from_diagonals`]
+pub type DiagonalFn = Arc<dyn Fn(usize) -> Complex64 + Send + Sync>;
+
+/// Wrap a closure with real or complex values as a [`DiagonalFn`]
+pub fn diagonal_fn<F, T>(values: F) -> DiagonalFn
+where
+    F: Fn(usize) -> T + Send + Sync + 'static,
+    T: Into<Complex64>,
+{
+    Arc::new(move |k| values(k).into())
+}
+
+/// Diagonal operator diag(d₀, d₁, ...)
+#[derive(Clone)]
+pub struct DiagonalOperator {
+    values: Arc<dyn Fn(usize) -> Complex64 + Send + Sync>,
+    real: bool,
+}
+
+impl DiagonalOperator {
+    pub fn new<F>(values: F) -> Self
+    where
+        F: Fn(usize) -> Complex64 + Send + Sync + 'static,
+    {
+        DiagonalOperator { values: Arc::new(values), real: false }
+    }
+
+    /// Diagonal with real entries, hence Hermitian
+    pub fn real<F>(values: F) -> Self
+    where
+        F: Fn(usize) -> f64 + Send + Sync + 'static,
+    {
+        DiagonalOperator {
+            values: Arc::new(move |i| Complex64::new(values(i), 0.0)),
+            real: true,
+        }
+    }
+}
+
+impl LinearOperator for DiagonalOperator {
+    fn get(&self, i: usize, j: usize) -> Complex64 {
+        if i == j {
+            (self.values)(i)
+        } else {
+            Complex64::new(0.0, 0.0)
+        }
+    }
+
+    fn bandwidths(&self) -> Option<(usize, usize)> {
+        Some((0, 0))
+    }
+
+    fn is_hermitian(&self) -> bool {
+        self.real
+    }
+
+    fn matvec(&self, x: &[Complex64]) -> Vec<Complex64> {
+        x.iter().enumerate().map(|(i, &xi)| (self.values)(i) * xi).collect()
+    }
+}
+
+/// Lazy combination of operators; the operands keep their own entry caches
+#[derive(Clone)]
+pub enum ComposedOperator {
+    /// A + B
+    Sum(InfiniteOperator, InfiniteOperator),
+    /// AB, with inner sums truncated to the tolerance unless both factors are banded
+    Product(InfiniteOperator, InfiniteOperator, f64),
+    /// cA
+    Scaled(InfiniteOperator, Complex64),
+}
+
+impl LinearOperator for ComposedOperator {
+    fn get(&self, i: usize, j: usize) -> Complex64 {
+        match self {
+            ComposedOperator::Sum(a, b) => a.get(i, j) + b.get(i, j),
+            ComposedOperator::Product(a, b, tol) => match (a.bandwidths(), b.bandwidths()) {
+                (Some((la, ua)), Some((lb, ub))) => {
+                    let first = i.saturating_sub(la).max(j.saturating_sub(ub));
+                    (first..=(i + ua).min(j + lb)).map(|k| a.get(i, k) * b.get(k, j)).sum()
+                }
+                _ => adaptive_sum(|k| a.get_uncached(i, k) * b.get_uncached(k, j), i.max(j), *tol),
+            },
+            ComposedOperator::Scaled(a, c) => c * a.get(i, j),
+        }
+    }
+
+    fn bandwidths(&self) -> Option<(usize, usize)> {
+        match self {
+            ComposedOperator::Sum(a, b) => match (a.bandwidths(), b.bandwidths()) {
+                (Some((la, ua)), Some((lb, ub))) => Some((la.max(lb), ua.max(ub))),
+                _ => None,
+            },
+            ComposedOperator::Product(a, b, _) => match (a.bandwidths(), b.bandwidths()) {
+                (Some((la, ua)), Some((lb, ub))) => Some((la + lb, ua + ub)),
+                _ => None,
+            },
+            ComposedOperator::Scaled(a, _) => a.bandwidths(),
+        }
+    }
+
+    fn is_hermitian(&self) -> bool {
+        match self {
+            ComposedOperator::Sum(a, b) => a.is_hermitian() && b.is_hermitian(),
+            // AB is Hermitian only if A and B commute, which is not tracked
+            ComposedOperator::Product(..) => false,
+            ComposedOperator::Scaled(a, c) => a.is_hermitian() && c.im == 0.0,
+        }
+    }
+}
+
+/// Sum Σₖ term(k) over all k ≥ 0, truncated adaptively
+///
+/// Terms are added in blocks of doubling length until three consecutive blocks
+/// starting past `peak` each contribute at most `tol` relative to the absolute sum
+/// so far, so a single run of zeros (a gap in a sparse row) does not end the sum.
+pub(crate) fn adaptive_sum(term: impl Fn(usize) -> Complex64, peak: usize, tol: f64) -> Complex64 {
+    let mut total = Complex64::new(0.0, 0.0);
+    let mut magnitude = 0.0;
+    let mut start = 0;
+    let mut block = 16;
+    let mut small_blocks = 0;
+    while start < MAX_SUM_TERMS {
+        let end = (start + block).min(MAX_SUM_TERMS);
+        let terms: Vec<Complex64> = (start..end).map(&term).collect();
+        let contribution: Complex64 = terms.iter().sum();
+        total += contribution;
+        magnitude += terms.iter().map(|t| t.norm()).sum::<f64>();
+        start = end;
+        let small = start > peak && contribution.norm() <= tol * magnitude.max(f64::MIN_POSITIVE);
+        small_blocks = if small { small_blocks + 1 } else { 0 };
+        if small_blocks == SMALL_BLOCKS {
+            break;
+        }
+        block *= 2;
+    }
+    total
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::real;
+    use approx::assert_abs_diff_eq;
+
+    /// Tridiagonal operator with entry 1 + i + 10j inside the band
+    fn tridiagonal() -> InfiniteOperator {
+        InfiniteOperator::from_operator(BandedOperator::new(1, 1, |i, j| real(1.0 + i as f64 + 10.0 * j as f64)))
+    }
+
+    #[test]
+    fn dense_operators_report_no_structure() {
+        let dense = DenseOperator::new(|i, j| Complex64::new(i as f64, j as f64));
+        assert_eq!(dense.get(3, 5), Complex64::new(3.0, 5.0));
+        assert_eq!(dense.bandwidths(), None);
+        assert!(!dense.is_hermitian());
+        let y = dense.matvec(&[real(1.0), real(1.0)]);
+        assert_eq!(y, [Complex64::new(0.0, 1.0), Complex64::new(2.0, 1.0)]);
+    }
+
+    #[test]
+    fn banded_operators_are_zero_outside_the_band() {
+        let banded = BandedOperator::new(1, 2, |i, j| {
+            assert!(j + 1 >= i && j <= i + 2, "evaluated outside the band at ({i}, {j})");
+            real(1.0 + i as f64 + 10.0 * j as f64)
+        });
+        assert_eq!(banded.bandwidths(), Some((1, 2)));
+        assert!(!banded.is_hermitian());
+        assert_eq!(banded.get(3, 2), real(24.0));
+        assert_eq!(banded.get(3, 5), real(54.0));
+        assert_eq!(banded.get(3, 1), real(0.0));
+        assert_eq!(banded.get(3, 6), real(0.0));
+        // The product with x = e₀ + e₁ is exact and one row longer than x
+        let y = banded.matvec(&[real(1.0), real(1.0)]);
+        assert_eq!(y, [real(12.0), real(14.0), real(13.0)]);
+    }
+
+    #[test]
+    fn diagonal_operators_are_hermitian_only_with_real_entries() {
+        let real_diagonal = DiagonalOperator::real(|i| i as f64 + 1.0);
+        assert_eq!(real_diagonal.get(2, 2), real(3.0));
+        assert_eq!(real_diagonal.get(2, 3), real(0.0));
+        assert_eq!(real_diagonal.bandwidths(), Some((0, 0)));
+        assert!(real_diagonal.is_hermitian());
+        assert_eq!(real_diagonal.matvec(&[real(1.0), real(2.0)]), [real(1.0), real(4.0)]);
+
+        let complex_diagonal = DiagonalOperator::new(|i| Complex64::new(0.0, i as f64));
+        assert_eq!(complex_diagonal.get(4, 4), Complex64::new(0.0, 4.0));
+        assert!(!complex_diagonal.is_hermitian());
+    }
+
+    #[test]
+    fn sums_and_multiples_combine_entries_and_structure() {
+        let diagonal = InfiniteOperator::from_operator(DiagonalOperator::real(|i| i as f64));
+        let sum = ComposedOperator::Sum(tridiagonal(), diagonal.clone());
+        assert_eq!(sum.get(2, 2), real(23.0 + 2.0));
+        assert_eq!(sum.get(2, 4), real(0.0));
+        assert_eq!(sum.bandwidths(), Some((1, 1)));
+        assert!(!sum.is_hermitian());
+        assert!(ComposedOperator::Sum(diagonal.clone(), diagonal.clone()).is_hermitian());
+
+        let dense = InfiniteOperator::new(|i, j| real((i + j) as f64));
+        assert_eq!(ComposedOperator::Sum(tridiagonal(), dense).bandwidths(), None);
+
+        let scaled = ComposedOperator::Scaled(diagonal.clone(), Complex64::new(0.0, 2.0));
+        assert_eq!(scaled.get(3, 3), Complex64::new(0.0, 6.0));
+        assert_eq!(scaled.bandwidths(), Some((0, 0)));
+        assert!(!scaled.is_hermitian());
+        assert!(ComposedOperator::Scaled(diagonal, real(-2.0)).is_hermitian());
+    }
+
+    #[test]
+    fn banded_products_match_the_dense_product() {
+        let product = ComposedOperator::Product(tridiagonal(), tridiagonal(), 1e-14);
+        assert_eq!(product.bandwidths(), Some((2, 2)));
+        assert!(!product.is_hermitian());
+        // Rows well inside the n×n section see the whole band, so the entries agree
+        let n = 12;
+        let section = tridiagonal().get_truncation(n);
+        let dense = section.dot(&section);
+        for i in 0..n - 2 {
+            for j in 0..n - 2 {
+                assert_abs_diff_eq!(product.get(i, j).re, dense[[i, j]].re, epsilon = 1e-12);
+            }
+        }
+        assert_eq!(product.get(6, 9), real(0.0));
+    }
+
+    #[test]
+    fn dense_products_sum_the_inner_index_to_the_tolerance() {
+        // A = (2^−(i+j)), so (AA)[i, j] = 2^−(i+j) Σₖ 4^−k = 4/3 · 2^−(i+j)
+        let a = InfiniteOperator::new(|i, j| real(0.5f64.powi((i + j) as i32)));
+        let product = ComposedOperator::Product(a.clone(), a, 1e-15);
+        assert_eq!(product.bandwidths(), None);
+        for (i, j) in [(0, 0), (1, 3), (4, 2)] {
+            let exact = 4.0 / 3.0 * 0.5f64.powi((i + j) as i32);
+            assert_abs_diff_eq!(product.get(i, j).re, exact, epsilon = 1e-14);
+        }
+    }
+
+    #[test]
+    fn adaptive_sums_reach_the_series_limit() {
+        let geometric = adaptive_sum(|k| real(0.5f64.powi(k as i32)), 0, 1e-15);
+        assert_abs_diff_eq!(geometric.re, 2.0, epsilon = 1e-14);
+        // The terms peak at k = 40, past the first block
+        let shifted = adaptive_sum(|k| real(if k < 40 { 0.0 } else { 0.5f64.powi(k as i32 - 40) }), 40, 1e-15);
+        assert_abs_diff_eq!(shifted.re, 2.0, epsilon = 1e-14);
+    }
+
+    #[test]
+    fn a_gap_of_zeros_does_not_end_an_adaptive_sum() {
+        // Nonzero terms at k = 0 and k = 120 only; the blocks [16, 48) and [48, 112) are zero
+        let sparse = adaptive_sum(|k| real(if k == 0 || k == 120 { 1.0 } else { 0.0 }), 0, 1e-12);
+        assert_eq!(sparse, real(2.0));
+    }
+
+    #[test]
+    #[cfg(feature = "rayon")]
+    fn par_truncate_matches_the_serial_truncation() {
+        let dense = DenseOperator::new(|i, j| Complex64::new(i as f64, 1.0 / (j + 1) as f64));
+        let banded = BandedOperator::new(2, 1, |i, j| real(1.0 + i as f64 + 10.0 * j as f64));
+        let diagonal = DiagonalOperator::new(|i| Complex64::new(i as f64, -1.0));
+        let operators: [&dyn LinearOperator; 3] = [&dense, &banded, &diagonal];
+        for operator in operators {
+            let serial = Array2::from_shape_fn((9, 9), |(i, j)| operator.get(i, j));
+            assert_eq!(operator.par_truncate(9), serial);
+        }
+
+        // The cached handle goes through its entry cache
+        let handle = tridiagonal();
+        assert_eq!(LinearOperator::par_truncate(&handle, 9), handle.get_truncation(9));
+    }
+}
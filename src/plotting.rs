@@ -0,0 +1,272 @@
+//! Plots of spectra and pseudospectra, available with the `plotters` feature.
+//!
+//! The output format follows the file extension: `.svg` gives a vector image with
+//! title, axis labels and legend, anything else a bitmap (PNG for `.png`). Bitmaps
+//! are drawn without text, since rendering glyphs needs a font backend that the
+//! crate does not pull in; use SVG for annotated figures.
+
+use crate::iqr::{IqrSpectrumResult, SpectralStatus};
+use crate::pseudospectra::PseudospectrumGrid;
+use num_complex::Complex64;
+use plotters::coord::types::RangedCoordf64;
+use plotters::coord::Shift;
+use plotters::prelude::*;
+use plotters::style::colors::colormaps::ViridisRGB;
+use std::io;
+use std::ops::Range;
+use std::path::Path;
+
+/// Size of the generated images in pixels
+pub const PLOT_SIZE: (u32, u32) = (800, 800);
+
+/// Largest number of contour levels drawn by [`plot_pseudospectrum`]
+const MAX_CONTOUR_LEVELS: i32 = 12;
+
+/// Scatter plot of the spectrum in the complex plane
+///
+/// Eigenvalues of the largest truncation are coloured by their [`SpectralStatus`]
+/// (stable blue, suspect orange, polluted red); those of the smaller truncations are
+/// drawn as small grey dots, so the convergence towards the largest is visible.
+pub fn plot_spectrum(result: &IqrSpectrumResult, path: impl AsRef<Path>) -> io::Result<()> {
+    let path = path.as_ref();
+    if is_svg(path) {
+        draw_spectrum(SVGBackend::new(path, PLOT_SIZE).into_drawing_area(), result, true).map_err(plot_error)
+    } else {
+        draw_spectrum(BitMapBackend::new(path, PLOT_SIZE).into_drawing_area(), result, false).map_err(plot_error)
+    }
+}
+
+/// Heat map of log₁₀ σ_min over the grid with contour lines at every power of ten
+///
+/// The contour at 10^k bounds the 10^k-pseudospectrum of the section. Dark regions
+/// are close to the spectrum.
+pub fn plot_pseudospectrum(grid: &PseudospectrumGrid, path: impl AsRef<Path>) -> io::Result<()> {
+    if grid.re.len() < 2 || grid.im.len() < 2 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "a pseudospectrum plot needs at least two grid points in each direction",
+        ));
+    }
+    let path = path.as_ref();
+    if is_svg(path) {
+        draw_pseudospectrum(SVGBackend::new(path, PLOT_SIZE).into_drawing_area(), grid, true).map_err(plot_error)
+    } else {
+        draw_pseudospectrum(BitMapBackend::new(path, PLOT_SIZE).into_drawing_area(), grid, false).map_err(plot_error)
+    }
+}
+
+fn is_svg(path: &Path) -> bool {
+    path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("svg"))
+}
+
+fn plot_error<E: std::error::Error + Send + Sync>(error: DrawingAreaErrorKind<E>) -> io::Error {
+    io::Error::other(error.to_string())
+}
+
+type DrawResult<DB> = Result<(), DrawingAreaErrorKind<<DB as DrawingBackend>::ErrorType>>;
+
+fn draw_spectrum<DB: DrawingBackend>(root: DrawingArea<DB, Shift>, result: &IqrSpectrumResult, text: bool) -> DrawResult<DB> {
+    let largest_n = result.eigenvalues_by_n.keys().next_back().copied();
+    let earlier: Vec<Complex64> = result
+        .eigenvalues_by_n
+        .iter()
+        .filter(|(&n, _)| Some(n) != largest_n)
+        .flat_map(|(_, r)| r.eigenvalues.iter().copied())
+        .collect();
+    let (x_range, y_range) = bounds(result.eigenvalues.iter().chain(&earlier).map(|z| (z.re, z.im)));
+
+    root.fill(&WHITE)?;
+    let mut chart = chart_builder(&root, "Spectrum", text).build_cartesian_2d(x_range, y_range)?;
+    draw_mesh(&mut chart, text)?;
+
+    chart.draw_series(earlier.iter().map(|z| Circle::new((z.re, z.im), 2, RGBColor(160, 160, 160).filled())))?;
+    for (status, color, label) in [
+        (SpectralStatus::Stable, BLUE, "stable"),
+        (SpectralStatus::Suspect, RGBColor(230, 140, 0), "suspect"),
+        (SpectralStatus::Polluted, RED, "polluted"),
+    ] {
+        let points = result
+            .eigenvalues
+            .iter()
+            .zip(&result.classifications)
+            .filter(move |(_, &s)| s == status)
+            .map(move |(z, _)| Circle::new((z.re, z.im), 4, color.filled()));
+        let series = chart.draw_series(points)?;
+        if text {
+            series
+                .label(label)
+                .legend(move |(x, y)| Circle::new((x, y), 4, color.filled()));
+        }
+    }
+    if text {
+        chart
+            .configure_series_labels()
+            .background_style(WHITE.mix(0.8))
+            .border_style(BLACK)
+            .draw()?;
+    }
+    root.present()
+}
+
+fn draw_pseudospectrum<DB: DrawingBackend>(root: DrawingArea<DB, Shift>, grid: &PseudospectrumGrid, text: bool) -> DrawResult<DB> {
+    let values = grid.sigma_min.mapv(|s| s.max(f64::MIN_POSITIVE).log10());
+    let low = values.iter().copied().fold(f64::INFINITY, f64::min);
+    let high = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let span = (high - low).max(f64::EPSILON);
+    let (re_min, re_max) = (grid.re[0], grid.re[grid.re.len() - 1]);
+    let (im_min, im_max) = (grid.im[0], grid.im[grid.im.len() - 1]);
+
+    root.fill(&WHITE)?;
+    let mut chart = chart_builder(&root, "log₁₀ σ_min", text).build_cartesian_2d(re_min..re_max, im_min..im_max)?;
+    draw_mesh(&mut chart, text)?;
+
+    // One rectangle per grid cell, coloured by the mean of its corners
+    let cells = (0..grid.re.len() - 1).flat_map(|a| (0..grid.im.len() - 1).map(move |b| (a, b)));
+    chart.draw_series(cells.map(|(a, b)| {
+        let mean = (values[(a, b)] + values[(a + 1, b)] + values[(a, b + 1)] + values[(a + 1, b + 1)]) / 4.0;
+        let color = ViridisRGB::get_color((mean - low) / span);
+        Rectangle::new([(grid.re[a], grid.im[b]), (grid.re[a + 1], grid.im[b + 1])], color.filled())
+    }))?;
+
+    let first = low.ceil() as i32;
+    let last = (high.floor() as i32).min(first + MAX_CONTOUR_LEVELS - 1);
+    for level in first..=last {
+        let segments = contour_segments(grid, &values, level as f64);
+        chart.draw_series(segments.into_iter().map(|segment| PathElement::new(segment.to_vec(), BLACK)))?;
+    }
+    root.present()
+}
+
+fn chart_builder<'a, 'b, DB: DrawingBackend>(root: &'a DrawingArea<DB, Shift>, title: &str, text: bool) -> ChartBuilder<'a, 'b, DB> {
+    let mut builder = ChartBuilder::on(root);
+    builder.margin(20);
+    if text {
+        builder
+            .caption(title, ("sans-serif", 24))
+            .x_label_area_size(40)
+            .y_label_area_size(60);
+    }
+    builder
+}
+
+fn draw_mesh<DB: DrawingBackend>(
+    chart: &mut ChartContext<'_, DB, Cartesian2d<RangedCoordf64, RangedCoordf64>>,
+    text: bool,
+) -> DrawResult<DB> {
+    let mut mesh = chart.configure_mesh();
+    if text {
+        mesh.x_desc("Re").y_desc("Im");
+    }
+    mesh.draw()
+}
+
+/// Bounding box of the points, padded by a tenth of its size (or by 1 if degenerate)
+fn bounds(points: impl Iterator<Item = (f64, f64)>) -> (Range<f64>, Range<f64>) {
+    let (mut x_min, mut x_max, mut y_min, mut y_max) = (f64::INFINITY, f64::NEG_INFINITY, f64::INFINITY, f64::NEG_INFINITY);
+    for (x, y) in points.filter(|(x, y)| x.is_finite() && y.is_finite()) {
+        x_min = x_min.min(x);
+        x_max = x_max.max(x);
+        y_min = y_min.min(y);
+        y_max = y_max.max(y);
+    }
+    if x_min > x_max {
+        return (-1.0..1.0, -1.0..1.0);
+    }
+    let pad = |min: f64, max: f64| {
+        let margin = if max > min { 0.1 * (max - min) } else { 1.0 };
+        (min - margin)..(max + margin)
+    };
+    (pad(x_min, x_max), pad(y_min, y_max))
+}
+
+/// Segments of the level line `values = level` by marching squares
+fn contour_segments(grid: &PseudospectrumGrid, values: &ndarray::Array2<f64>, level: f64) -> Vec<[(f64, f64); 2]> {
+    let mut segments = Vec::new();
+    for a in 0..grid.re.len() - 1 {
+        for b in 0..grid.im.len() - 1 {
+            // Corners in order around the cell
+            let corners = [(a, b), (a + 1, b), (a + 1, b + 1), (a, b + 1)];
+            let mut crossings = Vec::with_capacity(4);
+            for k in 0..4 {
+                let (p, q) = (corners[k], corners[(k + 1) % 4]);
+                let (vp, vq) = (values[p] - level, values[q] - level);
+                if (vp < 0.0) != (vq < 0.0) {
+                    let t = vp / (vp - vq);
+                    crossings.push((
+                        grid.re[p.0] + t * (grid.re[q.0] - grid.re[p.0]),
+                        grid.im[p.1] + t * (grid.im[q.1] - grid.im[p.1]),
+                    ));
+                }
+            }
+            // Two crossings give one segment; a saddle cell has four, paired in order
+            for pair in crossings.chunks_exact(2) {
+                segments.push([pair[0], pair[1]]);
+            }
+        }
+    }
+    segments
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_abs_diff_eq;
+    use ndarray::Array2;
+
+    /// Grid over re = 0, 1, 2, 3 and im = 0, 1, 2 with values f(re, im)
+    fn grid(f: impl Fn(f64, f64) -> f64) -> PseudospectrumGrid {
+        let re = vec![0.0, 1.0, 2.0, 3.0];
+        let im = vec![0.0, 1.0, 2.0];
+        let sigma_min = Array2::from_shape_fn((re.len(), im.len()), |(a, b)| f(re[a], im[b]));
+        PseudospectrumGrid { re, im, sigma_min, n: 1 }
+    }
+
+    #[test]
+    fn marching_squares_traces_a_vertical_level_line() {
+        let grid = grid(|x, _| x);
+        let segments = contour_segments(&grid, &grid.sigma_min, 1.5);
+        // One segment in each of the two cells between re = 1 and re = 2
+        assert_eq!(segments.len(), 2);
+        for [(x0, y0), (x1, y1)] in segments {
+            assert_abs_diff_eq!(x0, 1.5);
+            assert_abs_diff_eq!(x1, 1.5);
+            assert_abs_diff_eq!((y1 - y0).abs(), 1.0);
+        }
+    }
+
+    #[test]
+    fn marching_squares_endpoints_lie_on_a_linear_level_set() {
+        let grid = grid(|x, y| x + 2.0 * y);
+        let segments = contour_segments(&grid, &grid.sigma_min, 2.5);
+        assert!(!segments.is_empty());
+        for (x, y) in segments.iter().flatten() {
+            assert_abs_diff_eq!(x + 2.0 * y, 2.5, epsilon = 1e-12);
+        }
+        // A level outside the range of values has no contour
+        assert!(contour_segments(&grid, &grid.sigma_min, 10.0).is_empty());
+    }
+
+    #[test]
+    fn bounds_pad_the_bounding_box() {
+        let (x, y) = bounds([(0.0, 1.0), (10.0, 1.0), (f64::NAN, 50.0)].into_iter());
+        assert_abs_diff_eq!(x.start, -1.0);
+        assert_abs_diff_eq!(x.end, 11.0);
+        assert_abs_diff_eq!(y.start, 0.0);
+        assert_abs_diff_eq!(y.end, 2.0);
+        assert_eq!(bounds(std::iter::empty()), (-1.0..1.0, -1.0..1.0));
+    }
+
+    #[test]
+    fn pseudospectrum_plots_reject_degenerate_grids_and_write_svg() {
+        let mut degenerate = grid(|x, _| x);
+        degenerate.im.truncate(1);
+        let error = plot_pseudospectrum(&degenerate, std::env::temp_dir().join("unused.svg")).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+
+        let path = std::env::temp_dir().join(format!("infinite-arrays-pseudospectrum-{}.svg", std::process::id()));
+        plot_pseudospectrum(&grid(|x, y| 0.1 + x + y), &path).unwrap();
+        let svg = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(svg.starts_with("<svg"));
+    }
+}
@@ -0,0 +1,547 @@
+//! Pseudospectra of infinite operators from their finite sections.
+//!
+//! The ε-pseudospectrum is the set of z with σ_min(A − z) < ε. Full SVDs at every grid
+//! point are wasteful; instead the section is reduced to Hessenberg form once, and at
+//! each point the smallest singular value is found by inverse iteration on the
+//! triangular factor of the shifted Hessenberg matrix.
+//!
+//! The same sections also yield singular value analysis: σ_min of the first n
+//! columns (rows) of A − z bounds the injection modulus of A − z (of its adjoint),
+//! the quantity the rigorous spectrum algorithms of the SCI framework are built on.
+
+use crate::arrays::PaddedArray;
+use crate::iqr::InfiniteOperator;
+use crate::linalg;
+use ndarray::Array2;
+use num_complex::Complex64;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::Arc;
+
+/// Maximum number of inverse iteration steps per point
+const MAX_INVERSE_ITERATIONS: usize = 100;
+
+/// Smallest singular value of the n×n section of `A − z`
+pub fn smallest_singular_value(operator: &InfiniteOperator, n: usize, z: Complex64, tol: f64) -> f64 {
+    let mut shifted = linalg::to_dmatrix(&operator.get_truncation(n));
+    for i in 0..n {
+        shifted[(i, i)] -= z;
+    }
+    let r = shifted.qr().r();
+    linalg::triangular_sigma_min(&r, tol, MAX_INVERSE_ITERATIONS)
+}
+
+/// Smallest singular value of the first n columns of `A − z`
+///
+/// For banded operators all nonzero rows of those columns are included, so this is
+/// the injection modulus of `A − z` restricted to the first n basis vectors; it
+/// decreases with n towards the injection modulus of `A − z`. Without bandwidth
+/// information 2n rows are used.
+fn column_sigma_min(operator: &InfiniteOperator, n: usize, z: Complex64, tol: f64) -> f64 {
+    let r = column_section(operator, n, z, false).qr().r();
+    linalg::triangular_sigma_min(&r, tol, MAX_INVERSE_ITERATIONS)
+}
+
+/// The first n columns of `A − z`, or with `adjoint` the first n columns of `(A − z)*`
+fn column_section(operator: &InfiniteOperator, n: usize, z: Complex64, adjoint: bool) -> nalgebra::DMatrix<Complex64> {
+    let extra = match (operator.bandwidths(), adjoint) {
+        (Some((lower, _)), false) => lower,
+        (Some((_, upper)), true) => upper,
+        (None, _) => n,
+    };
+    let m = n + extra;
+    let truncation = operator.get_truncation(m);
+    let mut columns = nalgebra::DMatrix::from_fn(m, n, |i, j| {
+        if adjoint {
+            truncation[(j, i)].conj()
+        } else {
+            truncation[(i, j)]
+        }
+    });
+    for i in 0..n {
+        columns[(i, i)] -= if adjoint { z.conj() } else { z };
+    }
+    columns
+}
+
+/// Singular values of the n×n section, in decreasing order
+pub fn singular_values(operator: &InfiniteOperator, n: usize) -> Vec<f64> {
+    if n == 0 {
+        return Vec::new();
+    }
+    let mut values: Vec<f64> = linalg::to_dmatrix(&operator.get_truncation(n))
+        .singular_values()
+        .iter()
+        .copied()
+        .collect();
+    values.sort_by(|a, b| b.total_cmp(a));
+    values
+}
+
+/// Upper bound on the injection modulus inf ‖Ax‖/‖x‖ from the first n columns
+///
+/// Decreases with n towards the injection modulus (exact columns for banded
+/// operators, 2n rows otherwise).
+pub fn injection_modulus(operator: &InfiniteOperator, n: usize, tol: f64) -> f64 {
+    column_sigma_min(operator, n, Complex64::new(0.0, 0.0), tol)
+}
+
+/// Construct a unit vector x with ‖(A − λ)x‖ small, a Weyl sequence element
+///
+/// For every n in `n_range` the smallest right singular vector of the first n
+/// columns of A − λ is computed; its residual ‖(A − λ)x‖ (recomputed with
+/// `matvec`, exact for banded operators) certifies that λ lies in the
+/// residual-sized pseudospectrum, and within that distance of the spectrum when A is
+/// normal. The vector with the smallest residual is returned.
+pub fn approximate_eigenvector(operator: &InfiniteOperator, lambda: Complex64, n_range: &[usize]) -> ApproximateEigenvector {
+    let mut sizes = n_range.to_vec();
+    sizes.sort_unstable();
+    sizes.dedup();
+    let mut best: Option<(Vec<Complex64>, f64, usize)> = None;
+    let mut history = Vec::new();
+    for n in sizes.into_iter().filter(|&n| n > 0) {
+        let svd = column_section(operator, n, lambda, false).svd(false, true);
+        let v_t = svd.v_t.as_ref().expect("right singular vectors were requested");
+        let (smallest, _) = svd
+            .singular_values
+            .iter()
+            .enumerate()
+            .min_by(|a, b| a.1.total_cmp(b.1))
+            .expect("sections are nonempty");
+        let x: Vec<Complex64> = v_t.row(smallest).iter().map(|c| c.conj()).collect();
+        let mut image = operator.matvec(&x);
+        linalg::axpy(&mut image, -lambda, &x);
+        let residual = linalg::norm(&image);
+        history.push((n, residual));
+        if best.as_ref().map_or(true, |&(_, r, _)| residual < r) {
+            best = Some((x, residual, n));
+        }
+    }
+    
+    let (coefficients, residual, n) = best.unwrap_or((Vec::new(), f64::INFINITY, 0));
+    ApproximateEigenvector {
+        lambda,
+        coefficients,
+        residual,
+        n,
+        history,
+    }
+}
+
+/// Approximate eigenvector of an infinite operator
+#[derive(Debug, Clone)]
+pub struct ApproximateEigenvector {
+    pub lambda: Complex64,
+    /// Unit vector supported on the first n coordinates
+    pub coefficients: Vec<Complex64>,
+    /// ‖(A − λ)x‖
+    pub residual: f64,
+    pub n: usize,
+    /// (n, residual) for every section size tried
+    pub history: Vec<(usize, f64)>,
+}
+
+impl ApproximateEigenvector {
+    /// Real part of x as an infinite array
+    pub fn real_part(&self) -> PaddedArray {
+        PaddedArray::new(self.coefficients.iter().map(|c| c.re).collect())
+    }
+    
+    /// Imaginary part of x as an infinite array
+    pub fn imag_part(&self) -> PaddedArray {
+        PaddedArray::new(self.coefficients.iter().map(|c| c.im).collect())
+    }
+}
+
+/// Estimate the operator norm ‖A‖ from growing sections
+///
+/// The largest singular values of nested sections increase towards ‖A‖, so each is
+/// a lower bound; n doubles from 8 until the relative change drops below `tol` or
+/// `max_n` is reached. For banded operators the norm is also bounded by the sum over
+/// diagonals of their largest entry; this bound uses the entries of the largest
+/// section and is therefore certified when every diagonal attains its supremum
+/// there (constant, periodic or decaying coefficients).
+pub fn operator_norm(operator: &InfiniteOperator, tol: f64, max_n: usize) -> OperatorNormEstimate {
+    let mut n = 8.min(max_n.max(1));
+    let mut history: Vec<(usize, f64)> = Vec::new();
+    let mut converged = false;
+    loop {
+        let sigma_max = singular_values(operator, n).first().copied().unwrap_or(0.0);
+        let settled = history
+            .last()
+            .is_some_and(|&(_, prev)| (sigma_max - prev).abs() <= tol * sigma_max.max(f64::MIN_POSITIVE));
+        history.push((n, sigma_max));
+        if settled {
+            converged = true;
+            break;
+        }
+        if n >= max_n {
+            break;
+        }
+        n = (2 * n).min(max_n);
+    }
+    
+    let estimate = history.iter().map(|&(_, s)| s).fold(0.0, f64::max);
+    let upper_bound = operator.bandwidths().map(|(lower, upper)| {
+        let section = operator.get_truncation(n + lower.max(upper));
+        let size = section.nrows() as isize;
+        (-(upper as isize)..=lower as isize)
+            .map(|k| {
+                (0..size)
+                    .filter(|&j| (0..size).contains(&(j + k)))
+                    .map(|j| section[((j + k) as usize, j as usize)].norm())
+                    .fold(0.0, f64::max)
+            })
+            .sum::<f64>()
+            .max(estimate)
+    });
+    
+    OperatorNormEstimate {
+        estimate,
+        upper_bound,
+        history,
+        converged,
+    }
+}
+
+/// Estimate of the operator norm
+#[derive(Debug, Clone)]
+pub struct OperatorNormEstimate {
+    /// Largest σ_max over the sections; a lower bound on ‖A‖
+    pub estimate: f64,
+    /// Band-sum upper bound, available for banded operators
+    pub upper_bound: Option<f64>,
+    /// (n, σ_max of the n×n section)
+    pub history: Vec<(usize, f64)>,
+    pub converged: bool,
+}
+
+/// Track the singular values of growing sections
+///
+/// Besides σ_max and σ_min of the square sections, records the injection modulus
+/// bounds of A and of A* from column and row sections; σ_min(A) = min of the two
+/// limits is what the SCI spectrum algorithms use.
+pub fn svd_spectrum(operator: &InfiniteOperator, n_range: &[usize], tol: f64) -> SvdSpectrumResult {
+    let mut sizes = n_range.to_vec();
+    sizes.sort_unstable();
+    sizes.dedup();
+    let zero = Complex64::new(0.0, 0.0);
+    
+    let mut result = SvdSpectrumResult {
+        singular_values_by_n: BTreeMap::new(),
+        sigma_max: Vec::new(),
+        sigma_min: Vec::new(),
+        injection_modulus: Vec::new(),
+        adjoint_injection_modulus: Vec::new(),
+    };
+    for n in sizes {
+        let values = singular_values(operator, n);
+        result.sigma_max.push((n, values.first().copied().unwrap_or(0.0)));
+        result.sigma_min.push((n, values.last().copied().unwrap_or(0.0)));
+        result.singular_values_by_n.insert(n, values);
+        result.injection_modulus.push((n, column_sigma_min(operator, n, zero, tol)));
+        let r = column_section(operator, n, zero, true).qr().r();
+        result
+            .adjoint_injection_modulus
+            .push((n, linalg::triangular_sigma_min(&r, tol, MAX_INVERSE_ITERATIONS)));
+    }
+    result
+}
+
+/// Singular value behaviour of growing sections
+#[derive(Debug, Clone)]
+pub struct SvdSpectrumResult {
+    pub singular_values_by_n: BTreeMap<usize, Vec<f64>>,
+    /// (n, largest singular value of the n×n section)
+    pub sigma_max: Vec<(usize, f64)>,
+    /// (n, smallest singular value of the n×n section)
+    pub sigma_min: Vec<(usize, f64)>,
+    /// (n, injection modulus bound of A from its first n columns)
+    pub injection_modulus: Vec<(usize, f64)>,
+    /// (n, injection modulus bound of A* from the first n rows of A)
+    pub adjoint_injection_modulus: Vec<(usize, f64)>,
+}
+
+/// Estimate the distance from the energy `energy` to the spectrum of a self-adjoint operator
+///
+/// For self-adjoint A the distance equals the injection modulus of A − E, which the
+/// column sections bound from above with values that decrease in n. The smallest
+/// value seen is returned as `upper_bound`. `lower_bound` extrapolates the last
+/// decrements geometrically and `confidence` (in [0, 1]) measures how settled the
+/// sequence is; both are estimates rather than guarantees.
+pub fn spectral_gap(operator: &InfiniteOperator, energy: f64, n_range: &[usize], tol: f64) -> SpectralGapEstimate {
+    let mut sizes = n_range.to_vec();
+    sizes.sort_unstable();
+    sizes.dedup();
+    let z = Complex64::new(energy, 0.0);
+    let history: Vec<(usize, f64)> = sizes
+        .iter()
+        .map(|&n| (n, column_sigma_min(operator, n, z, tol)))
+        .collect();
+    
+    let upper_bound = history.iter().map(|&(_, d)| d).fold(f64::INFINITY, f64::min);
+    let (lower_bound, confidence) = match history.as_slice() {
+        [.., (_, d0), (_, d1), (_, d2)] => {
+            let (step, last_step) = ((d0 - d1).max(0.0), (d1 - d2).max(0.0));
+            let ratio = if step > 0.0 { (last_step / step).min(0.99) } else { 0.0 };
+            let remaining = last_step * ratio / (1.0 - ratio);
+            let lower = (upper_bound - remaining).max(0.0);
+            let confidence = if upper_bound > 0.0 { 1.0 - (remaining / upper_bound).min(1.0) } else { 1.0 };
+            (lower, confidence)
+        }
+        [.., (_, d0), (_, d1)] => {
+            let last_step = (d0 - d1).max(0.0);
+            let lower = (upper_bound - last_step).max(0.0);
+            let confidence = if upper_bound > 0.0 { 0.5 * (1.0 - (last_step / upper_bound).min(1.0)) } else { 0.5 };
+            (lower, confidence)
+        }
+        _ => (0.0, 0.0),
+    };
+    
+    SpectralGapEstimate {
+        energy,
+        upper_bound,
+        lower_bound,
+        confidence,
+        history,
+    }
+}
+
+/// Estimate of the distance from an energy to the spectrum
+#[derive(Debug, Clone)]
+pub struct SpectralGapEstimate {
+    pub energy: f64,
+    /// Smallest injection modulus seen; bounds the distance from above
+    pub upper_bound: f64,
+    /// Extrapolated limit of the decreasing upper bounds
+    pub lower_bound: f64,
+    /// How settled the upper bounds are, from 0 (not at all) to 1
+    pub confidence: f64,
+    /// (n, σ_min) for every column section computed
+    pub history: Vec<(usize, f64)>,
+}
+
+/// Evaluate σ_min(Aₙ − z) over the grid `re × im` of the complex plane
+///
+/// The Hessenberg reduction of the section is computed once and reused for every
+/// point, so each point costs O(n²) per inverse iteration step.
+pub fn pseudospectrum(
+    operator: &InfiniteOperator,
+    n: usize,
+    re: &[f64],
+    im: &[f64],
+    tol: f64,
+) -> PseudospectrumGrid {
+    let hessenberg = nalgebra::Hessenberg::new(linalg::to_dmatrix(&operator.get_truncation(n))).unpack_h();
+    let sigma_min = Array2::from_shape_fn((re.len(), im.len()), |(a, b)| {
+        let r = linalg::shifted_hessenberg_r(&hessenberg, Complex64::new(re[a], im[b]));
+        linalg::triangular_sigma_min(&r, tol, MAX_INVERSE_ITERATIONS)
+    });
+    
+    PseudospectrumGrid {
+        re: re.to_vec(),
+        im: im.to_vec(),
+        sigma_min,
+        n,
+    }
+}
+
+/// Smallest singular values of a shifted section over a rectangular grid
+#[derive(Debug, Clone)]
+pub struct PseudospectrumGrid {
+    pub re: Vec<f64>,
+    pub im: Vec<f64>,
+    /// `sigma_min[(a, b)]` is σ_min(Aₙ − (re[a] + i·im[b]))
+    pub sigma_min: Array2<f64>,
+    pub n: usize,
+}
+
+impl PseudospectrumGrid {
+    /// Whether the grid point (a, b) lies in the ε-pseudospectrum
+    pub fn contains(&self, a: usize, b: usize, epsilon: f64) -> bool {
+        self.sigma_min[(a, b)] < epsilon
+    }
+}
+
+/// Bound for section size n: a row count m ≥ n and D with ‖(I − Pₘ) A Pₙ‖ ≤ D
+pub type DispersionBound = dyn Fn(usize) -> (usize, f64) + Send + Sync;
+
+/// How the part of the first n columns below the computed rows is controlled
+///
+/// This is the dispersion of Colbrook & Hansen: rigorous bounds need to know how
+/// much of A Pₙ is lost by keeping only m rows.
+#[derive(Clone)]
+pub enum Dispersion {
+    /// Use the declared bandwidths: the first n columns vanish below row n + lower
+    Banded,
+    /// Known column decay, e.g. from a bound on the entries far below the diagonal
+    Bound(Arc<DispersionBound>),
+}
+
+impl Dispersion {
+    /// Rows m and tail bound D for section size n, if the operator supports it
+    fn rows(&self, operator: &InfiniteOperator, n: usize) -> Option<(usize, f64)> {
+        match self {
+            Dispersion::Banded => operator.bandwidths().map(|(lower, _)| (n + lower, 0.0)),
+            Dispersion::Bound(bound) => {
+                let (m, tail) = bound(n);
+                Some((m.max(n), tail))
+            }
+        }
+    }
+}
+
+impl fmt::Debug for Dispersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Dispersion::Banded => write!(f, "Banded"),
+            Dispersion::Bound(_) => write!(f, "Bound(<fn>)"),
+        }
+    }
+}
+
+/// Guaranteed error bounds for approximate eigenvalues computed from the n×n section
+///
+/// For each λ the smallest right singular vector x of Pₘ(A − λ)Pₙ is formed and
+/// ε = ‖Pₘ(A − λ)x‖ + D is evaluated, plus an allowance for rounding in that
+/// product. Then ‖(A − λ)x‖ ≤ ε for a unit vector x, so λ lies in the closure of the
+/// ε-pseudospectrum of A; when A is normal, λ is within ε of the spectrum. The
+/// bound holds for any λ, converged or not. Without usable dispersion information
+/// (e.g. [`Dispersion::Banded`] on an operator with no bandwidths) it is infinite.
+pub fn spectral_error_bounds(
+    operator: &InfiniteOperator,
+    eigenvalues: &[Complex64],
+    n: usize,
+    dispersion: &Dispersion,
+) -> Vec<f64> {
+    let Some((m, tail)) = dispersion.rows(operator, n).filter(|_| n > 0) else {
+        return vec![f64::INFINITY; eigenvalues.len()];
+    };
+    let truncation = operator.get_truncation(m);
+    let columns = nalgebra::DMatrix::from_fn(m, n, |i, j| truncation[(i, j)]);
+    eigenvalues
+        .iter()
+        .map(|&lambda| {
+            let mut shifted = columns.clone();
+            for i in 0..n {
+                shifted[(i, i)] -= lambda;
+            }
+            let svd = shifted.clone().svd(false, true);
+            let v_t = svd.v_t.as_ref().expect("right singular vectors were requested");
+            let (smallest, _) = svd
+                .singular_values
+                .iter()
+                .enumerate()
+                .min_by(|a, b| a.1.total_cmp(b.1))
+                .expect("sections are nonempty");
+            let mut x: nalgebra::DVector<Complex64> = v_t.row(smallest).adjoint();
+            x /= Complex64::new(x.norm(), 0.0);
+            let rounding = (n + 1) as f64 * f64::EPSILON * shifted.norm();
+            (&shifted * &x).norm() + tail + rounding
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::iqr::create_diagonal_operator;
+    use crate::test_fixtures::{laplacian, real};
+
+    /// diag(1, 2, 3, ...), so σ_min(Aₙ − z) = min_{k ≤ n} |k − z|
+    fn diagonal() -> InfiniteOperator {
+        create_diagonal_operator(|i| real((i + 1) as f64))
+    }
+
+    #[test]
+    fn smallest_singular_value_is_the_distance_to_the_diagonal() {
+        let a = diagonal();
+        assert!((smallest_singular_value(&a, 8, real(2.3), 1e-12) - 0.3).abs() < 1e-10);
+        assert!((smallest_singular_value(&a, 8, Complex64::new(3.0, 0.5), 1e-12) - 0.5).abs() < 1e-10);
+        // Only the section counts: 20 is far from 1, ..., 8
+        assert!((smallest_singular_value(&a, 8, real(20.0), 1e-12) - 12.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn pseudospectrum_grid_matches_the_distances() {
+        let grid = pseudospectrum(&diagonal(), 8, &[1.0, 2.5], &[0.0, 1.0], 1e-12);
+        assert_eq!(grid.sigma_min.dim(), (2, 2));
+        assert!(grid.sigma_min[(0, 0)] < 1e-10);
+        assert!((grid.sigma_min[(1, 0)] - 0.5).abs() < 1e-10);
+        assert!((grid.sigma_min[(0, 1)] - 1.0).abs() < 1e-10);
+        assert!((grid.sigma_min[(1, 1)] - 1.25f64.sqrt()).abs() < 1e-10);
+        assert!(grid.contains(0, 0, 1e-8) && !grid.contains(1, 0, 0.4));
+    }
+
+    #[test]
+    fn spectral_gap_brackets_the_distance_to_the_spectrum() {
+        // E = 1 lies at distance 1 from [−4, 0]
+        let gap = spectral_gap(&laplacian(), 1.0, &[16, 32, 64], 1e-12);
+        assert_eq!(gap.history.len(), 3);
+        assert!(gap.history.windows(2).all(|pair| pair[1].1 <= pair[0].1 + 1e-12));
+        assert!(gap.upper_bound >= 1.0 - 1e-10 && gap.upper_bound < 1.01);
+        assert!(gap.lower_bound <= gap.upper_bound && gap.lower_bound > 0.9);
+        assert!(gap.confidence > 0.5);
+        // E = −2 is inside the spectrum
+        assert!(spectral_gap(&laplacian(), -2.0, &[16, 64], 1e-12).upper_bound < 0.1);
+    }
+
+    /// Forward shift S e_k = e_{k+1}, an isometry whose adjoint kills e₀
+    fn shift() -> InfiniteOperator {
+        crate::banded_operator! { -1 => |_| 1.0 }
+    }
+
+    #[test]
+    fn singular_values_of_the_shift() {
+        // Sections of S are nilpotent, although S itself is bounded below
+        assert_eq!(singular_values(&shift(), 4), [1.0, 1.0, 1.0, 0.0]);
+        assert!(singular_values(&shift(), 0).is_empty());
+        assert!((injection_modulus(&shift(), 8, 1e-12) - 1.0).abs() < 1e-10);
+
+        let result = svd_spectrum(&shift(), &[8, 4], 1e-12);
+        assert_eq!(result.singular_values_by_n.keys().copied().collect::<Vec<_>>(), [4, 8]);
+        for (k, &n) in [4, 8].iter().enumerate() {
+            assert_eq!(result.sigma_max[k], (n, 1.0));
+            assert_eq!(result.sigma_min[k].1, 0.0);
+            assert!((result.injection_modulus[k].1 - 1.0).abs() < 1e-10);
+            assert!(result.adjoint_injection_modulus[k].1 < 1e-10);
+        }
+    }
+
+    #[test]
+    fn operator_norm_of_the_laplacian() {
+        // ‖tridiag(1, −2, 1)‖ = 4, and the band sum 1 + 2 + 1 is exact
+        let estimate = operator_norm(&laplacian(), 1e-3, 256);
+        assert!(estimate.estimate <= 4.0 && estimate.estimate > 3.99);
+        assert_eq!(estimate.upper_bound, Some(4.0));
+        assert!(estimate.history.windows(2).all(|pair| pair[1].1 >= pair[0].1));
+        assert!(operator_norm(&InfiniteOperator::new(|i, j| real(0.5f64.powi((i + j) as i32))), 1e-12, 64).upper_bound.is_none());
+    }
+
+    #[test]
+    fn approximate_eigenvectors_certify_points_of_the_spectrum() {
+        let inside = approximate_eigenvector(&laplacian(), real(-2.0), &[8, 32, 128]);
+        assert_eq!(inside.history.len(), 3);
+        assert!(inside.residual < 0.05);
+        assert_eq!(inside.n, 128);
+        assert!((linalg::norm(&inside.coefficients) - 1.0).abs() < 1e-12);
+        // E = 1 is at distance 1 from the spectrum of the self-adjoint Laplacian
+        let outside = approximate_eigenvector(&laplacian(), real(1.0), &[8, 32]);
+        assert!(outside.residual >= 1.0 - 1e-10);
+    }
+
+    #[test]
+    fn spectral_error_bounds_cover_the_distance_to_the_spectrum() {
+        let a = diagonal();
+        let bounds = spectral_error_bounds(&a, &[real(3.0), real(3.1)], 8, &Dispersion::Banded);
+        assert!(bounds[0] < 1e-12);
+        assert!(bounds[1] >= 0.1 - 1e-12 && bounds[1] < 0.1 + 1e-12);
+        // A declared tail bound is added on top
+        let tail = Dispersion::Bound(Arc::new(|n| (n, 0.25)));
+        let bounds = spectral_error_bounds(&a, &[real(3.0)], 8, &tail);
+        assert!((bounds[0] - 0.25).abs() < 1e-12);
+        // Without bandwidths the banded dispersion gives nothing
+        let dense = InfiniteOperator::new(|i, j| real(0.5f64.powi((i + j) as i32)));
+        assert_eq!(spectral_error_bounds(&dense, &[real(1.0)], 8, &Dispersion::Banded), [f64::INFINITY]);
+    }
+}
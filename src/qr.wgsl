@@ -0,0 +1,136 @@
+// Householder RQ step of the IQR iteration, the GPU counterpart of `RqWorkspace::rq_step`.
+//
+// Complex numbers are vec2<f64> (re, im); matrices are row-major n×n. For each column k
+// the host dispatches `reflector`, `column_sums` and `update` in turn, which overwrite `a`
+// with R; `reflect_rows` then multiplies every row of `matrix` by Q = H₀H₁⋯ from the right.
+
+struct Params {
+    n: u32,
+    k: u32,
+}
+
+@group(0) @binding(0) var<uniform> params: Params;
+@group(0) @binding(1) var<storage, read_write> matrix: array<vec2<f64>>;
+// Householder vector of step k in row k, entries k..n
+@group(0) @binding(2) var<storage, read_write> reflectors: array<vec2<f64>>;
+// 2 / ‖v_k‖², zero when column k needed no reflection
+@group(0) @binding(3) var<storage, read_write> betas: array<f64>;
+// Diagonal entry of R in column k
+@group(0) @binding(4) var<storage, read_write> alphas: array<vec2<f64>>;
+// Row combinations vᴴA of the current reflection
+@group(0) @binding(5) var<storage, read_write> sums: array<vec2<f64>>;
+
+const WORKGROUP: u32 = 64u;
+
+var<workgroup> partial: array<f64, 64>;
+
+fn cmul(x: vec2<f64>, y: vec2<f64>) -> vec2<f64> {
+    return vec2<f64>(x.x * y.x - x.y * y.y, x.x * y.y + x.y * y.x);
+}
+
+fn conj(x: vec2<f64>) -> vec2<f64> {
+    return vec2<f64>(x.x, -x.y);
+}
+
+fn norm_sqr(x: vec2<f64>) -> f64 {
+    return x.x * x.x + x.y * x.y;
+}
+
+// Reflector of column k; one workgroup
+@compute @workgroup_size(64)
+fn reflector(@builtin(local_invocation_index) t: u32) {
+    let n = params.n;
+    let k = params.k;
+    var tail = 0.0lf;
+    for (var i = k + 1u + t; i < n; i += WORKGROUP) {
+        tail += norm_sqr(matrix[i * n + k]);
+    }
+    partial[t] = tail;
+    workgroupBarrier();
+    for (var stride = WORKGROUP / 2u; stride > 0u; stride /= 2u) {
+        if t < stride {
+            partial[t] += partial[t + stride];
+        }
+        workgroupBarrier();
+    }
+    let total = partial[0];
+    if total == 0.0lf {
+        if t == 0u {
+            betas[k] = 0.0lf;
+        }
+        return;
+    }
+    let x0 = matrix[k * n + k];
+    let x0_norm = sqrt(norm_sqr(x0));
+    var phase = vec2<f64>(1.0lf, 0.0lf);
+    if x0_norm != 0.0lf {
+        phase = x0 / x0_norm;
+    }
+    let alpha = -phase * sqrt(norm_sqr(x0) + total);
+    for (var i = k + 1u + t; i < n; i += WORKGROUP) {
+        reflectors[k * n + i] = matrix[i * n + k];
+    }
+    if t == 0u {
+        let vk = x0 - alpha;
+        reflectors[k * n + k] = vk;
+        betas[k] = 2.0lf / (norm_sqr(vk) + total);
+        alphas[k] = alpha;
+    }
+}
+
+// sums_j = Σ_i conj(v_i) a_ij over columns j > k; one invocation per column
+@compute @workgroup_size(64)
+fn column_sums(@builtin(global_invocation_id) id: vec3<u32>) {
+    let n = params.n;
+    let k = params.k;
+    let j = k + 1u + id.x;
+    if j >= n || betas[k] == 0.0lf {
+        return;
+    }
+    var sum = vec2<f64>(0.0lf, 0.0lf);
+    for (var i = k; i < n; i++) {
+        sum += cmul(conj(reflectors[k * n + i]), matrix[i * n + j]);
+    }
+    sums[j] = sum;
+}
+
+// A ← A − β v sums on rows and columns k.., with column k set to (α, 0, …, 0)
+@compute @workgroup_size(8, 8)
+fn update(@builtin(global_invocation_id) id: vec3<u32>) {
+    let n = params.n;
+    let k = params.k;
+    let i = k + id.y;
+    let j = k + id.x;
+    if i >= n || j >= n || betas[k] == 0.0lf {
+        return;
+    }
+    if j == k {
+        matrix[i * n + k] = select(vec2<f64>(0.0lf, 0.0lf), alphas[k], i == k);
+    } else {
+        matrix[i * n + j] -= betas[k] * cmul(reflectors[k * n + i], sums[j]);
+    }
+}
+
+// Row r ← row r · H₀H₁⋯H_{n−2}; one invocation per row
+@compute @workgroup_size(64)
+fn reflect_rows(@builtin(global_invocation_id) id: vec3<u32>) {
+    let n = params.n;
+    let r = id.x;
+    if r >= n {
+        return;
+    }
+    for (var k = 0u; k + 1u < n; k++) {
+        let beta = betas[k];
+        if beta == 0.0lf {
+            continue;
+        }
+        var w = vec2<f64>(0.0lf, 0.0lf);
+        for (var j = k; j < n; j++) {
+            w += cmul(matrix[r * n + j], reflectors[k * n + j]);
+        }
+        w *= beta;
+        for (var j = k; j < n; j++) {
+            matrix[r * n + j] -= cmul(w, conj(reflectors[k * n + j]));
+        }
+    }
+}
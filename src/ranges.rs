@@ -1,143 +1,1337 @@
-//! Infinite range types for indexing infinite arrays.
-
-
-/// Infinite range starting from 1: 1, 2, 3, ...
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub struct OneToInf;
-
-impl OneToInf {
-    pub fn new() -> Self {
-        OneToInf
-    }
-    
-    pub fn start(&self) -> usize {
-        1
-    }
-    
-    pub fn contains(&self, item: usize) -> bool {
-        item >= 1
-    }
-    
-    pub fn index(&self, value: usize) -> usize {
-        if value < 1 {
-            panic!("{} not in OneToInf", value);
-        }
-        value - 1
-    }
-}
-
-impl Default for OneToInf {
-    fn default() -> Self {
-        OneToInf::new()
-    }
-}
-
-impl OneToInf {
-    pub fn iter(&self) -> impl Iterator<Item = usize> {
-        1..
-    }
-}
-
-impl OneToInf {
-    pub fn get(&self, key: usize) -> usize {
-        key + 1
-    }
-}
-
-/// Infinite unit range starting from a given value: start, start+1, start+2, ...
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub struct InfUnitRange {
-    start: usize,
-    step: usize,
-}
-
-impl InfUnitRange {
-    pub fn new(start: usize, step: usize) -> Self {
-        InfUnitRange { start, step }
-    }
-    
-    pub fn start(&self) -> usize {
-        self.start
-    }
-    
-    pub fn step(&self) -> usize {
-        self.step
-    }
-    
-    pub fn contains(&self, item: usize) -> bool {
-        if self.step == 1 {
-            item >= self.start
-        } else {
-            (item >= self.start) && ((item - self.start) % self.step == 0)
-        }
-    }
-    
-    pub fn index(&self, value: usize) -> usize {
-        if !self.contains(value) {
-            panic!("{} not in {:?}", value, self);
-        }
-        (value - self.start) / self.step
-    }
-}
-
-impl InfUnitRange {
-    pub fn iter(&self) -> impl Iterator<Item = usize> {
-        let start = self.start;
-        let step = self.step;
-        (0..).map(move |i| start + i * step)
-    }
-}
-
-impl InfUnitRange {
-    pub fn get(&self, key: usize) -> usize {
-        self.start + key * self.step
-    }
-}
-
-/// Infinite step range: start, start+step, start+2*step, ...
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub struct InfStepRange {
-    start: usize,
-    step: usize,
-}
-
-impl InfStepRange {
-    pub fn new(start: usize, step: usize) -> Self {
-        InfStepRange { start, step }
-    }
-    
-    pub fn start(&self) -> usize {
-        self.start
-    }
-    
-    pub fn step(&self) -> usize {
-        self.step
-    }
-    
-    pub fn contains(&self, item: usize) -> bool {
-        let remainder = (item as isize - self.start as isize) % self.step as isize;
-        remainder == 0 && item >= self.start
-    }
-    
-    pub fn index(&self, value: usize) -> usize {
-        if !self.contains(value) {
-            panic!("{} not in {:?}", value, self);
-        }
-        ((value as isize - self.start as isize) / self.step as isize) as usize
-    }
-}
-
-impl InfStepRange {
-    pub fn iter(&self) -> impl Iterator<Item = usize> {
-        let start = self.start;
-        let step = self.step;
-        (0..).map(move |i| (start as isize + i as isize * step as isize) as usize)
-    }
-}
-
-impl InfStepRange {
-    pub fn get(&self, key: usize) -> usize {
-        (self.start as isize + key as isize * self.step as isize) as usize
-    }
-}
-
+//! Infinite range types for indexing infinite arrays.
+
+use crate::arrays::{InfiniteArray, Shape};
+use crate::error::RangeError;
+use crate::infinity::{Infinity, SignedInfinity, Size};
+use alloc::format;
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use core::fmt;
+use core::iter::FusedIterator;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use core::num::NonZeroIsize;
+use core::ops::{Add, Mul, Neg, Range, RangeFrom};
+
+/// Iterator over the values of an infinite range
+///
+/// Endless: `next` never returns `None` (so the iterator is trivially fused), and
+/// there is no `DoubleEndedIterator` or `ExactSizeIterator` since there is no last
+/// element. Bound it with `take`, `take_while` or `zip` before collecting.
+#[derive(Debug, Clone)]
+pub struct InfRangeIter {
+    next: usize,
+    step: usize,
+}
+
+impl Iterator for InfRangeIter {
+    type Item = usize;
+    
+    fn next(&mut self) -> Option<usize> {
+        let value = self.next;
+        self.next += self.step;
+        Some(value)
+    }
+    
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (usize::MAX, None)
+    }
+    
+    fn nth(&mut self, n: usize) -> Option<usize> {
+        self.next += n * self.step;
+        self.next()
+    }
+}
+
+impl FusedIterator for InfRangeIter {}
+
+/// Infinite range starting from 1: 1, 2, 3, ...
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct OneToInf;
+
+impl OneToInf {
+    pub fn new() -> Self {
+        OneToInf
+    }
+    
+    pub fn start(&self) -> usize {
+        1
+    }
+    
+    pub fn contains(&self, item: usize) -> bool {
+        item >= 1
+    }
+    
+    /// Position of `value` in the range, panicking if it is not contained
+    pub fn index(&self, value: usize) -> usize {
+        self.try_index(value).unwrap_or_else(|error| panic!("{}", error))
+    }
+    
+    /// Position of `value` in the range
+    pub fn try_index(&self, value: usize) -> Result<usize, RangeError> {
+        value.checked_sub(1).ok_or_else(|| RangeError::new(value as u64, self))
+    }
+}
+
+impl Default for OneToInf {
+    fn default() -> Self {
+        OneToInf::new()
+    }
+}
+
+impl OneToInf {
+    pub fn iter(&self) -> InfRangeIter {
+        InfRangeIter { next: 1, step: 1 }
+    }
+}
+
+impl IntoIterator for OneToInf {
+    type Item = usize;
+    type IntoIter = InfRangeIter;
+    
+    fn into_iter(self) -> InfRangeIter {
+        self.iter()
+    }
+}
+
+impl IntoIterator for &OneToInf {
+    type Item = usize;
+    type IntoIter = InfRangeIter;
+    
+    fn into_iter(self) -> InfRangeIter {
+        self.iter()
+    }
+}
+
+impl OneToInf {
+    pub fn get(&self, key: usize) -> usize {
+        key + 1
+    }
+}
+
+/// Infinite unit range starting from a given value: start, start+1, start+2, ...
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct InfUnitRange {
+    start: usize,
+}
+
+impl InfUnitRange {
+    /// Former constructor taking a step, kept for code written against it
+    ///
+    /// Returns start, start+step, ... as an [`InfStepRange`], so existing calls keep
+    /// their values for every step, while code that names the result `InfUnitRange`
+    /// stops compiling. Panics where [`InfStepRange::new`] does, for step 0, and for
+    /// arguments above `isize::MAX`.
+    #[deprecated(note = "use `InfUnitRange::starting_at`; for steps other than 1 use `InfStepRange::new`")]
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(start: usize, step: usize) -> InfStepRange {
+        let signed = |n: usize| isize::try_from(n).expect("range bounds must fit in isize");
+        InfStepRange::new(signed(start), signed(step))
+    }
+    
+    pub fn starting_at(start: usize) -> Self {
+        InfUnitRange { start }
+    }
+    
+    pub fn start(&self) -> usize {
+        self.start
+    }
+    
+    /// Always 1
+    pub fn step(&self) -> usize {
+        1
+    }
+    
+    pub fn contains(&self, item: usize) -> bool {
+        item >= self.start
+    }
+    
+    /// Position of `value` in the range, panicking if it is not contained
+    pub fn index(&self, value: usize) -> usize {
+        self.try_index(value).unwrap_or_else(|error| panic!("{}", error))
+    }
+    
+    /// Position of `value` in the range
+    pub fn try_index(&self, value: usize) -> Result<usize, RangeError> {
+        value.checked_sub(self.start).ok_or_else(|| RangeError::new(value as u64, self))
+    }
+}
+
+impl InfUnitRange {
+    pub fn iter(&self) -> InfRangeIter {
+        InfRangeIter { next: self.start, step: 1 }
+    }
+}
+
+impl IntoIterator for InfUnitRange {
+    type Item = usize;
+    type IntoIter = InfRangeIter;
+    
+    fn into_iter(self) -> InfRangeIter {
+        self.iter()
+    }
+}
+
+impl IntoIterator for &InfUnitRange {
+    type Item = usize;
+    type IntoIter = InfRangeIter;
+    
+    fn into_iter(self) -> InfRangeIter {
+        self.iter()
+    }
+}
+
+impl InfUnitRange {
+    pub fn get(&self, key: usize) -> usize {
+        self.start + key
+    }
+}
+
+impl From<usize> for InfUnitRange {
+    fn from(start: usize) -> Self {
+        InfUnitRange::starting_at(start)
+    }
+}
+
+impl From<OneToInf> for InfUnitRange {
+    fn from(_: OneToInf) -> Self {
+        InfUnitRange::starting_at(1)
+    }
+}
+
+/// Infinite step range: start, start+step, start+2*step, ...
+///
+/// The step is a nonzero integer of either sign. A negative step gives a descending
+/// range such as 0, −1, −2, ... toward −∞, used as index sets for bi-infinite
+/// arrays and for reversed views. Ranges are ordered by start and then by step,
+/// like the sequences of their values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct InfStepRange {
+    start: isize,
+    step: NonZeroIsize,
+}
+
+impl InfStepRange {
+    /// Panics if `step` is zero
+    pub fn new(start: isize, step: isize) -> Self {
+        match NonZeroIsize::new(step) {
+            Some(step) => InfStepRange { start, step },
+            None => panic!("step of an infinite range cannot be zero"),
+        }
+    }
+    
+    /// start, start−1, start−2, ...
+    pub fn descending(start: isize) -> Self {
+        InfStepRange::new(start, -1)
+    }
+    
+    pub fn start(&self) -> isize {
+        self.start
+    }
+    
+    pub fn step(&self) -> isize {
+        self.step.get()
+    }
+    
+    /// Whether the range ascends toward +∞
+    pub fn is_ascending(&self) -> bool {
+        self.step() > 0
+    }
+    
+    pub fn contains(&self, item: isize) -> bool {
+        let offset = item as i128 - self.start as i128;
+        let step = self.step() as i128;
+        offset % step == 0 && offset / step >= 0
+    }
+    
+    /// Position of `value` in the range, panicking if it is not contained
+    pub fn index(&self, value: isize) -> usize {
+        self.try_index(value).unwrap_or_else(|error| panic!("{}", error))
+    }
+    
+    /// Position of `value` in the range
+    pub fn try_index(&self, value: isize) -> Result<usize, RangeError> {
+        if !self.contains(value) {
+            return Err(RangeError::new(value as i64, self));
+        }
+        Ok(((value as i128 - self.start as i128) / self.step() as i128) as usize)
+    }
+    
+    pub fn get(&self, key: usize) -> isize {
+        self.start + key as isize * self.step()
+    }
+    
+    /// Limit of the values: +∞ for ascending ranges, −∞ for descending ones
+    pub fn limit(&self) -> SignedInfinity {
+        if self.is_ascending() { SignedInfinity::Positive } else { SignedInfinity::Negative }
+    }
+    
+    pub fn iter(&self) -> SignedRangeIter {
+        SignedRangeIter { next: self.start, step: self.step() }
+    }
+}
+
+impl IntoIterator for InfStepRange {
+    type Item = isize;
+    type IntoIter = SignedRangeIter;
+    
+    fn into_iter(self) -> SignedRangeIter {
+        self.iter()
+    }
+}
+
+impl IntoIterator for &InfStepRange {
+    type Item = isize;
+    type IntoIter = SignedRangeIter;
+    
+    fn into_iter(self) -> SignedRangeIter {
+        self.iter()
+    }
+}
+
+/// The range as a unit range, if its step is 1 and it starts at or above 0
+impl TryFrom<InfStepRange> for InfUnitRange {
+    type Error = InfStepRange;
+    
+    fn try_from(range: InfStepRange) -> Result<Self, InfStepRange> {
+        match usize::try_from(range.start) {
+            Ok(start) if range.step() == 1 => Ok(InfUnitRange::starting_at(start)),
+            _ => Err(range),
+        }
+    }
+}
+
+/// Iterator over the values of an [`InfStepRange`], endless like [`InfRangeIter`]
+#[derive(Debug, Clone)]
+pub struct SignedRangeIter {
+    next: isize,
+    step: isize,
+}
+
+impl Iterator for SignedRangeIter {
+    type Item = isize;
+    
+    fn next(&mut self) -> Option<isize> {
+        let value = self.next;
+        self.next += self.step;
+        Some(value)
+    }
+    
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (usize::MAX, None)
+    }
+    
+    fn nth(&mut self, n: usize) -> Option<isize> {
+        self.next += n as isize * self.step;
+        self.next()
+    }
+}
+
+impl FusedIterator for SignedRangeIter {}
+
+/// Integers unbounded below: ..., end−2, end−1, end
+///
+/// Iteration and positions run downward from `end`, so position k holds end − k.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct NegInfRange {
+    end: isize,
+}
+
+impl NegInfRange {
+    pub fn new(end: isize) -> Self {
+        NegInfRange { end }
+    }
+    
+    pub fn end(&self) -> isize {
+        self.end
+    }
+    
+    /// Lower end of the range, −∞
+    pub fn start(&self) -> SignedInfinity {
+        SignedInfinity::Negative
+    }
+    
+    pub fn contains(&self, item: isize) -> bool {
+        item <= self.end
+    }
+    
+    /// Position of `value` counted down from `end`, panicking if it is not contained
+    pub fn index(&self, value: isize) -> usize {
+        self.try_index(value).unwrap_or_else(|error| panic!("{}", error))
+    }
+    
+    /// Position of `value` counted down from `end`
+    pub fn try_index(&self, value: isize) -> Result<usize, RangeError> {
+        if !self.contains(value) {
+            return Err(RangeError::new(value as i64, self));
+        }
+        Ok((self.end as i128 - value as i128) as usize)
+    }
+    
+    pub fn get(&self, key: usize) -> isize {
+        self.end - key as isize
+    }
+    
+    pub fn iter(&self) -> SignedRangeIter {
+        SignedRangeIter { next: self.end, step: -1 }
+    }
+}
+
+impl IntoIterator for NegInfRange {
+    type Item = isize;
+    type IntoIter = SignedRangeIter;
+    
+    fn into_iter(self) -> SignedRangeIter {
+        self.iter()
+    }
+}
+
+/// All integers ℤ, the axis of bi-infinite arrays and Laurent operators
+///
+/// Positions enumerate ℤ symmetrically about 0: 0, 1, −1, 2, −2, ..., so position
+/// 2m − 1 holds m and position 2m holds −m.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct FullZRange;
+
+impl FullZRange {
+    /// −∞
+    pub fn start(&self) -> SignedInfinity {
+        SignedInfinity::Negative
+    }
+    
+    /// +∞
+    pub fn end(&self) -> SignedInfinity {
+        SignedInfinity::Positive
+    }
+    
+    pub fn contains(&self, _item: isize) -> bool {
+        true
+    }
+    
+    /// Position of `value` in the symmetric enumeration
+    pub fn index(&self, value: isize) -> usize {
+        if value > 0 {
+            2 * value as usize - 1
+        } else {
+            2 * value.unsigned_abs()
+        }
+    }
+    
+    pub fn get(&self, key: usize) -> isize {
+        let m = key.div_ceil(2) as isize;
+        if key % 2 == 1 { m } else { -m }
+    }
+    
+    /// 0, 1, −1, 2, −2, ...
+    pub fn iter(&self) -> SymmetricIter {
+        SymmetricIter { position: 0 }
+    }
+    
+    /// The symmetric window −radius, ..., radius in increasing order
+    pub fn window(&self, radius: usize) -> core::ops::RangeInclusive<isize> {
+        -(radius as isize)..=radius as isize
+    }
+}
+
+impl IntoIterator for FullZRange {
+    type Item = isize;
+    type IntoIter = SymmetricIter;
+    
+    fn into_iter(self) -> SymmetricIter {
+        self.iter()
+    }
+}
+
+/// Iterator over ℤ in the order 0, 1, −1, 2, −2, ...; endless like [`InfRangeIter`]
+///
+/// The first 2r + 1 items are exactly the symmetric window −r..=r.
+#[derive(Debug, Clone)]
+pub struct SymmetricIter {
+    position: usize,
+}
+
+impl Iterator for SymmetricIter {
+    type Item = isize;
+    
+    fn next(&mut self) -> Option<isize> {
+        let value = FullZRange.get(self.position);
+        self.position += 1;
+        Some(value)
+    }
+    
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (usize::MAX, None)
+    }
+    
+    fn nth(&mut self, n: usize) -> Option<isize> {
+        self.position += n;
+        self.next()
+    }
+}
+
+impl FusedIterator for SymmetricIter {}
+
+/// Infinite range with rational start and step: (start + k·step) / den for k = 0, 1, ...
+///
+/// Each value is computed from k directly in integer arithmetic, so unlike summing a
+/// floating-point step there is no drift however far the range is indexed. The range
+/// is an [`InfiniteArray`] of the values as f64, e.g. for quadrature node grids.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RationalStepRange {
+    start: i64,
+    step: i64,
+    den: i64,
+}
+
+impl RationalStepRange {
+    /// Range start/den, (start + step)/den, ..., stored in lowest terms with den > 0
+    pub fn new(start: i64, step: i64, den: i64) -> Self {
+        if step == 0 {
+            panic!("step of an infinite range cannot be zero");
+        }
+        if den == 0 {
+            panic!("denominator of a rational range cannot be zero");
+        }
+        let sign = den.signum();
+        let divisor = gcd(gcd(start, step), den);
+        RationalStepRange {
+            start: sign * start / divisor,
+            step: sign * step / divisor,
+            den: den.abs() / divisor,
+        }
+    }
+    
+    /// Start as a reduced fraction (numerator, denominator)
+    pub fn start(&self) -> (i64, i64) {
+        reduce(self.start as i128, self.den)
+    }
+    
+    /// Step as a reduced fraction (numerator, denominator)
+    pub fn step(&self) -> (i64, i64) {
+        reduce(self.step as i128, self.den)
+    }
+    
+    /// Common denominator of all values
+    pub fn denominator(&self) -> i64 {
+        self.den
+    }
+    
+    /// Value k as a reduced fraction (numerator, denominator)
+    pub fn get_exact(&self, key: usize) -> (i64, i64) {
+        reduce(self.numerator(key), self.den)
+    }
+    
+    /// Value k, rounded to f64 from the exact fraction
+    pub fn get(&self, key: usize) -> f64 {
+        self.numerator(key) as f64 / self.den as f64
+    }
+    
+    /// Whether num/den is one of the values
+    pub fn contains(&self, num: i64, den: i64) -> bool {
+        if den == 0 {
+            return false;
+        }
+        // num/den = (start + k·step)/self.den  ⇔  num·self.den − start·den = k·step·den
+        let offset = num as i128 * self.den as i128 - self.start as i128 * den as i128;
+        let step = self.step as i128 * den as i128;
+        offset % step == 0 && offset / step >= 0
+    }
+    
+    /// Exact numerator of value k over the common denominator
+    fn numerator(&self, key: usize) -> i128 {
+        self.start as i128 + key as i128 * self.step as i128
+    }
+}
+
+/// Ranges as lazy arrays of their values, e.g. `InfiniteDiagonal::from(OneToInf)`
+/// for the position operator diag(1, 2, 3, ...)
+macro_rules! range_arrays {
+    ($($range:ty),+) => {
+        $(
+            impl InfiniteArray for $range {
+                fn get(&self, index: usize) -> f64 {
+                    <$range>::get(self, index) as f64
+                }
+                
+                fn shape(&self) -> Shape {
+                    Shape::OneD(Size::Infinite)
+                }
+            }
+        )+
+    };
+}
+
+range_arrays!(OneToInf, InfUnitRange, InfStepRange, NegInfRange, FullZRange);
+
+impl InfiniteArray for RationalStepRange {
+    fn get(&self, index: usize) -> f64 {
+        RationalStepRange::get(self, index)
+    }
+    
+    fn shape(&self) -> Shape {
+        Shape::OneD(Size::Infinite)
+    }
+}
+
+fn gcd(a: i64, b: i64) -> i64 {
+    let (mut a, mut b) = (a.unsigned_abs(), b.unsigned_abs());
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a as i64
+}
+
+/// num/den in lowest terms, den > 0
+fn reduce(num: i128, den: i64) -> (i64, i64) {
+    let (mut a, mut b) = (num.unsigned_abs(), den.unsigned_abs() as u128);
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    let divisor = a.max(1) as i128;
+    ((num / divisor) as i64, (den as i128 / divisor) as i64)
+}
+
+// Range algebra: shifting, scaling and negating a range gives another range, so
+// index computations stay allocation-free. Scaling by zero panics, as a range with
+// step zero is not a range.
+
+impl Add<usize> for OneToInf {
+    type Output = InfUnitRange;
+    
+    fn add(self, k: usize) -> InfUnitRange {
+        InfUnitRange::starting_at(1 + k)
+    }
+}
+
+impl Mul<usize> for OneToInf {
+    type Output = InfStepRange;
+    
+    fn mul(self, k: usize) -> InfStepRange {
+        nonzero_factor(k);
+        InfStepRange::new(k as isize, k as isize)
+    }
+}
+
+impl Neg for OneToInf {
+    type Output = InfStepRange;
+    
+    fn neg(self) -> InfStepRange {
+        InfStepRange::new(-1, -1)
+    }
+}
+
+impl Add<usize> for InfUnitRange {
+    type Output = InfUnitRange;
+    
+    fn add(self, k: usize) -> InfUnitRange {
+        InfUnitRange::starting_at(self.start + k)
+    }
+}
+
+impl Mul<usize> for InfUnitRange {
+    type Output = InfStepRange;
+    
+    fn mul(self, k: usize) -> InfStepRange {
+        nonzero_factor(k);
+        InfStepRange::new((self.start * k) as isize, k as isize)
+    }
+}
+
+impl Neg for InfUnitRange {
+    type Output = InfStepRange;
+    
+    fn neg(self) -> InfStepRange {
+        InfStepRange::new(-(self.start as isize), -1)
+    }
+}
+
+impl Add<isize> for InfStepRange {
+    type Output = InfStepRange;
+    
+    fn add(self, k: isize) -> InfStepRange {
+        InfStepRange::new(self.start + k, self.step())
+    }
+}
+
+/// A negative factor reverses the direction of the range
+impl Mul<isize> for InfStepRange {
+    type Output = InfStepRange;
+    
+    fn mul(self, k: isize) -> InfStepRange {
+        InfStepRange::new(self.start * k, self.step() * k)
+    }
+}
+
+impl Neg for InfStepRange {
+    type Output = InfStepRange;
+    
+    fn neg(self) -> InfStepRange {
+        InfStepRange::new(-self.start, -self.step())
+    }
+}
+
+impl Add<i64> for RationalStepRange {
+    type Output = RationalStepRange;
+    
+    fn add(self, k: i64) -> RationalStepRange {
+        RationalStepRange::new(self.start + k * self.den, self.step, self.den)
+    }
+}
+
+impl Mul<i64> for RationalStepRange {
+    type Output = RationalStepRange;
+    
+    fn mul(self, k: i64) -> RationalStepRange {
+        RationalStepRange::new(self.start * k, self.step * k, self.den)
+    }
+}
+
+impl Neg for RationalStepRange {
+    type Output = RationalStepRange;
+    
+    fn neg(self) -> RationalStepRange {
+        RationalStepRange::new(-self.start, -self.step, self.den)
+    }
+}
+
+/// k + range and k * range, as the ranges on the right
+macro_rules! commuted_range_ops {
+    ($($scalar:ty => $($range:ty),+;)+) => {
+        $($(
+            impl Add<$range> for $scalar {
+                type Output = <$range as Add<$scalar>>::Output;
+                
+                fn add(self, range: $range) -> Self::Output {
+                    range + self
+                }
+            }
+            
+            impl Mul<$range> for $scalar {
+                type Output = <$range as Mul<$scalar>>::Output;
+                
+                fn mul(self, range: $range) -> Self::Output {
+                    range * self
+                }
+            }
+        )+)+
+    };
+}
+
+commuted_range_ops! {
+    usize => OneToInf, InfUnitRange;
+    isize => InfStepRange;
+    i64 => RationalStepRange;
+}
+
+fn nonzero_factor(k: usize) {
+    if k == 0 {
+        panic!("step of an infinite range cannot be zero");
+    }
+}
+
+impl From<OneToInf> for InfStepRange {
+    fn from(_: OneToInf) -> Self {
+        InfStepRange::new(1, 1)
+    }
+}
+
+impl From<InfUnitRange> for InfStepRange {
+    fn from(range: InfUnitRange) -> Self {
+        InfStepRange::new(range.start as isize, 1)
+    }
+}
+
+// `step_by(k)` keeps the first value and then every k-th, like `Iterator::step_by`,
+// but returns a range built by composition, so `r.step_by(2).step_by(3)` is
+// `r.step_by(6)` rather than a nested iterator. A step of zero panics.
+
+impl OneToInf {
+    pub fn step_by(&self, k: usize) -> InfStepRange {
+        compose(*self, InfStepRange::new(0, k as isize))
+    }
+}
+
+impl InfUnitRange {
+    pub fn step_by(&self, k: usize) -> InfStepRange {
+        compose(*self, InfStepRange::new(0, k as isize))
+    }
+}
+
+impl InfStepRange {
+    pub fn step_by(&self, k: usize) -> InfStepRange {
+        compose(*self, InfStepRange::new(0, k as isize))
+    }
+}
+
+/// Common values of two ranges running in the same direction, or `None` if they
+/// share none
+///
+/// The intersection of two arithmetic progressions is again one, with step the lcm
+/// of the steps; its start is found by solving the two congruences (Chinese remainder
+/// theorem) and then moved past both starts. An ascending and a descending range
+/// share only finitely many values, which is not a range, so they give `None` too.
+pub fn intersect(a: impl Into<InfStepRange>, b: impl Into<InfStepRange>) -> Option<InfStepRange> {
+    let (a, b) = (a.into(), b.into());
+    match (a.is_ascending(), b.is_ascending()) {
+        (true, true) => {}
+        (false, false) => return intersect(-a, -b).map(Neg::neg),
+        _ => return None,
+    }
+    let (s, t) = (a.step() as i128, b.step() as i128);
+    let g = gcd(a.step() as i64, b.step() as i64) as i128;
+    let difference = b.start as i128 - a.start as i128;
+    if difference % g != 0 {
+        return None;
+    }
+    // a.start + s·k ≡ b.start (mod t)  ⇔  (s/g)·k ≡ difference/g (mod t/g)
+    let modulus = t / g;
+    let k = (difference / g).rem_euclid(modulus) * modular_inverse((s / g).rem_euclid(modulus), modulus) % modulus;
+    let step = s / g * t;
+    let mut start = a.start as i128 + s * k;
+    let lowest = a.start.max(b.start) as i128;
+    if start < lowest {
+        start += (lowest - start + step - 1) / step * step;
+    }
+    Some(InfStepRange::new(start as isize, step as isize))
+}
+
+/// The range a indexed by b: entry k is `a.get(b.get(k))`
+///
+/// The values of b are used as 0-based positions in a, so the result starts at
+/// a.get(b.start) and steps by a.step · b.step. Panics if b descends or starts
+/// below 0, as its values would leave the positions of a.
+pub fn compose(a: impl Into<InfStepRange>, b: impl Into<InfStepRange>) -> InfStepRange {
+    let (a, b) = (a.into(), b.into());
+    match usize::try_from(b.start) {
+        Ok(position) if b.is_ascending() => InfStepRange::new(a.get(position), a.step() * b.step()),
+        _ => panic!("{} does not index an infinite range", b),
+    }
+}
+
+/// Inverse of x modulo m for coprime x and m (0 when m = 1)
+fn modular_inverse(x: i128, m: i128) -> i128 {
+    let (mut old_r, mut r) = (x, m);
+    let (mut old_s, mut s) = (1i128, 0i128);
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_s, s) = (s, old_s - q * s);
+    }
+    old_s.rem_euclid(m)
+}
+
+/// Positions accepted by the `slice` methods of the ranges
+///
+/// Positions are 0-based like `get`: a finite window `a..b` gives the values at
+/// positions a, ..., b − 1, and an open window `a..` gives the infinite tail from
+/// position a as a range again.
+pub trait RangeSlice<R> {
+    type Output;
+    
+    fn slice_of(self, range: &R) -> Self::Output;
+}
+
+macro_rules! range_slice_methods {
+    ($($range:ty),+) => {
+        $(
+            impl $range {
+                /// Finite window (`a..b`) or infinite tail (`a..`) by 0-based position
+                pub fn slice<S: RangeSlice<Self>>(&self, positions: S) -> S::Output {
+                    positions.slice_of(self)
+                }
+                
+                /// Successive windows of `size` values: `slice(0..size)`, `slice(size..2 * size)`, ...
+                ///
+                /// Endless like the range itself. Panics if `size` is zero.
+                pub fn chunks(&self, size: usize) -> RangeChunks<Self> {
+                    if size == 0 {
+                        panic!("chunk size must be nonzero");
+                    }
+                    RangeChunks { range: *self, size, position: 0 }
+                }
+            }
+        )+
+    };
+}
+
+range_slice_methods!(OneToInf, InfUnitRange, InfStepRange);
+
+/// Iterator over the windows of a range, see e.g. [`InfUnitRange::chunks`]
+#[derive(Debug, Clone)]
+pub struct RangeChunks<R> {
+    range: R,
+    size: usize,
+    position: usize,
+}
+
+impl<R> Iterator for RangeChunks<R>
+where
+    Range<usize>: RangeSlice<R>,
+{
+    type Item = <Range<usize> as RangeSlice<R>>::Output;
+    
+    fn next(&mut self) -> Option<Self::Item> {
+        let start = self.position;
+        self.position += self.size;
+        Some((start..self.position).slice_of(&self.range))
+    }
+    
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (usize::MAX, None)
+    }
+}
+
+impl<R> FusedIterator for RangeChunks<R> where Range<usize>: RangeSlice<R> {}
+
+impl RangeSlice<OneToInf> for Range<usize> {
+    type Output = Range<usize>;
+    
+    fn slice_of(self, range: &OneToInf) -> Range<usize> {
+        range.get(self.start)..range.get(self.end.max(self.start))
+    }
+}
+
+impl RangeSlice<OneToInf> for RangeFrom<usize> {
+    type Output = InfUnitRange;
+    
+    fn slice_of(self, range: &OneToInf) -> InfUnitRange {
+        InfUnitRange::starting_at(range.get(self.start))
+    }
+}
+
+impl RangeSlice<InfUnitRange> for Range<usize> {
+    type Output = Range<usize>;
+    
+    fn slice_of(self, range: &InfUnitRange) -> Range<usize> {
+        range.get(self.start)..range.get(self.end.max(self.start))
+    }
+}
+
+impl RangeSlice<InfUnitRange> for RangeFrom<usize> {
+    type Output = InfUnitRange;
+    
+    fn slice_of(self, range: &InfUnitRange) -> InfUnitRange {
+        InfUnitRange::starting_at(range.get(self.start))
+    }
+}
+
+impl RangeSlice<InfStepRange> for Range<usize> {
+    type Output = Vec<isize>;
+    
+    fn slice_of(self, range: &InfStepRange) -> Vec<isize> {
+        self.map(|k| range.get(k)).collect()
+    }
+}
+
+impl RangeSlice<InfStepRange> for RangeFrom<usize> {
+    type Output = InfStepRange;
+    
+    fn slice_of(self, range: &InfStepRange) -> InfStepRange {
+        InfStepRange::new(range.get(self.start), range.step())
+    }
+}
+
+/// Pairs (a_i, b_j) of two ranges in diagonal (Cantor) order
+///
+/// The pairs are visited by increasing i + j and, within a diagonal, by increasing
+/// i: (a₀, b₀), (a₀, b₁), (a₁, b₀), (a₀, b₂), ... Every pair of two infinite ranges
+/// is reached after finitely many steps, which gives a total order for searching or
+/// sampling an infinite matrix. Finite iterators are accepted too; the enumeration
+/// then ends once all pairs have been produced.
+pub fn cartesian<A, B>(a: A, b: B) -> CantorIter<A::IntoIter, B::IntoIter>
+where
+    A: IntoIterator,
+    B: IntoIterator,
+    A::Item: Clone,
+    B::Item: Clone,
+{
+    CantorIter {
+        a: Prefix::new(a.into_iter()),
+        b: Prefix::new(b.into_iter()),
+        diagonal: 0,
+        offset: 0,
+    }
+}
+
+/// Iterator returned by [`cartesian`]
+pub struct CantorIter<A: Iterator, B: Iterator> {
+    a: Prefix<A>,
+    b: Prefix<B>,
+    diagonal: usize,
+    offset: usize,
+}
+
+impl<A, B> Iterator for CantorIter<A, B>
+where
+    A: Iterator,
+    B: Iterator,
+    A::Item: Clone,
+    B::Item: Clone,
+{
+    type Item = (A::Item, B::Item);
+    
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            // Positions (i, diagonal − i) that can exist given the known lengths
+            let first = self.b.len.map_or(0, |n| (self.diagonal + 1).saturating_sub(n));
+            let last = self.a.len.map_or(self.diagonal, |m| self.diagonal.min(m.saturating_sub(1)));
+            if self.a.len == Some(0) || self.b.len == Some(0) {
+                return None;
+            }
+            if let (Some(m), Some(n)) = (self.a.len, self.b.len) {
+                if self.diagonal > m + n - 2 {
+                    return None;
+                }
+            }
+            let i = self.offset.max(first);
+            if i > last {
+                self.diagonal += 1;
+                self.offset = 0;
+                continue;
+            }
+            self.offset = i + 1;
+            let j = self.diagonal - i;
+            if let (Some(x), Some(y)) = (self.a.get(i), self.b.get(j)) {
+                return Some((x, y));
+            }
+        }
+    }
+}
+
+impl<A, B> FusedIterator for CantorIter<A, B>
+where
+    A: Iterator,
+    B: Iterator,
+    A::Item: Clone,
+    B::Item: Clone,
+{
+}
+
+/// Values of an iterator read so far, with its length once it is exhausted
+struct Prefix<I: Iterator> {
+    iter: I,
+    values: Vec<I::Item>,
+    len: Option<usize>,
+}
+
+impl<I: Iterator> Prefix<I>
+where
+    I::Item: Clone,
+{
+    fn new(iter: I) -> Self {
+        Prefix { iter, values: Vec::new(), len: None }
+    }
+    
+    fn get(&mut self, position: usize) -> Option<I::Item> {
+        while self.values.len() <= position && self.len.is_none() {
+            match self.iter.next() {
+                Some(value) => self.values.push(value),
+                None => self.len = Some(self.values.len()),
+            }
+        }
+        self.values.get(position).cloned()
+    }
+}
+
+// Julia-style rendering: `start:∞` for unit ranges, `start:step:∞` otherwise, with
+// −∞ as the end of descending ranges.
+
+impl fmt::Display for OneToInf {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "1:{}", Infinity)
+    }
+}
+
+impl fmt::Display for InfUnitRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.start, Infinity)
+    }
+}
+
+impl fmt::Display for InfStepRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}:{}", self.start, self.step, self.limit())
+    }
+}
+
+impl fmt::Display for NegInfRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.start(), self.end)
+    }
+}
+
+impl fmt::Display for FullZRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.start(), self.end())
+    }
+}
+
+/// Fractions in lowest terms, e.g. `1/6:1/3:∞`
+impl fmt::Display for RationalStepRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let fraction = |(num, den): (i64, i64)| if den == 1 { num.to_string() } else { format!("{}/{}", num, den) };
+        let end = if self.step > 0 { SignedInfinity::Positive } else { SignedInfinity::Negative };
+        write!(f, "{}:{}:{}", fraction(self.start()), fraction(self.step()), end)
+    }
+}
+
+// Canonical forms: `OneToInf`, `InfUnitRange` and `InfStepRange` describe the same
+// kind of set, so each has an `InfStepRange` form and ranges of different types
+// compare equal when their forms do. Within a type, equality, hashing and ordering
+// use the same fields, so every range type can key a `HashMap` or `BTreeMap`.
+
+impl OneToInf {
+    /// The range as the `InfStepRange` 1:1:∞
+    pub fn canonical(&self) -> InfStepRange {
+        InfStepRange::from(*self)
+    }
+}
+
+impl InfUnitRange {
+    /// The range as an `InfStepRange` with step 1
+    pub fn canonical(&self) -> InfStepRange {
+        InfStepRange::from(*self)
+    }
+}
+
+impl InfStepRange {
+    /// The simplest type describing the range: `OneToInf` or `InfUnitRange` when the
+    /// step is 1, the range itself otherwise
+    pub fn simplify(&self) -> CanonicalRange {
+        match (self.start, self.step()) {
+            (1, 1) => CanonicalRange::OneToInf(OneToInf),
+            (start, 1) if start >= 0 => CanonicalRange::Unit(InfUnitRange::starting_at(start as usize)),
+            _ => CanonicalRange::Step(*self),
+        }
+    }
+}
+
+/// Result of [`InfStepRange::simplify`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum CanonicalRange {
+    OneToInf(OneToInf),
+    Unit(InfUnitRange),
+    Step(InfStepRange),
+}
+
+macro_rules! canonical_equality {
+    ($($a:ty, $b:ty);+) => {
+        $(
+            impl PartialEq<$b> for $a {
+                fn eq(&self, other: &$b) -> bool {
+                    InfStepRange::from(*self) == InfStepRange::from(*other)
+                }
+            }
+            
+            impl PartialEq<$a> for $b {
+                fn eq(&self, other: &$a) -> bool {
+                    other == self
+                }
+            }
+        )+
+    };
+}
+
+canonical_equality!(OneToInf, InfUnitRange; OneToInf, InfStepRange; InfUnitRange, InfStepRange);
+
+impl PartialOrd for RationalStepRange {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Ordered by the exact value of the start and then of the step
+impl Ord for RationalStepRange {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        // Denominators are positive, so cross-multiplying preserves the order
+        let scaled = |num: i64, den: i64| num as i128 * den as i128;
+        scaled(self.start, other.den)
+            .cmp(&scaled(other.start, self.den))
+            .then(scaled(self.step, other.den).cmp(&scaled(other.step, self.den)))
+    }
+}
+
+macro_rules! range_lengths {
+    ($($range:ty),+) => {
+        $(
+            impl $range {
+                /// Number of values, always [`Size::Infinite`]
+                pub fn len(&self) -> Size {
+                    Size::Infinite
+                }
+                
+                /// Always false
+                pub fn is_empty(&self) -> bool {
+                    false
+                }
+            }
+        )+
+    };
+}
+
+range_lengths!(OneToInf, InfUnitRange, InfStepRange, NegInfRange, FullZRange, RationalStepRange);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn step_range_descends_with_a_negative_step() {
+        let range = InfStepRange::new(3, -2);
+        assert_eq!(range.iter().take(4).collect::<Vec<_>>(), [3, 1, -1, -3]);
+        assert!(range.contains(-5) && !range.contains(5) && !range.contains(0));
+        assert_eq!(range.index(-3), 3);
+        assert_eq!(range.limit(), SignedInfinity::Negative);
+        assert_eq!(-range, InfStepRange::new(-3, 2));
+        assert_eq!(range * -1, InfStepRange::new(-3, 2));
+        assert_eq!(range.to_string(), "3:-2:-∞");
+        assert_eq!(-OneToInf, InfStepRange::descending(-1));
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn deprecated_unit_range_constructor_keeps_the_step() {
+        assert_eq!(InfUnitRange::new(2, 3), InfStepRange::new(2, 3));
+        assert_eq!(InfUnitRange::new(2, 3).iter().take(3).collect::<Vec<_>>(), [2, 5, 8]);
+        assert_eq!(InfUnitRange::try_from(InfUnitRange::new(2, 1)), Ok(InfUnitRange::starting_at(2)));
+    }
+
+    #[test]
+    fn intersect_follows_the_direction_of_the_ranges() {
+        assert_eq!(intersect(InfStepRange::new(0, 2), InfStepRange::new(1, 3)), Some(InfStepRange::new(4, 6)));
+        assert_eq!(intersect(InfStepRange::new(0, -2), InfStepRange::new(-1, -3)), Some(InfStepRange::new(-4, -6)));
+        assert_eq!(intersect(InfStepRange::new(0, 2), InfStepRange::new(10, -2)), None);
+    }
+
+    #[test]
+    fn range_arithmetic() {
+        assert_eq!(OneToInf + 2, InfUnitRange::starting_at(3));
+        assert_eq!(OneToInf * 3, InfStepRange::new(3, 3));
+        assert_eq!(2 * InfUnitRange::starting_at(1), InfStepRange::new(2, 2));
+        assert_eq!(-InfUnitRange::starting_at(2), InfStepRange::new(-2, -1));
+        assert_eq!(InfStepRange::new(1, 2) + -4, InfStepRange::new(-3, 2));
+        assert_eq!(-3 * InfStepRange::new(1, 2), InfStepRange::new(-3, -6));
+        assert_eq!(-RationalStepRange::new(1, 2, 4), RationalStepRange::new(-1, -2, 4));
+        assert_eq!(RationalStepRange::new(1, 2, 6).get_exact(1), (1, 2));
+        assert_eq!(RationalStepRange::new(1, 2, 6) + 1, RationalStepRange::new(7, 2, 6));
+    }
+
+    #[test]
+    fn intersect_edge_cases() {
+        // No common values, or common values past both starts
+        assert_eq!(intersect(InfStepRange::new(0, 2), InfStepRange::new(1, 2)), None);
+        assert_eq!(intersect(InfStepRange::new(0, 4), InfStepRange::new(2, 6)), Some(InfStepRange::new(8, 12)));
+        // One range inside the other
+        assert_eq!(intersect(OneToInf, InfStepRange::new(4, 3)), Some(InfStepRange::new(4, 3)));
+        // The common start lies past the larger start
+        assert_eq!(intersect(InfUnitRange::starting_at(100), InfStepRange::new(0, 7)), Some(InfStepRange::new(105, 7)));
+        assert_eq!(intersect(InfStepRange::new(3, 5), InfStepRange::new(3, 5)), Some(InfStepRange::new(3, 5)));
+    }
+
+    #[test]
+    fn compose_indexes_by_position() {
+        // Positions are 0-based: OneToInf at 0, 2, 4, ... is 1, 3, 5, ...
+        assert_eq!(compose(OneToInf, InfStepRange::new(0, 2)), InfStepRange::new(1, 2));
+        assert_eq!(compose(InfStepRange::new(5, -1), InfStepRange::new(2, 3)), InfStepRange::new(3, -3));
+        assert_eq!(compose(InfStepRange::new(1, 2), OneToInf), InfStepRange::new(3, 2));
+        assert_eq!(OneToInf.step_by(2).step_by(3), InfStepRange::new(1, 6));
+        let range = InfStepRange::new(7, -2);
+        assert_eq!(range.step_by(2), InfStepRange::new(7, -4));
+    }
+
+    #[test]
+    fn slices() {
+        let range = InfStepRange::new(2, 3);
+        assert_eq!(range.slice(1..4), [5, 8, 11]);
+        assert_eq!(range.slice(2..), InfStepRange::new(8, 3));
+        assert_eq!(InfUnitRange::starting_at(5).slice(1..3), 6..8);
+    }
+
+    #[test]
+    fn try_index_reports_values_outside_the_range() {
+        let range = InfStepRange::new(2, 3);
+        assert_eq!(range.try_index(11), Ok(3));
+        assert!(range.try_index(10).is_err());
+        assert!(range.try_index(-1).is_err());
+        assert_eq!(OneToInf.try_index(0).unwrap_err().value, 0);
+    }
+
+    #[test]
+    fn ranges_toward_negative_infinity_iterate() {
+        assert_eq!(NegInfRange::new(2).iter().take(3).collect::<Vec<_>>(), [2, 1, 0]);
+        assert_eq!(FullZRange.iter().take(5).collect::<Vec<_>>(), [0, 1, -1, 2, -2]);
+    }
+
+    #[test]
+    fn chunks_are_consecutive_blocks() {
+        assert_eq!(OneToInf.chunks(2).nth(1), Some(3..5));
+    }
+
+    #[test]
+    fn ranges_of_different_types_compare_by_their_values() {
+        assert_eq!(InfStepRange::new(1, 1), OneToInf);
+        assert_eq!(InfUnitRange::starting_at(1), OneToInf);
+        assert_ne!(InfStepRange::new(1, 2), OneToInf);
+        assert_eq!(InfStepRange::new(4, 1).simplify(), CanonicalRange::Unit(InfUnitRange::starting_at(4)));
+        assert_eq!(InfStepRange::new(-4, 1).simplify(), CanonicalRange::Step(InfStepRange::new(-4, 1)));
+        assert_eq!(InfUnitRange::try_from(InfStepRange::new(2, 1)), Ok(InfUnitRange::starting_at(2)));
+        assert!(InfUnitRange::try_from(InfStepRange::new(-2, 1)).is_err());
+    }
+
+    #[test]
+    fn cartesian_of_two_infinite_ranges_walks_the_diagonals() {
+        let pairs: Vec<_> = cartesian(OneToInf, OneToInf).take(6).collect();
+        assert_eq!(pairs, [(1, 1), (1, 2), (2, 1), (1, 3), (2, 2), (3, 1)]);
+    }
+
+    #[test]
+    fn cartesian_of_finite_ranges_yields_every_pair_once() {
+        let mut pairs = cartesian(0..3, 0..4);
+        let mut seen: Vec<_> = pairs.by_ref().collect();
+        assert_eq!(seen.len(), 12);
+        assert_eq!(pairs.next(), None);
+        assert_eq!(pairs.next(), None);
+        seen.sort_unstable();
+        seen.dedup();
+        assert_eq!(seen.len(), 12);
+        assert!(seen.iter().all(|&(i, j)| i < 3 && j < 4));
+    }
+
+    #[test]
+    fn cartesian_with_one_finite_factor_stays_inside_it() {
+        let pairs: Vec<_> = cartesian(0..2, 0..).take(7).collect();
+        assert_eq!(pairs, [(0, 0), (0, 1), (1, 0), (0, 2), (1, 1), (0, 3), (1, 2)]);
+        let pairs: Vec<_> = cartesian(0.., 0..2).take(7).collect();
+        assert_eq!(pairs, [(0, 0), (0, 1), (1, 0), (1, 1), (2, 0), (2, 1), (3, 0)]);
+    }
+
+    #[test]
+    fn cartesian_with_an_empty_factor_is_empty() {
+        assert_eq!(cartesian(0..0, OneToInf).next(), None);
+        assert_eq!(cartesian(OneToInf, 0..0).next(), None);
+        assert_eq!(cartesian(0..0, 0..3).next(), None);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn ranges_survive_a_json_round_trip() {
+        let json = serde_json::to_string(&OneToInf).unwrap();
+        assert_eq!(serde_json::from_str::<OneToInf>(&json).unwrap(), OneToInf);
+        let unit = InfUnitRange::starting_at(4);
+        let json = serde_json::to_string(&unit).unwrap();
+        assert_eq!(serde_json::from_str::<InfUnitRange>(&json).unwrap(), unit);
+        let descending = InfStepRange::new(3, -2);
+        let json = serde_json::to_string(&descending).unwrap();
+        assert_eq!(json, r#"{"start":3,"step":-2}"#);
+        assert_eq!(serde_json::from_str::<InfStepRange>(&json).unwrap(), descending);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn a_step_range_with_step_zero_does_not_deserialize() {
+        assert!(serde_json::from_str::<InfStepRange>(r#"{"start":3,"step":0}"#).is_err());
+    }
+}
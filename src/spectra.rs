@@ -0,0 +1,191 @@
+//! Comparison of eigenvalue sets.
+//!
+//! Spectra computed at different truncation sizes or parameter values are compared
+//! either as sets, through the Hausdorff distance, or pair by pair after an optimal
+//! matching. The adaptive IQR driver uses the matching to decide when the leading
+//! eigenvalues have settled.
+
+use crate::display;
+use crate::linalg;
+use ndarray::Array2;
+use num_complex::Complex64;
+use std::fmt;
+
+/// Distance from a point to the nearest element of a finite set (infinite for an empty set)
+pub fn distance_to_set<'a>(z: Complex64, set: impl IntoIterator<Item = &'a Complex64>) -> f64 {
+    set.into_iter().map(|&mu| (z - mu).norm()).fold(f64::INFINITY, f64::min)
+}
+
+/// Hausdorff distance max(sup_{a∈A} d(a, B), sup_{b∈B} d(b, A)) between two finite sets
+///
+/// Zero for two empty sets and infinite when only one is empty. Unlike a matched
+/// comparison it ignores multiplicities, so it is the natural measure of spectral
+/// convergence where eigenvalues accumulate.
+pub fn hausdorff_distance(a: &[Complex64], b: &[Complex64]) -> f64 {
+    if a.is_empty() && b.is_empty() {
+        return 0.0;
+    }
+    let one_sided = |from: &[Complex64], to: &[Complex64]| {
+        from.iter().map(|&z| distance_to_set(z, to)).fold(0.0, f64::max)
+    };
+    one_sided(a, b).max(one_sided(b, a))
+}
+
+/// Pair each eigenvalue in `other` with a distinct eigenvalue in `reference`
+///
+/// The pairing minimises the total distance (Hungarian assignment); entry i is the
+/// index in `reference` of the partner of `other[i]`, or `None` for surplus
+/// eigenvalues when `other` is the larger set.
+pub fn match_eigenvalues(reference: &[Complex64], other: &[Complex64]) -> Vec<Option<usize>> {
+    let cost = Array2::from_shape_fn((other.len(), reference.len()), |(i, j)| (other[i] - reference[j]).norm());
+    linalg::hungarian(&cost)
+}
+
+/// Two eigenvalues paired by [`compare_spectra`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MatchedPair {
+    /// Index in `other`
+    pub index: usize,
+    /// Index of the partner in `reference`
+    pub reference_index: usize,
+    pub value: Complex64,
+    pub reference: Complex64,
+    /// |value − reference|
+    pub difference: f64,
+}
+
+/// Pairwise comparison of two eigenvalue sets
+#[derive(Debug, Clone)]
+pub struct SpectrumComparison {
+    /// Matched pairs in the order of `other`
+    pub pairs: Vec<MatchedPair>,
+    /// Indices into `other` left without a partner
+    pub unmatched: Vec<usize>,
+    /// Indices into `reference` left without a partner
+    pub unmatched_reference: Vec<usize>,
+    pub hausdorff_distance: f64,
+}
+
+impl SpectrumComparison {
+    /// Largest difference over the matched pairs (zero if there are none)
+    pub fn max_difference(&self) -> f64 {
+        self.pairs.iter().map(|pair| pair.difference).fold(0.0, f64::max)
+    }
+
+    /// Difference of each entry of `other` from its partner, infinite when unmatched
+    pub fn differences(&self) -> Vec<f64> {
+        let len = self.pairs.len() + self.unmatched.len();
+        let mut differences = vec![f64::INFINITY; len];
+        for pair in &self.pairs {
+            differences[pair.index] = pair.difference;
+        }
+        differences
+    }
+}
+
+/// Match `other` against `reference` and report the differences of the pairs
+pub fn compare_spectra(reference: &[Complex64], other: &[Complex64]) -> SpectrumComparison {
+    let mut used = vec![false; reference.len()];
+    let mut pairs = Vec::new();
+    let mut unmatched = Vec::new();
+    for (index, partner) in match_eigenvalues(reference, other).into_iter().enumerate() {
+        match partner {
+            Some(reference_index) => {
+                used[reference_index] = true;
+                pairs.push(MatchedPair {
+                    index,
+                    reference_index,
+                    value: other[index],
+                    reference: reference[reference_index],
+                    difference: (other[index] - reference[reference_index]).norm(),
+                });
+            }
+            None => unmatched.push(index),
+        }
+    }
+    SpectrumComparison {
+        pairs,
+        unmatched,
+        unmatched_reference: (0..reference.len()).filter(|&j| !used[j]).collect(),
+        hausdorff_distance: hausdorff_distance(reference, other),
+    }
+}
+
+/// Summary line followed by one line per matched pair and per unmatched eigenvalue;
+/// the precision (default 6) applies to the eigenvalues
+impl fmt::Display for SpectrumComparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let precision = f.precision().unwrap_or(6);
+        write!(
+            f,
+            "SpectrumComparison: {} pairs, max difference {:.3e}, Hausdorff distance {:.3e}",
+            self.pairs.len(),
+            self.max_difference(),
+            self.hausdorff_distance
+        )?;
+        for pair in &self.pairs {
+            write!(
+                f,
+                "\n  [{}] {:.*} {} [{}] {:.*}  {} = {:.3e}",
+                pair.index,
+                precision,
+                pair.value,
+                display::matched(),
+                pair.reference_index,
+                precision,
+                pair.reference,
+                display::delta(),
+                pair.difference
+            )?;
+        }
+        for &index in &self.unmatched {
+            write!(f, "\n  [{}] unmatched", index)?;
+        }
+        for &index in &self.unmatched_reference {
+            write!(f, "\n  reference [{}] unmatched", index)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::real;
+
+    #[test]
+    fn hausdorff_distance_takes_both_directions() {
+        let a = [real(0.0), real(1.0)];
+        let b = [real(0.1), real(1.0), real(5.0)];
+        assert!((hausdorff_distance(&a, &b) - 4.0).abs() < 1e-12);
+        assert_eq!(hausdorff_distance(&[], &[]), 0.0);
+        assert_eq!(hausdorff_distance(&a, &[]), f64::INFINITY);
+        assert_eq!(distance_to_set(real(0.5), &a), 0.5);
+    }
+
+    #[test]
+    fn matching_minimises_the_total_distance() {
+        // Greedy nearest-first pairing would give 0.45 ↔ 0 and leave 0.1 with 1
+        let reference = [real(0.0), real(1.0)];
+        assert_eq!(match_eigenvalues(&reference, &[real(0.45), real(0.1)]), [Some(1), Some(0)]);
+
+        let comparison = compare_spectra(&[real(0.0), real(1.0), real(2.0)], &[real(1.01)]);
+        assert_eq!(comparison.pairs.len(), 1);
+        assert_eq!(comparison.pairs[0].reference_index, 1);
+        assert_eq!(comparison.unmatched_reference, [0, 2]);
+        assert!((comparison.max_difference() - 0.01).abs() < 1e-12);
+
+        let comparison = compare_spectra(&[real(0.0)], &[real(0.1), real(3.0)]);
+        assert_eq!(comparison.unmatched, [1]);
+        assert_eq!(comparison.differences()[1], f64::INFINITY);
+    }
+
+    #[test]
+    fn comparison_display_is_plain_ascii_under_ascii() {
+        let comparison = compare_spectra(&[real(0.0), real(1.0)], &[real(1.01)]);
+        assert!(comparison.to_string().contains('↔'));
+        let ascii = display::Ascii(&comparison).to_string();
+        assert!(ascii.is_ascii());
+        assert!(ascii.contains("<->") && ascii.contains("delta = "));
+    }
+}
@@ -0,0 +1,181 @@
+//! Infinite arrays as async streams, available with the `stream` feature.
+//!
+//! [`ArrayStream`] yields the entries of an [`InfiniteArray`] one at a time and
+//! [`ChunkStream`] in blocks, for feeding async pipelines such as websocket plots or
+//! progressively refined UIs. Streams are pulled, so a slow consumer simply polls
+//! less often and nothing is evaluated ahead of demand. Entries are computed inside
+//! `poll_next`; [`ArrayStream::yield_every`] hands control back to the executor
+//! periodically when they are expensive.
+
+use crate::arrays::InfiniteArray;
+use futures_core::Stream;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+/// Endless stream of `array.get(k)` for k = start, start + 1, ...
+pub struct ArrayStream {
+    array: Arc<dyn InfiniteArray>,
+    position: usize,
+    yield_every: Option<usize>,
+    since_yield: usize,
+}
+
+impl ArrayStream {
+    pub fn new(array: Arc<dyn InfiniteArray>) -> Self {
+        ArrayStream::starting_at(array, 0)
+    }
+
+    /// Stream beginning at 0-based position `start`
+    pub fn starting_at(array: Arc<dyn InfiniteArray>, start: usize) -> Self {
+        ArrayStream {
+            array,
+            position: start,
+            yield_every: None,
+            since_yield: 0,
+        }
+    }
+
+    /// Return `Pending` (after waking the task) once every `n` entries
+    ///
+    /// Lets other tasks on a single-threaded executor run between expensive entries.
+    pub fn yield_every(mut self, n: usize) -> Self {
+        assert!(n > 0, "yield_every needs a positive count");
+        self.yield_every = Some(n);
+        self
+    }
+
+    /// Position of the next entry
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Stream of blocks of `k` consecutive entries
+    pub fn chunks(self, k: usize) -> ChunkStream {
+        assert!(k > 0, "chunks needs a positive block length");
+        ChunkStream { stream: self, k }
+    }
+
+    /// Whether to yield before the next entry, resetting the count if so
+    fn take_yield(&mut self) -> bool {
+        match self.yield_every {
+            Some(n) if self.since_yield >= n => {
+                self.since_yield = 0;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+impl Stream for ArrayStream {
+    type Item = f64;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<f64>> {
+        let this = self.get_mut();
+        if this.take_yield() {
+            cx.waker().wake_by_ref();
+            return Poll::Pending;
+        }
+        let value = this.array.get(this.position);
+        this.position += 1;
+        this.since_yield += 1;
+        Poll::Ready(Some(value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (usize::MAX, None)
+    }
+}
+
+/// Endless stream of blocks of `k` consecutive entries, see [`ArrayStream::chunks`]
+///
+/// A block is computed in one poll; with `yield_every` set on the underlying stream
+/// the task yields between blocks once that many entries have been produced.
+pub struct ChunkStream {
+    stream: ArrayStream,
+    k: usize,
+}
+
+impl Stream for ChunkStream {
+    type Item = Vec<f64>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Vec<f64>>> {
+        let this = self.get_mut();
+        let stream = &mut this.stream;
+        if stream.take_yield() {
+            cx.waker().wake_by_ref();
+            return Poll::Pending;
+        }
+        let start = stream.position;
+        let block = (start..start + this.k).map(|i| stream.array.get(i)).collect();
+        stream.position += this.k;
+        stream.since_yield += this.k;
+        Poll::Ready(Some(block))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (usize::MAX, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arrays::Shape;
+    use crate::broadcasting::BroadcastArray;
+    use crate::infinity::Size;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::task::{Wake, Waker};
+
+    /// Waker counting how often it is woken
+    #[derive(Default)]
+    struct CountingWaker(AtomicUsize);
+
+    impl Wake for CountingWaker {
+        fn wake(self: Arc<Self>) {
+            self.wake_by_ref();
+        }
+
+        fn wake_by_ref(self: &Arc<Self>) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    /// The array k ↦ k
+    fn naturals() -> Arc<dyn InfiniteArray> {
+        Arc::new(BroadcastArray::new(|k| k as f64, Shape::OneD(Size::Infinite)))
+    }
+
+    fn poll<S: Stream + Unpin>(stream: &mut S, cx: &mut Context<'_>) -> Poll<Option<S::Item>> {
+        Pin::new(stream).poll_next(cx)
+    }
+
+    #[test]
+    fn yield_every_returns_pending_after_n_items() {
+        let counter = Arc::new(CountingWaker::default());
+        let waker = Waker::from(counter.clone());
+        let mut cx = Context::from_waker(&waker);
+        let mut stream = ArrayStream::starting_at(naturals(), 5).yield_every(2);
+        assert_eq!(poll(&mut stream, &mut cx), Poll::Ready(Some(5.0)));
+        assert_eq!(poll(&mut stream, &mut cx), Poll::Ready(Some(6.0)));
+        assert_eq!(poll(&mut stream, &mut cx), Poll::Pending);
+        // The task is woken so the executor polls it again
+        assert_eq!(counter.0.load(Ordering::SeqCst), 1);
+        assert_eq!(stream.position(), 7);
+        assert_eq!(poll(&mut stream, &mut cx), Poll::Ready(Some(7.0)));
+        assert_eq!(poll(&mut stream, &mut cx), Poll::Ready(Some(8.0)));
+        assert_eq!(poll(&mut stream, &mut cx), Poll::Pending);
+        assert_eq!(counter.0.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn chunks_yields_consecutive_blocks() {
+        let waker = Waker::from(Arc::new(CountingWaker::default()));
+        let mut cx = Context::from_waker(&waker);
+        let mut chunks = ArrayStream::starting_at(naturals(), 1).chunks(3);
+        assert_eq!(poll(&mut chunks, &mut cx), Poll::Ready(Some(vec![1.0, 2.0, 3.0])));
+        assert_eq!(poll(&mut chunks, &mut cx), Poll::Ready(Some(vec![4.0, 5.0, 6.0])));
+        assert_eq!(chunks.size_hint(), (usize::MAX, None));
+    }
+}
@@ -0,0 +1,126 @@
+//! Locks behind the lazy caches.
+//!
+//! With `std` on targets with threads the caches are guarded by `std::sync::Mutex`.
+//! On single-threaded WebAssembly (wasm32 without the `atomics` target feature, as
+//! for browsers and WASI) no second thread can exist, so a [`RefCell`] does the same
+//! job without the locking machinery. Other `no_std` targets use a spin lock.
+//!
+//! All three implement [`LockValues`], so call sites do not depend on the choice;
+//! the caches behind it have no use for poisoning. The `Mutex` and the cell also
+//! expose `lock() -> Result<guard, _>` for the state of the `std`-only solvers.
+//!
+//! [`RefCell`]: core::cell::RefCell
+
+#[cfg(all(feature = "std", not(all(target_arch = "wasm32", not(target_feature = "atomics")))))]
+pub(crate) use std::sync::Mutex as Lock;
+
+#[cfg(all(target_arch = "wasm32", not(target_feature = "atomics")))]
+pub(crate) use single_threaded::Lock;
+
+#[cfg(all(not(feature = "std"), not(all(target_arch = "wasm32", not(target_feature = "atomics")))))]
+pub(crate) use spinning::Lock;
+
+use core::ops::DerefMut;
+
+/// Locking that ignores poisoning
+///
+/// The caches only ever hold finished values, inserted after they are computed, so
+/// a panic on another thread cannot leave one half-updated and a poisoned cache is
+/// as good as any other.
+pub(crate) trait LockValues<T> {
+    type Guard<'a>: DerefMut<Target = T>
+    where
+        Self: 'a;
+
+    fn lock_values(&self) -> Self::Guard<'_>;
+}
+
+#[cfg(all(feature = "std", not(all(target_arch = "wasm32", not(target_feature = "atomics")))))]
+impl<T> LockValues<T> for Lock<T> {
+    type Guard<'a> = std::sync::MutexGuard<'a, T> where T: 'a;
+
+    fn lock_values(&self) -> Self::Guard<'_> {
+        self.lock().unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+#[cfg(all(target_arch = "wasm32", not(target_feature = "atomics")))]
+mod single_threaded {
+    use core::cell::{RefCell, RefMut};
+    use core::convert::Infallible;
+
+    /// `Mutex` look-alike for a target without threads; never poisoned
+    #[derive(Debug, Default)]
+    pub(crate) struct Lock<T>(RefCell<T>);
+
+    // SAFETY: without the `atomics` target feature wasm32 runs a single thread, so
+    // the cell is never accessed concurrently.
+    unsafe impl<T: Send> Sync for Lock<T> {}
+
+    impl<T> Lock<T> {
+        pub(crate) fn new(value: T) -> Self {
+            Lock(RefCell::new(value))
+        }
+
+        pub(crate) fn lock(&self) -> Result<RefMut<'_, T>, Infallible> {
+            Ok(self.0.borrow_mut())
+        }
+    }
+
+    impl<T> super::LockValues<T> for Lock<T> {
+        type Guard<'a> = RefMut<'a, T> where T: 'a;
+
+        fn lock_values(&self) -> RefMut<'_, T> {
+            self.0.borrow_mut()
+        }
+    }
+}
+
+#[cfg(all(not(feature = "std"), not(all(target_arch = "wasm32", not(target_feature = "atomics")))))]
+mod spinning {
+    use spin::mutex::{SpinMutex, SpinMutexGuard};
+
+    /// `Mutex` look-alike over a spin lock; never poisoned
+    #[derive(Debug, Default)]
+    pub(crate) struct Lock<T>(SpinMutex<T>);
+
+    impl<T> Lock<T> {
+        pub(crate) fn new(value: T) -> Self {
+            Lock(SpinMutex::new(value))
+        }
+    }
+
+    impl<T> super::LockValues<T> for Lock<T> {
+        type Guard<'a> = SpinMutexGuard<'a, T> where T: 'a;
+
+        fn lock_values(&self) -> SpinMutexGuard<'_, T> {
+            self.0.lock()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Lock, LockValues};
+
+    #[test]
+    fn lock_values_hands_out_the_guarded_value() {
+        let lock = Lock::new(1);
+        *lock.lock_values() += 1;
+        assert_eq!(*lock.lock_values(), 2);
+    }
+
+    #[test]
+    #[cfg(all(feature = "std", not(target_arch = "wasm32")))]
+    fn lock_values_recovers_a_poisoned_lock() {
+        let lock = std::sync::Arc::new(Lock::new(1));
+        let poisoner = lock.clone();
+        let _ = std::thread::spawn(move || {
+            let _guard = poisoner.lock().unwrap();
+            panic!("poison the lock");
+        })
+        .join();
+        assert!(lock.lock().is_err());
+        assert_eq!(*lock.lock_values(), 1);
+    }
+}
@@ -0,0 +1,25 @@
+//! Operators and helpers shared by the unit tests
+
+use num_complex::Complex64;
+
+use crate::iqr::InfiniteOperator;
+
+pub(crate) fn real(x: f64) -> Complex64 {
+    Complex64::new(x, 0.0)
+}
+
+/// A = tridiag(−1, 3, −1), positive definite with spectrum [1, 5]
+pub(crate) fn tridiagonal() -> InfiniteOperator {
+    crate::banded_operator! { -1 => |_| -1.0, 0 => |_| 3.0, 1 => |_| -1.0 }
+}
+
+/// Solution of A x = e₀ for [`tridiagonal`]: x_k = ρᵏ / (3 − ρ), ρ = (3 − √5)/2
+pub(crate) fn tridiagonal_solution(k: usize) -> f64 {
+    let rho = (3.0 - 5f64.sqrt()) / 2.0;
+    rho.powi(k as i32) / (3.0 - rho)
+}
+
+/// tridiag(1, −2, 1), with spectrum [−4, 0]
+pub(crate) fn laplacian() -> InfiniteOperator {
+    crate::banded_operator! { -1 => |_| 1.0, 0 => |_| -2.0, 1 => |_| 1.0 }
+}
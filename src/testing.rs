@@ -0,0 +1,177 @@
+//! `proptest` strategies for infinite arrays and operators, available with the
+//! `proptest` feature.
+//!
+//! The strategies generate the structured arrays of this crate ([`Fill`],
+//! [`BroadcastArray`], [`Vcat`]) and random banded and off-diagonally decaying
+//! operators, so downstream code built on [`InfiniteArray`] and [`LinearOperator`]
+//! can be property-tested. [`arrays_agree`] and [`operators_agree`] compare two
+//! arrays or operators on a finite section and fail the test case on the first
+//! mismatch, so they can be used with `?` inside `proptest!`.
+
+use crate::arrays::{Fill, InfiniteArray, Shape, Vcat};
+use crate::broadcasting::BroadcastArray;
+use crate::infinity::Size;
+use crate::iqr::InfiniteOperator;
+use crate::operators::{BandedOperator, LinearOperator};
+use num_complex::Complex64;
+use proptest::collection::vec;
+use proptest::prelude::*;
+use proptest::test_runner::TestCaseError;
+use std::sync::Arc;
+
+/// Largest magnitude of the generated entries and coefficients
+const MAX_VALUE: f64 = 1e3;
+
+/// Longest generated head of a [`Vcat`]
+const MAX_HEAD: usize = 16;
+
+fn value() -> impl Strategy<Value = f64> {
+    -MAX_VALUE..MAX_VALUE
+}
+
+fn complex() -> impl Strategy<Value = Complex64> {
+    (value(), value()).prop_map(|(re, im)| Complex64::new(re, im))
+}
+
+/// Infinite vectors with a constant value
+pub fn fill() -> impl Strategy<Value = Fill> {
+    value().prop_map(|value| Fill::new(value, None))
+}
+
+/// Infinite vectors `a + b·k + c·r^k` with |r| < 1, evaluated lazily
+pub fn broadcast() -> impl Strategy<Value = BroadcastArray> {
+    (value(), value(), value(), -0.99..0.99f64).prop_map(|(a, b, c, r)| {
+        BroadcastArray::new(move |k| a + b * k as f64 + c * r.powi(k as i32), Shape::OneD(Size::Infinite))
+    })
+}
+
+/// A finite head of up to 16 entries followed by a [`fill`] or [`broadcast`] tail
+pub fn vcat() -> impl Strategy<Value = Vcat> {
+    let tail = prop_oneof![fill().prop_map(Tail::Fill), broadcast().prop_map(Tail::Broadcast)];
+    (vec(value(), 0..=MAX_HEAD), tail).prop_map(|(head, tail)| {
+        let tail: Arc<dyn InfiniteArray> = match tail {
+            Tail::Fill(tail) => Arc::new(tail),
+            Tail::Broadcast(tail) => Arc::new(tail),
+        };
+        Vcat::new(head, tail)
+    })
+}
+
+/// Generated tail of a [`vcat`]; strategies need `Debug` values, which
+/// `Arc<dyn InfiniteArray>` is not
+#[derive(Debug)]
+enum Tail {
+    Fill(Fill),
+    Broadcast(BroadcastArray),
+}
+
+/// Banded operators with bandwidths up to `max_bandwidth`
+///
+/// Diagonal d holds `c_d + s_d·sin(i)` in row i, so the operators are neither
+/// Toeplitz nor symmetric in general.
+pub fn banded_operator(max_bandwidth: usize) -> impl Strategy<Value = InfiniteOperator> {
+    (0..=max_bandwidth, 0..=max_bandwidth)
+        .prop_flat_map(|(lower, upper)| {
+            let diagonals = lower + upper + 1;
+            (Just(lower), Just(upper), vec(complex(), diagonals), vec(value(), diagonals))
+        })
+        .prop_map(|(lower, upper, constants, slopes)| {
+            InfiniteOperator::from_operator(BandedOperator::new(lower, upper, move |i, j| {
+                let d = j + lower - i;
+                constants[d] + slopes[d] * (i as f64).sin()
+            }))
+        })
+}
+
+/// Dense operators whose entries decay like `ρ^|i - j|` away from the diagonal
+///
+/// The rate ρ lies in [0.1, 0.9], so sections converge and the operators are
+/// bounded, as the spectral algorithms expect.
+pub fn decaying_operator() -> impl Strategy<Value = InfiniteOperator> {
+    (complex(), complex(), 0.1..0.9f64).prop_map(|(diagonal, scale, rho)| {
+        InfiniteOperator::new(move |i, j| {
+            let distance = i.abs_diff(j);
+            if distance == 0 {
+                diagonal
+            } else {
+                scale * rho.powi(distance as i32) * ((i + j) as f64).cos()
+            }
+        })
+    })
+}
+
+/// Whether `a` and `b` agree to within `tol`, relative to the larger magnitude
+/// once it exceeds 1; equal infinities and two NaNs agree
+fn close(a: f64, b: f64, tol: f64) -> bool {
+    a == b || (a.is_nan() && b.is_nan()) || (a - b).abs() <= tol * a.abs().max(b.abs()).max(1.0)
+}
+
+/// Fail unless entries 0..n of `a` and `b` agree up to `tol`
+///
+/// The tolerance is absolute for entries of magnitude at most 1 and relative above.
+pub fn arrays_agree(a: &dyn InfiniteArray, b: &dyn InfiniteArray, n: usize, tol: f64) -> Result<(), TestCaseError> {
+    for k in 0..n {
+        let (x, y) = (a.get(k), b.get(k));
+        if !close(x, y, tol) {
+            return Err(TestCaseError::fail(format!("entry {} differs: {} vs {}", k, x, y)));
+        }
+    }
+    Ok(())
+}
+
+/// Fail unless the n×n sections of `a` and `b` agree up to `tol`, as in [`arrays_agree`]
+pub fn operators_agree(a: &dyn LinearOperator, b: &dyn LinearOperator, n: usize, tol: f64) -> Result<(), TestCaseError> {
+    for i in 0..n {
+        for j in 0..n {
+            let (x, y) = (a.get(i, j), b.get(i, j));
+            if !close(x.re, y.re, tol) || !close(x.im, y.im, tol) {
+                return Err(TestCaseError::fail(format!("entry ({}, {}) differs: {} vs {}", i, j, x, y)));
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    proptest! {
+        #[test]
+        fn generated_arrays_agree_with_themselves(a in fill(), b in broadcast(), c in vcat()) {
+            arrays_agree(&a, &a, 32, 0.0)?;
+            arrays_agree(&b, &b, 32, 0.0)?;
+            arrays_agree(&c, &c, 32, 0.0)?;
+        }
+
+        #[test]
+        fn generated_operators_agree_with_themselves(a in banded_operator(2), b in decaying_operator()) {
+            operators_agree(&a, &a, 8, 0.0)?;
+            operators_agree(&b, &b, 8, 0.0)?;
+            let (lower, upper) = a.bandwidths().unwrap();
+            prop_assert!(lower <= 2 && upper <= 2);
+            prop_assert_eq!(a.get(lower + 1, 0), Complex64::new(0.0, 0.0));
+            prop_assert_eq!(a.get(0, upper + 1), Complex64::new(0.0, 0.0));
+        }
+    }
+
+    #[test]
+    fn different_arrays_disagree() {
+        let a = Fill::new(1.0, None);
+        let b = Vcat::new(vec![1.0, 1.0, 2.0], Arc::new(Fill::new(1.0, None)));
+        assert!(arrays_agree(&a, &b, 2, 0.0).is_ok());
+        assert!(arrays_agree(&a, &b, 3, 0.0).is_err());
+        assert!(arrays_agree(&a, &b, 3, 0.5).is_ok());
+        let nan = Fill::new(f64::NAN, None);
+        assert!(arrays_agree(&nan, &nan, 3, 0.0).is_ok());
+    }
+
+    #[test]
+    fn different_operators_disagree() {
+        let a = InfiniteOperator::new(|i, j| Complex64::new((i + j) as f64, 0.0));
+        let b = InfiniteOperator::new(|i, j| Complex64::new((i + j) as f64, if i == 3 { 1e-3 } else { 0.0 }));
+        assert!(operators_agree(&a, &b, 3, 0.0).is_ok());
+        assert!(operators_agree(&a, &b, 4, 1e-4).is_err());
+        assert!(operators_agree(&a, &b, 4, 1e-2).is_ok());
+    }
+}
@@ -0,0 +1,490 @@
+//! Banded Toeplitz operators and their symbols.
+//!
+//! A Toeplitz operator has constant diagonals, T[i, j] = a_{i−j}, and is described by
+//! its symbol a(z) = Σ a_k z^k. For banded Toeplitz operators plus a compact (here
+//! finite-rank) perturbation, the essential spectrum is determined by the symbol
+//! alone: it is the curve a(𝕋) together with the points it winds around. Everything
+//! else in the spectrum is discrete.
+
+use crate::iqr::InfiniteOperator;
+use crate::linalg;
+use crate::operators::LinearOperator;
+use ndarray::Array2;
+use num_complex::Complex64;
+use std::f64::consts::PI;
+use std::sync::Arc;
+
+/// Number of points used to sample the symbol curve
+const SYMBOL_SAMPLES: usize = 1024;
+
+/// Banded Toeplitz operator T[i, j] = a_{i−j}
+#[derive(Debug, Clone)]
+pub struct InfiniteToeplitz {
+    lowest: isize,
+    coefficients: Vec<Complex64>,
+}
+
+impl InfiniteToeplitz {
+    /// Create from the coefficients a_lowest, a_{lowest+1}, ... of the symbol
+    pub fn new(lowest: isize, coefficients: Vec<Complex64>) -> Self {
+        InfiniteToeplitz { lowest, coefficients }
+    }
+    
+    /// Symbol coefficient a_k (zero outside the band)
+    pub fn coefficient(&self, k: isize) -> Complex64 {
+        let idx = k - self.lowest;
+        if idx < 0 {
+            return Complex64::new(0.0, 0.0);
+        }
+        self.coefficients.get(idx as usize).copied().unwrap_or(Complex64::new(0.0, 0.0))
+    }
+    
+    /// Get matrix element at position (i, j)
+    pub fn get(&self, i: usize, j: usize) -> Complex64 {
+        self.coefficient(i as isize - j as isize)
+    }
+    
+    /// Lower and upper bandwidths
+    pub fn bandwidths(&self) -> (usize, usize) {
+        let highest = self.lowest + self.coefficients.len() as isize - 1;
+        (highest.max(0) as usize, (-self.lowest).max(0) as usize)
+    }
+    
+    /// Evaluate the symbol a(z) at z = e^{iθ}
+    pub fn symbol(&self, theta: f64) -> Complex64 {
+        self.coefficients
+            .iter()
+            .enumerate()
+            .map(|(idx, &a)| a * Complex64::from_polar(1.0, (self.lowest + idx as isize) as f64 * theta))
+            .sum()
+    }
+    
+    /// Sample the symbol curve a(𝕋) at equally spaced angles (closed: last == first)
+    pub fn symbol_curve(&self, samples: usize) -> Vec<Complex64> {
+        (0..=samples)
+            .map(|k| self.symbol(2.0 * PI * k as f64 / samples as f64))
+            .collect()
+    }
+    
+    /// Winding number of the symbol curve around λ
+    ///
+    /// Returns `None` when λ lies on the sampled curve, where it is undefined.
+    pub fn winding_number(&self, lambda: Complex64) -> Option<i64> {
+        winding_number(&self.symbol_curve(SYMBOL_SAMPLES), lambda)
+    }
+    
+    /// Classify λ from the symbol alone
+    ///
+    /// λ within `curve_tol` of a(𝕋) is essential spectrum. Elsewhere T − λ is
+    /// Fredholm with index −κ, κ the winding number of a(𝕋) around λ: κ < 0 makes λ an
+    /// eigenvalue with a (−κ)-dimensional eigenspace, κ > 0 puts it in the residual
+    /// spectrum, and κ = 0 in the resolvent set (T(a − λ) is invertible by Coburn's
+    /// lemma).
+    pub fn classify_point(&self, lambda: Complex64, curve_tol: f64) -> ToeplitzSpectralType {
+        let curve = self.symbol_curve(SYMBOL_SAMPLES);
+        classify_against_curve(&curve, lambda, curve_tol)
+    }
+    
+    /// Spectrum of T(a) on the grid `re × im`, from the symbol curve and winding numbers
+    ///
+    /// Unlike eigenvalues of sections, which cluster on curves inside the spectrum and
+    /// miss the filled regions entirely, this is exact up to the curve sampling.
+    pub fn spectrum(&self, re: &[f64], im: &[f64], curve_tol: f64) -> ToeplitzSpectrum {
+        let symbol_curve = self.symbol_curve(SYMBOL_SAMPLES);
+        let regions = Array2::from_shape_fn((re.len(), im.len()), |(a, b)| {
+            classify_against_curve(&symbol_curve, Complex64::new(re[a], im[b]), curve_tol)
+        });
+        ToeplitzSpectrum {
+            symbol_curve,
+            re: re.to_vec(),
+            im: im.to_vec(),
+            regions,
+        }
+    }
+    
+    /// Wiener–Hopf factorization a(z) = a₋(z) z^κ a₊(z) of the symbol
+    ///
+    /// The roots of z^{−lowest} a(z) are split at the unit circle: roots inside go to
+    /// a₋ (analytic and nonzero outside the disk) and roots outside to a₊ (analytic
+    /// and nonzero inside it). κ is the winding number of a(𝕋) around 0, and T(a) is
+    /// Fredholm with index −κ. Returns `None` if a vanishes on the unit circle (a
+    /// root within `1e-10` of it), when no factorization exists.
+    pub fn wiener_hopf(&self) -> Option<WienerHopfFactorization> {
+        let zero = Complex64::new(0.0, 0.0);
+        let first = self.coefficients.iter().position(|c| c.norm() > 0.0)?;
+        let last = self.coefficients.iter().rposition(|c| c.norm() > 0.0)?;
+        let poly = &self.coefficients[first..=last];
+        let lowest = self.lowest + first as isize;
+        let degree = poly.len() - 1;
+        
+        // Roots of the polynomial z^{−lowest} a(z) from its companion matrix
+        let roots = if degree == 0 {
+            Vec::new()
+        } else {
+            let leading = poly[degree];
+            let companion = Array2::from_shape_fn((degree, degree), |(i, j)| {
+                if i == 0 {
+                    -poly[degree - 1 - j] / leading
+                } else if j + 1 == i {
+                    Complex64::new(1.0, 0.0)
+                } else {
+                    zero
+                }
+            });
+            linalg::eigenvalues(&companion)
+        };
+        if roots.iter().any(|r| (r.norm() - 1.0).abs() < 1e-10) {
+            return None;
+        }
+        let (inside, outside): (Vec<Complex64>, Vec<Complex64>) = roots.into_iter().partition(|r| r.norm() < 1.0);
+        
+        // a(z) = c z^lowest Π_in z(1 − r/z) Π_out (−r)(1 − z/r)
+        let constant = outside.iter().fold(poly[degree], |acc, &r| acc * -r);
+        let minus = inside.iter().fold(vec![Complex64::new(1.0, 0.0)], |acc, &r| poly_mul(&acc, &[Complex64::new(1.0, 0.0), -r]));
+        let plus = outside
+            .iter()
+            .fold(vec![constant], |acc, &r| poly_mul(&acc, &[Complex64::new(1.0, 0.0), -1.0 / r]));
+        
+        Some(WienerHopfFactorization {
+            index: lowest as i64 + inside.len() as i64,
+            plus,
+            minus,
+        })
+    }
+    
+    /// Convert to a general (banded) infinite operator
+    pub fn to_operator(&self) -> InfiniteOperator {
+        InfiniteOperator::from_operator(self.clone())
+    }
+}
+
+impl LinearOperator for InfiniteToeplitz {
+    fn get(&self, i: usize, j: usize) -> Complex64 {
+        InfiniteToeplitz::get(self, i, j)
+    }
+    
+    fn bandwidths(&self) -> Option<(usize, usize)> {
+        Some(InfiniteToeplitz::bandwidths(self))
+    }
+    
+    /// a_{−k} = conj(a_k) for every k
+    fn is_hermitian(&self) -> bool {
+        let (lower, upper) = InfiniteToeplitz::bandwidths(self);
+        let reach = lower.max(upper) as isize;
+        (-reach..=reach).all(|k| self.coefficient(-k) == self.coefficient(k).conj())
+    }
+}
+
+/// Spectral classification of a point for a Toeplitz operator
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToeplitzSpectralType {
+    /// T − λ is invertible
+    Resolvent,
+    /// λ lies on the symbol curve
+    Essential,
+    /// T − λ is injective with cokernel of the given dimension
+    Residual { cokernel_dimension: usize },
+    /// λ is an eigenvalue with eigenspace of the given dimension
+    Eigenvalue { kernel_dimension: usize },
+}
+
+impl ToeplitzSpectralType {
+    /// Whether the point belongs to the spectrum
+    pub fn in_spectrum(&self) -> bool {
+        *self != ToeplitzSpectralType::Resolvent
+    }
+}
+
+fn classify_against_curve(curve: &[Complex64], lambda: Complex64, curve_tol: f64) -> ToeplitzSpectralType {
+    if distance_to_curve(curve, lambda) <= curve_tol {
+        return ToeplitzSpectralType::Essential;
+    }
+    match winding_number(curve, lambda) {
+        None => ToeplitzSpectralType::Essential,
+        Some(0) => ToeplitzSpectralType::Resolvent,
+        Some(k) if k > 0 => ToeplitzSpectralType::Residual { cokernel_dimension: k as usize },
+        Some(k) => ToeplitzSpectralType::Eigenvalue { kernel_dimension: (-k) as usize },
+    }
+}
+
+/// Spectrum of a Toeplitz operator: the symbol curve and the regions it winds around
+#[derive(Debug, Clone)]
+pub struct ToeplitzSpectrum {
+    /// Samples of a(𝕋), the essential spectrum
+    pub symbol_curve: Vec<Complex64>,
+    pub re: Vec<f64>,
+    pub im: Vec<f64>,
+    /// `regions[(a, b)]` classifies re[a] + i·im[b]
+    pub regions: Array2<ToeplitzSpectralType>,
+}
+
+impl ToeplitzSpectrum {
+    /// Whether the grid point (a, b) lies in the spectrum
+    pub fn contains(&self, a: usize, b: usize) -> bool {
+        self.regions[(a, b)].in_spectrum()
+    }
+}
+
+/// Largest number of terms kept from the inverse series of the Wiener–Hopf factors
+const MAX_SERIES_TERMS: usize = 1 << 16;
+
+/// Product of two polynomials given by their coefficients
+fn poly_mul(a: &[Complex64], b: &[Complex64]) -> Vec<Complex64> {
+    let mut product = vec![Complex64::new(0.0, 0.0); a.len() + b.len() - 1];
+    for (i, &x) in a.iter().enumerate() {
+        for (j, &y) in b.iter().enumerate() {
+            product[i + j] += x * y;
+        }
+    }
+    product
+}
+
+/// Power series of 1/p for a polynomial p without roots in the closed unit disk
+///
+/// Terms decay geometrically; the series is cut once `degree` consecutive terms are
+/// below `tol` relative to the largest one.
+fn reciprocal_series(p: &[Complex64], tol: f64) -> Vec<Complex64> {
+    let mut series = vec![Complex64::new(1.0, 0.0) / p[0]];
+    let mut largest = series[0].norm();
+    let mut small = 0;
+    while series.len() < MAX_SERIES_TERMS && small < p.len().max(2) - 1 {
+        let k = series.len();
+        let sum: Complex64 = (1..p.len().min(k + 1)).map(|m| p[m] * series[k - m]).sum();
+        let term = -sum / p[0];
+        largest = largest.max(term.norm());
+        small = if term.norm() <= tol * largest { small + 1 } else { 0 };
+        series.push(term);
+    }
+    series
+}
+
+/// Wiener–Hopf factorization a(z) = a₋(z) z^κ a₊(z) of a Toeplitz symbol
+#[derive(Debug, Clone)]
+pub struct WienerHopfFactorization {
+    /// κ, the winding number of the symbol around 0
+    pub index: i64,
+    /// Coefficients of a₊(z) = Σ plus[k] z^k
+    pub plus: Vec<Complex64>,
+    /// Coefficients of a₋(z) = Σ minus[k] z^{−k}, normalized to minus[0] = 1
+    pub minus: Vec<Complex64>,
+}
+
+impl WienerHopfFactorization {
+    /// Evaluate a₋(z) z^κ a₊(z) at z = e^{iθ}
+    pub fn symbol(&self, theta: f64) -> Complex64 {
+        let z = Complex64::from_polar(1.0, theta);
+        let plus: Complex64 = self.plus.iter().rev().fold(Complex64::new(0.0, 0.0), |acc, &c| acc * z + c);
+        let minus: Complex64 = self.minus.iter().rev().fold(Complex64::new(0.0, 0.0), |acc, &c| acc / z + c);
+        minus * z.powi(self.index as i32) * plus
+    }
+    
+    /// T(a)⁻¹ = T(a₊⁻¹) T(a₋⁻¹), available when the index is zero
+    ///
+    /// The inverse series of the factors are truncated at `tol`, which makes the
+    /// returned operator banded.
+    pub fn toeplitz_inverse(&self, tol: f64) -> Option<InfiniteOperator> {
+        if self.index != 0 {
+            return None;
+        }
+        let b = Arc::new(reciprocal_series(&self.plus, tol));
+        let c = Arc::new(reciprocal_series(&self.minus, tol));
+        let (lower, upper) = (b.len() - 1, c.len() - 1);
+        Some(
+            InfiniteOperator::new(move |i, j| {
+                let first = (i + 1).saturating_sub(b.len()).max((j + 1).saturating_sub(c.len()));
+                (first..=i.min(j)).map(|k| b[i - k] * c[j - k]).sum()
+            })
+            .with_bandwidths(lower, upper),
+        )
+    }
+}
+
+/// Winding number of a closed polyline around λ
+fn winding_number(curve: &[Complex64], lambda: Complex64) -> Option<i64> {
+    let mut total = 0.0;
+    for pair in curve.windows(2) {
+        let (a, b) = (pair[0] - lambda, pair[1] - lambda);
+        if a.norm() == 0.0 || b.norm() == 0.0 {
+            return None;
+        }
+        total += (b / a).arg();
+    }
+    Some((total / (2.0 * PI)).round() as i64)
+}
+
+/// Distance from λ to a polyline
+fn distance_to_curve(curve: &[Complex64], lambda: Complex64) -> f64 {
+    curve
+        .windows(2)
+        .map(|pair| {
+            let (a, b) = (pair[0], pair[1]);
+            let d = b - a;
+            let len = d.norm_sqr();
+            let t = if len == 0.0 {
+                0.0
+            } else {
+                (((lambda - a) * d.conj()).re / len).clamp(0.0, 1.0)
+            };
+            (lambda - (a + d * t)).norm()
+        })
+        .fold(f64::INFINITY, f64::min)
+}
+
+/// Whether an eigenvalue belongs to the essential or the discrete spectrum
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpectrumKind {
+    /// On the symbol curve or inside a region it winds around
+    Essential,
+    /// An isolated eigenvalue created by the perturbation
+    Discrete,
+}
+
+/// Eigenvalues of a perturbed Toeplitz operator separated by kind
+#[derive(Debug, Clone)]
+pub struct SeparatedSpectrum {
+    pub eigenvalues: Vec<Complex64>,
+    pub kinds: Vec<SpectrumKind>,
+    /// Samples of the symbol curve a(𝕋), the boundary of the essential spectrum
+    pub symbol_curve: Vec<Complex64>,
+}
+
+impl SeparatedSpectrum {
+    /// The eigenvalues labeled discrete
+    pub fn discrete(&self) -> Vec<Complex64> {
+        self.eigenvalues
+            .iter()
+            .zip(&self.kinds)
+            .filter(|(_, &kind)| kind == SpectrumKind::Discrete)
+            .map(|(&lambda, _)| lambda)
+            .collect()
+    }
+}
+
+/// Separate essential and discrete spectrum of T(a) + K for a finite-rank K
+///
+/// `perturbation` is added to the leading block of the Toeplitz operator. The
+/// eigenvalues of the n×n section are labeled essential when they lie within
+/// `curve_tol` of the symbol curve or have nonzero winding number, and discrete
+/// otherwise.
+pub fn separate_spectrum(
+    base: &InfiniteToeplitz,
+    perturbation: &Array2<Complex64>,
+    n: usize,
+    curve_tol: f64,
+) -> SeparatedSpectrum {
+    let mut section = Array2::from_shape_fn((n, n), |(i, j)| base.get(i, j));
+    for ((i, j), &value) in perturbation.indexed_iter() {
+        if i < n && j < n {
+            section[(i, j)] += value;
+        }
+    }
+    
+    let mut eigenvalues = linalg::eigenvalues(&section);
+    linalg::sort_by_magnitude(&mut eigenvalues);
+    let symbol_curve = base.symbol_curve(SYMBOL_SAMPLES);
+    let kinds = eigenvalues
+        .iter()
+        .map(|&lambda| {
+            let on_curve = distance_to_curve(&symbol_curve, lambda) <= curve_tol;
+            match winding_number(&symbol_curve, lambda) {
+                Some(0) if !on_curve => SpectrumKind::Discrete,
+                _ => SpectrumKind::Essential,
+            }
+        })
+        .collect();
+    
+    SeparatedSpectrum {
+        eigenvalues,
+        kinds,
+        symbol_curve,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::real;
+
+    /// Forward shift S, T[i + 1, i] = 1, with symbol a(z) = z
+    fn shift() -> InfiniteToeplitz {
+        InfiniteToeplitz::new(1, vec![real(1.0)])
+    }
+
+    #[test]
+    fn winding_number_of_the_shift_symbol() {
+        let s = shift();
+        assert_eq!(s.get(1, 0), real(1.0));
+        assert_eq!(s.winding_number(real(0.0)), Some(1));
+        assert_eq!(s.winding_number(real(2.0)), Some(0));
+        assert_eq!(s.winding_number(Complex64::new(0.3, -0.4)), Some(1));
+        // 1 = a(e^{i0}) is a sample of the curve
+        assert_eq!(s.winding_number(real(1.0)), None);
+    }
+
+    #[test]
+    fn rank_one_perturbation_creates_one_discrete_eigenvalue() {
+        // a(z) = z + 1/z has essential spectrum [−2, 2]; adding 2 in the corner creates
+        // the eigenvalue 2 + 1/2
+        let base = InfiniteToeplitz::new(-1, vec![real(1.0), real(0.0), real(1.0)]);
+        let mut perturbation = Array2::zeros((1, 1));
+        perturbation[(0, 0)] = real(2.0);
+        let separated = separate_spectrum(&base, &perturbation, 64, 1e-8);
+        let discrete = separated.discrete();
+        assert_eq!(discrete.len(), 1);
+        assert!((discrete[0] - 2.5).norm() < 1e-10);
+        assert_eq!(separated.eigenvalues.len(), 64);
+        assert_eq!(separated.kinds.iter().filter(|&&kind| kind == SpectrumKind::Essential).count(), 63);
+    }
+
+    /// a(z) = (1 − z/3)(1 − 1/(2z)) = −1/(2z) + 7/6 − z/3, with index 0
+    fn invertible() -> InfiniteToeplitz {
+        InfiniteToeplitz::new(-1, vec![real(-0.5), real(7.0 / 6.0), real(-1.0 / 3.0)])
+    }
+
+    #[test]
+    fn wiener_hopf_factors_reproduce_the_symbol() {
+        let t = invertible();
+        let factorization = t.wiener_hopf().unwrap();
+        assert_eq!(factorization.index, 0);
+        assert_eq!(factorization.minus[0], real(1.0));
+        // a₋(z) = 1 − 1/(2z) and a₊(z) = 1 − z/3
+        assert!((factorization.minus[1] + 0.5).norm() < 1e-12);
+        assert!((factorization.plus[1] / factorization.plus[0] + 1.0 / 3.0).norm() < 1e-12);
+        for k in 0..16 {
+            let theta = 0.4 * k as f64;
+            assert!((factorization.symbol(theta) - t.symbol(theta)).norm() < 1e-12);
+        }
+        assert_eq!(shift().wiener_hopf().unwrap().index, 1);
+        // z + 1/z vanishes at ±i on the unit circle
+        assert!(InfiniteToeplitz::new(-1, vec![real(1.0), real(0.0), real(1.0)]).wiener_hopf().is_none());
+    }
+
+    #[test]
+    fn toeplitz_inverse_matches_the_inverse_of_a_large_section() {
+        let t = invertible();
+        let inverse = t.wiener_hopf().unwrap().toeplitz_inverse(1e-14).unwrap();
+        let section = linalg::to_dmatrix(&t.to_operator().get_truncation(200)).try_inverse().unwrap();
+        for i in 0..8 {
+            for j in 0..8 {
+                assert!((inverse.get(i, j) - section[(i, j)]).norm() < 1e-10);
+            }
+        }
+        assert!(shift().wiener_hopf().unwrap().toeplitz_inverse(1e-14).is_none());
+    }
+
+    #[test]
+    fn classify_point_from_the_winding_number() {
+        // S has index −1: λ inside the unit circle is residual spectrum
+        let s = shift();
+        assert_eq!(s.classify_point(real(0.0), 1e-8), ToeplitzSpectralType::Residual { cokernel_dimension: 1 });
+        assert_eq!(s.classify_point(real(2.0), 1e-8), ToeplitzSpectralType::Resolvent);
+        assert_eq!(s.classify_point(Complex64::new(0.0, 1.0), 1e-8), ToeplitzSpectralType::Essential);
+        // S* kills e₀, so λ inside the circle is an eigenvalue
+        let adjoint = InfiniteToeplitz::new(-1, vec![real(1.0)]);
+        assert_eq!(adjoint.classify_point(real(0.5), 1e-8), ToeplitzSpectralType::Eigenvalue { kernel_dimension: 1 });
+
+        let spectrum = s.spectrum(&[0.0, 0.5, 2.0], &[0.0], 1e-8);
+        assert!(spectrum.contains(0, 0) && spectrum.contains(1, 0));
+        assert!(!spectrum.contains(2, 0));
+    }
+}
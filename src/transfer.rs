@@ -0,0 +1,170 @@
+//! Transfer matrices of tridiagonal operators.
+//!
+//! For a tridiagonal operator with subdiagonal cₙ = A[n, n−1], diagonal aₙ and
+//! superdiagonal bₙ = A[n, n+1], a solution of A u = E u satisfies
+//! (uₙ₊₁, uₙ) = Tₙ(E) (uₙ, uₙ₋₁) with Tₙ(E) = [[(E − aₙ)/bₙ, −cₙ/bₙ], [1, 0]].
+//! Growth of the products Φₙ = Tₙ₋₁⋯T₀ detects spectrum: the Lyapunov exponent
+//! γ(E) = lim (1/n) log‖Φₙ(E)‖ vanishes on the absolutely continuous spectrum of
+//! ergodic operators (Ishii–Pastur–Kotani), and for periodic operators the spectrum
+//! is where the discriminant tr Φₚ(E) lies in [−2, 2].
+//!
+//! Only the three central diagonals of the operator are read.
+
+use crate::iqr::InfiniteOperator;
+use nalgebra::{Matrix2, Vector2};
+use num_complex::Complex64;
+
+/// Transfer matrix Tₙ(E)
+///
+/// Panics if the superdiagonal entry bₙ vanishes, where the recursion breaks down.
+pub fn transfer_matrix(operator: &InfiniteOperator, n: usize, energy: Complex64) -> Matrix2<Complex64> {
+    let b = operator.get(n, n + 1);
+    assert!(b.norm() > 0.0, "superdiagonal entry {} vanishes", n);
+    let c = if n == 0 { Complex64::new(0.0, 0.0) } else { operator.get(n, n - 1) };
+    let a = operator.get(n, n);
+    Matrix2::new((energy - a) / b, -c / b, Complex64::new(1.0, 0.0), Complex64::new(0.0, 0.0))
+}
+
+/// Product Φ = T_{start+len−1}(E) ⋯ T_start(E)
+pub fn transfer_product(operator: &InfiniteOperator, start: usize, len: usize, energy: Complex64) -> Matrix2<Complex64> {
+    (start..start + len).fold(Matrix2::identity(), |acc, n| transfer_matrix(operator, n, energy) * acc)
+}
+
+/// Finite-n Lyapunov exponent (1/n) log‖Φₙ(E)(1, 0)ᵀ‖
+///
+/// The vector (u₀, u₋₁) = (1, 0) is the solution satisfying the boundary condition
+/// at the origin; it is renormalized at every step so that large n cannot overflow.
+/// The estimate carries an O(1/n) bias, so zero exponents show up as O(log n / n).
+pub fn lyapunov_exponent(operator: &InfiniteOperator, energy: Complex64, n: usize) -> f64 {
+    let mut v = Vector2::new(Complex64::new(1.0, 0.0), Complex64::new(0.0, 0.0));
+    let mut log_growth = 0.0;
+    for k in 0..n {
+        v = transfer_matrix(operator, k, energy) * v;
+        let norm = v.norm();
+        log_growth += norm.ln();
+        v /= Complex64::new(norm, 0.0);
+    }
+    log_growth / n.max(1) as f64
+}
+
+/// Lyapunov exponents over a range of real energies
+#[derive(Debug, Clone)]
+pub struct LyapunovScan {
+    pub energies: Vec<f64>,
+    pub exponents: Vec<f64>,
+    /// Whether each exponent is below the zero threshold
+    pub zero_exponent: Vec<bool>,
+}
+
+impl LyapunovScan {
+    /// Maximal runs of consecutive energies with zero exponent, as [start, end]
+    ///
+    /// For ergodic (random or quasi-periodic) operators these approximate the
+    /// absolutely continuous spectrum.
+    pub fn zero_intervals(&self) -> Vec<(f64, f64)> {
+        intervals(&self.energies, &self.zero_exponent)
+    }
+}
+
+/// Compute finite-n Lyapunov exponents at the given energies
+///
+/// Exponents below `zero_tol` are flagged as zero; a threshold a few times
+/// log(n)/n separates them from the positive exponents of localized states.
+pub fn lyapunov_scan(operator: &InfiniteOperator, energies: &[f64], n: usize, zero_tol: f64) -> LyapunovScan {
+    let exponents: Vec<f64> = energies
+        .iter()
+        .map(|&e| lyapunov_exponent(operator, Complex64::new(e, 0.0), n))
+        .collect();
+    let zero_exponent = exponents.iter().map(|&g| g <= zero_tol).collect();
+    LyapunovScan {
+        energies: energies.to_vec(),
+        exponents,
+        zero_exponent,
+    }
+}
+
+/// Spectral bands of a self-adjoint periodic tridiagonal operator
+///
+/// Samples the discriminant Δ(E) = tr Φₚ(E) over `samples` energies in
+/// [e_min, e_max] and returns the intervals where |Δ| ≤ 2, which make up the
+/// (purely absolutely continuous) spectrum of the periodic operator on ℤ; the
+/// operator on ℕ adds at most finitely many eigenvalues per gap.
+pub fn periodic_bands(operator: &InfiniteOperator, period: usize, e_min: f64, e_max: f64, samples: usize) -> Vec<(f64, f64)> {
+    let samples = samples.max(2);
+    let energies: Vec<f64> = (0..samples)
+        .map(|k| e_min + (e_max - e_min) * k as f64 / (samples - 1) as f64)
+        .collect();
+    // Start one period in, so that the boundary term c₀ = 0 does not enter
+    let inside: Vec<bool> = energies
+        .iter()
+        .map(|&e| transfer_product(operator, period, period, Complex64::new(e, 0.0)).trace().norm() <= 2.0)
+        .collect();
+    intervals(&energies, &inside)
+}
+
+/// Maximal runs of flagged consecutive points
+fn intervals(points: &[f64], flags: &[bool]) -> Vec<(f64, f64)> {
+    let mut runs = Vec::new();
+    let mut start: Option<f64> = None;
+    for (i, (&x, &flag)) in points.iter().zip(flags).enumerate() {
+        match (flag, start) {
+            (true, None) => start = Some(x),
+            (false, Some(s)) => {
+                runs.push((s, points[i - 1]));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let (Some(s), Some(&last)) = (start, points.last()) {
+        runs.push((s, last));
+    }
+    runs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::real;
+
+    /// The free Laplacian tridiag(1, 0, 1), with spectrum [−2, 2]
+    fn free() -> InfiniteOperator {
+        crate::banded_operator! { -1 => |_| 1.0, 0 => |_| 0.0, 1 => |_| 1.0 }
+    }
+
+    #[test]
+    fn transfer_products_give_chebyshev_polynomials() {
+        // u₀ = 1, u₁ = E, u₂ = E² − 1, u₃ = E³ − 2E
+        let product = transfer_product(&free(), 0, 3, real(1.0));
+        assert_eq!(product * Vector2::new(real(1.0), real(0.0)), Vector2::new(real(-1.0), real(0.0)));
+        assert_eq!(transfer_matrix(&free(), 2, real(0.5))[(0, 0)], real(0.5));
+    }
+
+    #[test]
+    fn lyapunov_exponent_vanishes_on_the_spectrum() {
+        assert!(lyapunov_exponent(&free(), real(0.0), 2000).abs() < 1e-12);
+        // Outside, γ(E) = arcosh(E/2)
+        let gamma = lyapunov_exponent(&free(), real(3.0), 2000);
+        assert!((gamma - 1.5f64.acosh()).abs() < 1e-2);
+
+        let energies: Vec<f64> = (0..=24).map(|k| -3.0 + 0.25 * k as f64).collect();
+        let scan = lyapunov_scan(&free(), &energies, 2000, 0.05);
+        assert_eq!(scan.zero_intervals(), [(-2.0, 2.0)]);
+    }
+
+    #[test]
+    fn periodic_bands_of_an_alternating_potential() {
+        // Diagonal ±1 and unit hopping: E² = 1 + |1 + e^{ik}|², bands ±[1, √5]
+        let operator = crate::banded_operator! {
+            -1 => |_| 1.0,
+            0 => |i| if i % 2 == 0 { 1.0 } else { -1.0 },
+            1 => |_| 1.0,
+        };
+        let bands = periodic_bands(&operator, 2, -3.0, 3.0, 6001);
+        assert_eq!(bands.len(), 2);
+        let expected = [(-5f64.sqrt(), -1.0), (1.0, 5f64.sqrt())];
+        for ((a, b), (c, d)) in bands.iter().zip(expected) {
+            assert!((a - c).abs() < 2e-3 && (b - d).abs() < 2e-3);
+        }
+    }
+}
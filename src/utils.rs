@@ -8,7 +8,8 @@ pub fn get_infinity() -> Infinity {
 }
 
 /// Check if a value is infinity
+#[deprecated(note = "compares types rather than values; use `MaybeInfinite::is_infinite`")]
 pub fn is_infinity<T: 'static>(_value: &T) -> bool {
-    std::any::TypeId::of::<Infinity>() == std::any::TypeId::of::<T>()
+    core::any::TypeId::of::<Infinity>() == core::any::TypeId::of::<T>()
 }
 